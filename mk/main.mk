@@ -264,10 +264,10 @@ endif
 
 # FIXME: x86-ism
 LLVM_COMPONENTS=x86 arm aarch64 mips ipo bitreader bitwriter linker asmparser mcjit \
-                interpreter instrumentation
+                interpreter instrumentation profiledata
 
 # Only build these LLVM tools
-LLVM_TOOLS=bugpoint llc llvm-ar llvm-as llvm-dis llvm-mc opt llvm-extract
+LLVM_TOOLS=bugpoint llc llvm-ar llvm-as llvm-dis llvm-mc opt llvm-extract llvm-profdata
 
 define DEF_LLVM_VARS
 # The configure script defines these variables with the target triples
@@ -385,7 +385,11 @@ endif
 TSREQ$(1)_T_$(2)_H_$(3) = \
 	$$(HSREQ$(1)_H_$(3)) \
 	$$(TLIB$(1)_T_$(2)_H_$(3))/libmorestack.a \
-	$$(TLIB$(1)_T_$(2)_H_$(3))/libcompiler-rt.a
+	$$(TLIB$(1)_T_$(2)_H_$(3))/libcompiler-rt.a \
+	$$(if $$(filter $(2),$$(PROFILER_TARGETS)), \
+	  $$(TLIB$(1)_T_$(2)_H_$(3))/libprofiler-rt.a) \
+	$$(if $$(findstring x86_64-unknown-linux-gnu,$(2)), \
+	  $$(foreach san,$$(SANITIZERS),$$(TLIB$(1)_T_$(2)_H_$(3))/lib$$(san)-rt.a))
 
 # Prerequisites for a working stageN compiler and libraries, for a specific
 # target
@@ -119,6 +119,13 @@ $(foreach lib,$(NATIVE_LIBS), \
   $(eval $(call THIRD_PARTY_LIB,$(target),$(lib)))))
 
 
+# compiler-rt's profiling runtime needs a POSIX C library, so it's only built
+# for these targets. The compiler is built knowing them, and refuses
+# `-C profile-generate` and `-Z coverage` for any other.
+PROFILER_TARGETS := x86_64-unknown-linux-gnu i686-unknown-linux-gnu \
+                    x86_64-apple-darwin i686-apple-darwin
+export CFG_PROFILER_TARGETS := $(PROFILER_TARGETS)
+
 ################################################################################
 # Building third-party targets with external build systems
 #
@@ -234,6 +241,71 @@ $$(COMPRT_LIB_$(1)): $$(COMPRT_DEPS) $$(MKFILE_DEPS)
 		triple-builtins
 	$$(Q)cp $$(COMPRT_BUILD_DIR_$(1))/triple/builtins/libcompiler_rt.a $$(COMPRT_LIB_$(1))
 
+################################################################################
+# compiler-rt's profiling runtime
+#
+# Programs built with `-C profile-generate` link against this; it writes the
+# counters of the instrumented code out to a .profraw file on exit. Only built
+# for the targets in PROFILER_TARGETS.
+################################################################################
+
+ifneq ($(filter $(1),$(PROFILER_TARGETS)),)
+
+PROFRT_SRC_DIR := $(S)src/compiler-rt/lib/profile
+PROFRT_SRCS := $(wildcard $(PROFRT_SRC_DIR)/*.c $(PROFRT_SRC_DIR)/*.cc)
+
+PROFRT_NAME_$(1) := $$(call CFG_STATIC_LIB_NAME_$(1),profiler-rt)
+PROFRT_LIB_$(1) := $$(RT_OUTPUT_DIR_$(1))/$$(PROFRT_NAME_$(1))
+PROFRT_BUILD_DIR_$(1) := $$(RT_OUTPUT_DIR_$(1))/profiler-rt
+PROFRT_OBJS_$(1) := $$(PROFRT_SRCS:$$(PROFRT_SRC_DIR)/%=$$(PROFRT_BUILD_DIR_$(1))/%)
+PROFRT_OBJS_$(1) := $$(PROFRT_OBJS_$(1):.cc=.o)
+PROFRT_OBJS_$(1) := $$(PROFRT_OBJS_$(1):.c=.o)
+
+$$(PROFRT_BUILD_DIR_$(1))/%.o: $$(PROFRT_SRC_DIR)/%.c $$(MKFILE_DEPS)
+	@mkdir -p $$(@D)
+	@$$(call E, compile: $$@)
+	$$(Q)$$(call CFG_COMPILE_C_$(1), $$@, $$(RUNTIME_CFLAGS_$(1))) $$<
+
+# The part that registers the runtime with the program is C++.
+$$(PROFRT_BUILD_DIR_$(1))/%.o: $$(PROFRT_SRC_DIR)/%.cc $$(MKFILE_DEPS)
+	@mkdir -p $$(@D)
+	@$$(call E, compile: $$@)
+	$$(Q)$$(call CFG_COMPILE_CXX_$(1), $$@, $$(RUNTIME_CFLAGS_$(1))) $$<
+
+$$(PROFRT_LIB_$(1)): $$(PROFRT_OBJS_$(1))
+	@$$(call E, link: $$@)
+	$$(Q)$$(AR_$(1)) rcs $$@ $$^
+endif
+
+################################################################################
+# compiler-rt's sanitizer runtimes
+#
+# Programs built with `-Z sanitizer` link against one of these. They are only
+# supported on x86_64 Linux, and are built by compiler-rt's own clang_linux
+# configurations.
+################################################################################
+
+SANITIZERS := asan tsan msan lsan
+
+ifeq ($(1),x86_64-unknown-linux-gnu)
+SANRT_BUILD_DIR_$(1) := $$(RT_OUTPUT_DIR_$(1))/sanitizers
+
+$$(SANRT_BUILD_DIR_$(1))/stamp: $$(COMPRT_DEPS) $$(MKFILE_DEPS)
+	@$$(call E, make: compiler-rt sanitizers)
+	$$(Q)$$(MAKE) -C "$(S)src/compiler-rt" \
+		ProjSrcRoot="$(S)src/compiler-rt" \
+		ProjObjRoot="$$(abspath $$(SANRT_BUILD_DIR_$(1)))" \
+		CC="$$(CC_$(1))" \
+		AR="$$(AR_$(1))" \
+		RANLIB="$$(AR_$(1)) s" \
+		clang_linux
+	$$(Q)touch $$@
+
+$$(foreach san,$$(SANITIZERS),$$(RT_OUTPUT_DIR_$(1))/lib$$(san)-rt.a): \
+	    $$(RT_OUTPUT_DIR_$(1))/lib%-rt.a: $$(SANRT_BUILD_DIR_$(1))/stamp
+	$$(Q)cp $$(SANRT_BUILD_DIR_$(1))/clang_linux/$$*-x86_64/libcompiler_rt.a $$@
+endif
+
 ################################################################################
 # libbacktrace
 #
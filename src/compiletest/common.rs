@@ -12,6 +12,7 @@ pub use self::Mode::*;
 use std::fmt;
 use std::str::FromStr;
 use regex::Regex;
+use test::OutputFormat;
 
 #[derive(Clone, PartialEq)]
 pub enum Mode {
@@ -22,7 +23,8 @@ pub enum Mode {
     Pretty,
     DebugInfoGdb,
     DebugInfoLldb,
-    Codegen
+    Codegen,
+    Ui
 }
 
 impl Copy for Mode {}
@@ -38,6 +40,7 @@ impl FromStr for Mode {
           "debuginfo-lldb" => Some(DebugInfoLldb),
           "debuginfo-gdb" => Some(DebugInfoGdb),
           "codegen" => Some(Codegen),
+          "ui" => Some(Ui),
           _ => None,
         }
     }
@@ -54,6 +57,7 @@ impl fmt::String for Mode {
             DebugInfoGdb => "debuginfo-gdb",
             DebugInfoLldb => "debuginfo-lldb",
             Codegen => "codegen",
+            Ui => "ui",
         }, f)
     }
 }
@@ -115,6 +119,9 @@ pub struct Config {
     // Write out a parseable log of tests that were run
     pub logfile: Option<Path>,
 
+    // How to report the results of the tests
+    pub format: OutputFormat,
+
     // Write out a json file containing any metrics of the run
     pub save_metrics: Option<Path>,
 
@@ -169,6 +176,9 @@ pub struct Config {
     // the path containing LLDB's Python module
     pub lldb_python_dir: Option<String>,
 
+    // Overwrite the expected output of ui tests with the actual output
+    pub bless: bool,
+
     // Explain what's going on
     pub verbose: bool
 }
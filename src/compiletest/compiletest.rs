@@ -75,7 +75,9 @@ pub fn parse_config(args: Vec<String> ) -> Config {
           optopt("", "host-rustcflags", "flags to pass to rustc for host", "FLAGS"),
           optopt("", "target-rustcflags", "flags to pass to rustc for target", "FLAGS"),
           optflag("", "verbose", "run tests verbosely, showing all output"),
+          optflag("", "bless", "overwrite the expected output of ui tests with the actual output"),
           optopt("", "logfile", "file to log test execution to", "FILE"),
+          optopt("", "format", "how to report test results", "pretty|json|junit"),
           optopt("", "save-metrics", "file to save metrics to", "FILE"),
           optopt("", "ratchet-metrics", "file to ratchet metrics against", "FILE"),
           optopt("", "ratchet-noise-percent",
@@ -151,6 +153,9 @@ pub fn parse_config(args: Vec<String> ) -> Config {
         filter: filter,
         cfail_regex: Regex::new(errors::EXPECTED_PATTERN).unwrap(),
         logfile: matches.opt_str("logfile").map(|s| Path::new(s)),
+        format: matches.opt_str("format")
+                       .map(|s| s.parse::<test::OutputFormat>().expect("invalid format"))
+                       .unwrap_or(test::PrettyFormat),
         save_metrics: matches.opt_str("save-metrics").map(|s| Path::new(s)),
         ratchet_metrics:
             matches.opt_str("ratchet-metrics").map(|s| Path::new(s)),
@@ -176,6 +181,7 @@ pub fn parse_config(args: Vec<String> ) -> Config {
             !opt_str2(matches.opt_str("adb-test-dir")).is_empty(),
         lldb_python_dir: matches.opt_str("lldb-python-dir"),
         test_shard: test::opt_shard(matches.opt_str("test-shard")),
+        bless: matches.opt_present("bless"),
         verbose: matches.opt_present("verbose"),
     }
 }
@@ -191,6 +197,7 @@ pub fn log_config(config: &Config) {
     logv(c, format!("stage_id: {}", config.stage_id));
     logv(c, format!("mode: {}", config.mode));
     logv(c, format!("run_ignored: {}", config.run_ignored));
+    logv(c, format!("format: {:?}", config.format));
     logv(c, format!("filter: {}",
                     opt_str(&config.filter
                                    .as_ref()
@@ -213,6 +220,7 @@ pub fn log_config(config: &Config) {
         None => logv(c, "test_shard: (all)".to_string()),
         Some((a,b)) => logv(c, format!("test_shard: {}.{}", a, b))
     }
+    logv(c, format!("bless: {}", config.bless));
     logv(c, format!("verbose: {}", config.verbose));
     logv(c, format!("\n"));
 }
@@ -275,10 +283,10 @@ pub fn run_tests(config: &Config) {
 
 pub fn test_opts(config: &Config) -> test::TestOpts {
     test::TestOpts {
-        filter: match config.filter {
-            None => None,
-            Some(ref filter) => Some(filter.clone()),
-        },
+        list: false,
+        filters: config.filter.iter().map(|re| re.to_string()).collect(),
+        filter_exact: false,
+        skip: Vec::new(),
         run_ignored: config.run_ignored,
         logfile: config.logfile.clone(),
         run_tests: true,
@@ -289,9 +297,18 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
         test_shard: config.test_shard.clone(),
         nocapture: false,
         color: test::AutoColor,
+        format: config.format,
         show_boxplot: false,
         boxplot_width: 50,
         show_all_stats: false,
+        test_timeout: None,
+        report_slowest: None,
+        shuffle: false,
+        shuffle_seed: None,
+        save_baseline: None,
+        baseline: None,
+        baseline_dir: None,
+        baseline_report: None,
     }
 }
 
@@ -351,6 +368,7 @@ pub fn make_test<F>(config: &Config, testfile: &Path, f: F) -> test::TestDescAnd
             name: make_test_name(config, testfile),
             ignore: header::is_test_ignored(config, testfile),
             should_fail: test::ShouldFail::No,
+            timeout: None,
         },
         testfn: f(),
     }
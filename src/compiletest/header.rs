@@ -207,6 +207,12 @@ pub fn is_test_ignored(config: &Config, testfile: &Path) -> bool {
         }
     }
 
+    // The sanitizer runtimes are only built for x86_64 Linux.
+    fn ignore_sanitizer(config: &Config, line: &str) -> bool {
+        config.target.as_slice() != "x86_64-unknown-linux-gnu" &&
+            parse_name_directive(line, "needs-sanitizer-support")
+    }
+
     let val = iter_header(testfile, |ln| {
         !parse_name_directive(ln, "ignore-test") &&
         !parse_name_directive(ln, ignore_target(config).as_slice()) &&
@@ -214,7 +220,8 @@ pub fn is_test_ignored(config: &Config, testfile: &Path) -> bool {
         !(config.mode == common::Pretty && parse_name_directive(ln, "ignore-pretty")) &&
         !(config.target != config.host && parse_name_directive(ln, "ignore-cross-compile")) &&
         !ignore_gdb(config, ln) &&
-        !ignore_lldb(config, ln)
+        !ignore_lldb(config, ln) &&
+        !ignore_sanitizer(config, ln)
     });
 
     !val
@@ -12,7 +12,7 @@ use self::TargetLocation::*;
 
 use common::Config;
 use common::{CompileFail, Pretty, RunFail, RunPass, RunPassValgrind, DebugInfoGdb};
-use common::{Codegen, DebugInfoLldb};
+use common::{Codegen, DebugInfoLldb, Ui};
 use errors;
 use header::TestProps;
 use header;
@@ -73,6 +73,7 @@ pub fn run_metrics(config: Config, testfile: String, mm: &mut MetricMap) {
       DebugInfoGdb => run_debuginfo_gdb_test(&config, &props, &testfile),
       DebugInfoLldb => run_debuginfo_lldb_test(&config, &props, &testfile),
       Codegen => run_codegen_test(&config, &props, &testfile, mm),
+      Ui => run_ui_test(&config, &props, &testfile),
     }
 }
 
@@ -112,6 +113,88 @@ fn run_cfail_test(config: &Config, props: &TestProps, testfile: &Path) {
     check_forbid_output(props, output_to_check.as_slice(), &proc_res);
 }
 
+fn run_ui_test(config: &Config, props: &TestProps, testfile: &Path) {
+    let proc_res = compile_test(config, props, testfile);
+    check_no_compiler_crash(&proc_res);
+
+    let expected_file = testfile.with_extension("stderr");
+    let actual = normalize_output(testfile, proc_res.stderr.as_slice());
+
+    if config.bless {
+        // A test without any output doesn't need an (empty) expected file.
+        if actual.is_empty() {
+            if expected_file.exists() {
+                fs::unlink(&expected_file).unwrap();
+            }
+        } else {
+            File::create(&expected_file).write_str(actual.as_slice()).unwrap();
+        }
+        return;
+    }
+
+    let expected = if expected_file.exists() {
+        let contents = File::open(&expected_file).read_to_string().unwrap();
+        contents.replace("\r\n", "\n")
+    } else {
+        String::new()
+    };
+    if expected == actual {
+        return;
+    }
+
+    let first_mismatch = expected.lines()
+                                 .zip(actual.lines())
+                                 .take_while(|&(e, a)| e == a)
+                                 .count() + 1;
+    print!("\n\
+error: compiler output differs from {} (first difference on line {})\n\
+expected:\n\
+------------------------------------------\n\
+{}\n\
+------------------------------------------\n\
+actual:\n\
+------------------------------------------\n\
+{}\n\
+------------------------------------------\n\
+If the new output is correct, rerun the test with --bless to update {}.\n\
+\n",
+           expected_file.display(), first_mismatch, expected, actual,
+           expected_file.display());
+    panic!();
+}
+
+/// Makes the output of the compiler independent of the machine the test is
+/// run on: the directory containing the test is replaced with `$DIR/` and
+/// Windows line endings with Unix ones.
+fn normalize_output(testfile: &Path, output: &str) -> String {
+    let dir = testfile.dir_path();
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    let dir = dir.as_str().unwrap();
+    let output = output.replace("\r\n", "\n");
+    if dir == "." {
+        return output;
+    }
+
+    // The markers below a source line are indented past the name of the
+    // file, so they have to move along with it.
+    let (old_prefix, new_prefix) = (dir.len() + 1, "$DIR/".len());
+    let mut normalized = String::new();
+    for line in output.lines() {
+        let line = line.replace(dir, "$DIR").replace("$DIR\\", "$DIR/");
+        let indent = line.len() - line.trim_left_matches(' ').len();
+        let rest = &line[indent..];
+        let marker = rest.trim_left();
+        if indent >= old_prefix && (marker.starts_with("^") || marker.starts_with("...")) {
+            normalized.extend(repeat(' ').take(indent - old_prefix + new_prefix));
+            normalized.push_str(rest);
+        } else {
+            normalized.push_str(line.as_slice());
+        }
+        normalized.push('\n');
+    }
+    normalized
+}
+
 fn run_rfail_test(config: &Config, props: &TestProps, testfile: &Path) {
     let proc_res = if !config.jit {
         let proc_res = compile_test(config, props, testfile);
@@ -325,6 +325,96 @@ pub trait Int
     #[stable]
     fn checked_div(self, other: Self) -> Option<Self>;
 
+    /// Wrapping integer addition. Computes `self + other`, wrapping around
+    /// at the boundary of the type instead of overflowing. Unlike `+`, this
+    /// never panics, even when overflow checks are enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::num::Int;
+    ///
+    /// assert_eq!(250u8.wrapping_add(10), 4);
+    /// assert_eq!(127i8.wrapping_add(1), -128);
+    /// ```
+    #[unstable = "pending integer conventions"]
+    fn wrapping_add(self, other: Self) -> Self;
+
+    /// Wrapping integer subtraction. Computes `self - other`, wrapping
+    /// around at the boundary of the type instead of overflowing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::num::Int;
+    ///
+    /// assert_eq!(0u8.wrapping_sub(1), 255);
+    /// assert_eq!((-128i8).wrapping_sub(1), 127);
+    /// ```
+    #[unstable = "pending integer conventions"]
+    fn wrapping_sub(self, other: Self) -> Self;
+
+    /// Wrapping integer multiplication. Computes `self * other`, wrapping
+    /// around at the boundary of the type instead of overflowing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::num::Int;
+    ///
+    /// assert_eq!(16u8.wrapping_mul(17), 16);
+    /// assert_eq!(64i8.wrapping_mul(2), -128);
+    /// ```
+    #[unstable = "pending integer conventions"]
+    fn wrapping_mul(self, other: Self) -> Self;
+
+    /// Wrapping negation. Computes `-self`, wrapping around at the boundary
+    /// of the type, so that the minimum value of a signed type is its own
+    /// negation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::num::Int;
+    ///
+    /// assert_eq!(5i8.wrapping_neg(), -5);
+    /// assert_eq!((-128i8).wrapping_neg(), -128);
+    /// assert_eq!(1u8.wrapping_neg(), 255);
+    /// ```
+    #[unstable = "pending integer conventions"]
+    #[inline]
+    fn wrapping_neg(self) -> Self {
+        Int::zero().wrapping_sub(self)
+    }
+
+    /// Shifts `self` left by `n` bits, where `n` is taken modulo the number
+    /// of bits of the type rather than overflowing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::num::Int;
+    ///
+    /// assert_eq!(1u8.wrapping_shl(7), 128);
+    /// assert_eq!(1u8.wrapping_shl(9), 2);
+    /// ```
+    #[unstable = "pending integer conventions"]
+    fn wrapping_shl(self, n: uint) -> Self;
+
+    /// Shifts `self` right by `n` bits, where `n` is taken modulo the number
+    /// of bits of the type rather than overflowing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::num::Int;
+    ///
+    /// assert_eq!(128u8.wrapping_shr(7), 1);
+    /// assert_eq!(128u8.wrapping_shr(9), 64);
+    /// ```
+    #[unstable = "pending integer conventions"]
+    fn wrapping_shr(self, n: uint) -> Self;
+
     /// Saturating integer addition. Computes `self + other`, saturating at
     /// the numeric bounds instead of overflowing.
     #[stable]
@@ -381,6 +471,13 @@ macro_rules! checked_op {
     }}
 }
 
+macro_rules! wrapping_op {
+    ($T:ty, $U:ty, $op:path, $x:expr, $y:expr) => {{
+        let (result, _) = unsafe { $op($x as $U, $y as $U) };
+        result as $T
+    }}
+}
+
 macro_rules! uint_impl {
     ($T:ty = $ActualT:ty, $BITS:expr,
      $ctpop:path,
@@ -452,6 +549,27 @@ macro_rules! uint_impl {
                     v => Some(self / v),
                 }
             }
+
+            #[inline]
+            fn wrapping_add(self, other: $T) -> $T {
+                wrapping_op!($T, $ActualT, $add_with_overflow, self, other)
+            }
+
+            #[inline]
+            fn wrapping_sub(self, other: $T) -> $T {
+                wrapping_op!($T, $ActualT, $sub_with_overflow, self, other)
+            }
+
+            #[inline]
+            fn wrapping_mul(self, other: $T) -> $T {
+                wrapping_op!($T, $ActualT, $mul_with_overflow, self, other)
+            }
+
+            #[inline]
+            fn wrapping_shl(self, n: uint) -> $T { self << (n % $BITS) }
+
+            #[inline]
+            fn wrapping_shr(self, n: uint) -> $T { self >> (n % $BITS) }
         }
     }
 }
@@ -577,6 +695,27 @@ macro_rules! int_impl {
                     v   => Some(self / v),
                 }
             }
+
+            #[inline]
+            fn wrapping_add(self, other: $T) -> $T {
+                wrapping_op!($T, $ActualT, $add_with_overflow, self, other)
+            }
+
+            #[inline]
+            fn wrapping_sub(self, other: $T) -> $T {
+                wrapping_op!($T, $ActualT, $sub_with_overflow, self, other)
+            }
+
+            #[inline]
+            fn wrapping_mul(self, other: $T) -> $T {
+                wrapping_op!($T, $ActualT, $mul_with_overflow, self, other)
+            }
+
+            #[inline]
+            fn wrapping_shl(self, n: uint) -> $T { self << (n % $BITS) }
+
+            #[inline]
+            fn wrapping_shr(self, n: uint) -> $T { self >> (n % $BITS) }
         }
     }
 }
@@ -649,7 +788,7 @@ macro_rules! signed_int_impl {
         impl SignedInt for $T {
             #[inline]
             fn abs(self) -> $T {
-                if self.is_negative() { -self } else { self }
+                if self.is_negative() { self.wrapping_neg() } else { self }
             }
 
             #[inline]
@@ -43,10 +43,10 @@ static EMPTY: ChaChaRng = ChaChaRng {
 
 macro_rules! quarter_round{
     ($a: expr, $b: expr, $c: expr, $d: expr) => {{
-        $a += $b; $d ^= $a; $d = $d.rotate_left(16);
-        $c += $d; $b ^= $c; $b = $b.rotate_left(12);
-        $a += $b; $d ^= $a; $d = $d.rotate_left( 8);
-        $c += $d; $b ^= $c; $b = $b.rotate_left( 7);
+        $a = $a.wrapping_add($b); $d ^= $a; $d = $d.rotate_left(16);
+        $c = $c.wrapping_add($d); $b ^= $c; $b = $b.rotate_left(12);
+        $a = $a.wrapping_add($b); $d ^= $a; $d = $d.rotate_left( 8);
+        $c = $c.wrapping_add($d); $b ^= $c; $b = $b.rotate_left( 7);
     }}
 }
 
@@ -74,7 +74,7 @@ fn core(output: &mut [u32; STATE_WORDS], input: &[u32; STATE_WORDS]) {
     }
 
     for i in range(0, STATE_WORDS) {
-        output[i] += input[i];
+        output[i] = output[i].wrapping_add(input[i]);
     }
 }
 
@@ -145,13 +145,13 @@ impl ChaChaRng {
         core(&mut self.buffer, &self.state);
         self.index = 0;
         // update 128-bit counter
-        self.state[12] += 1;
+        self.state[12] = self.state[12].wrapping_add(1);
         if self.state[12] != 0 { return };
-        self.state[13] += 1;
+        self.state[13] = self.state[13].wrapping_add(1);
         if self.state[13] != 0 { return };
-        self.state[14] += 1;
+        self.state[14] = self.state[14].wrapping_add(1);
         if self.state[14] != 0 { return };
-        self.state[15] += 1;
+        self.state[15] = self.state[15].wrapping_add(1);
     }
 }
 
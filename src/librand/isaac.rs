@@ -11,6 +11,7 @@
 //! The ISAAC random number generator.
 
 use core::prelude::*;
+use core::num::Int;
 use core::slice;
 use core::iter::{range_step, repeat};
 
@@ -60,7 +61,7 @@ impl IsaacRng {
     /// of `rsl` as a seed, otherwise construct one algorithmically (not
     /// randomly).
     fn init(&mut self, use_rsl: bool) {
-        let mut a = 0x9e3779b9;
+        let mut a = 0x9e3779b9u32;
         let mut b = a;
         let mut c = a;
         let mut d = a;
@@ -71,14 +72,14 @@ impl IsaacRng {
 
         macro_rules! mix {
             () => {{
-                a^=b<<11; d+=a; b+=c;
-                b^=c>>2;  e+=b; c+=d;
-                c^=d<<8;  f+=c; d+=e;
-                d^=e>>16; g+=d; e+=f;
-                e^=f<<10; h+=e; f+=g;
-                f^=g>>4;  a+=f; g+=h;
-                g^=h<<8;  b+=g; h+=a;
-                h^=a>>9;  c+=h; a+=b;
+                a^=b<<11; d=d.wrapping_add(a); b=b.wrapping_add(c);
+                b^=c>>2;  e=e.wrapping_add(b); c=c.wrapping_add(d);
+                c^=d<<8;  f=f.wrapping_add(c); d=d.wrapping_add(e);
+                d^=e>>16; g=g.wrapping_add(d); e=e.wrapping_add(f);
+                e^=f<<10; h=h.wrapping_add(e); f=f.wrapping_add(g);
+                f^=g>>4;  a=a.wrapping_add(f); g=g.wrapping_add(h);
+                g^=h<<8;  b=b.wrapping_add(g); h=h.wrapping_add(a);
+                h^=a>>9;  c=c.wrapping_add(h); a=a.wrapping_add(b);
             }}
         }
 
@@ -90,10 +91,10 @@ impl IsaacRng {
             macro_rules! memloop {
                 ($arr:expr) => {{
                     for i in range_step(0, RAND_SIZE as uint, 8) {
-                        a+=$arr[i  ]; b+=$arr[i+1];
-                        c+=$arr[i+2]; d+=$arr[i+3];
-                        e+=$arr[i+4]; f+=$arr[i+5];
-                        g+=$arr[i+6]; h+=$arr[i+7];
+                        a=a.wrapping_add($arr[i  ]); b=b.wrapping_add($arr[i+1]);
+                        c=c.wrapping_add($arr[i+2]); d=d.wrapping_add($arr[i+3]);
+                        e=e.wrapping_add($arr[i+4]); f=f.wrapping_add($arr[i+5]);
+                        g=g.wrapping_add($arr[i+6]); h=h.wrapping_add($arr[i+7]);
                         mix!();
                         self.mem[i  ]=a; self.mem[i+1]=b;
                         self.mem[i+2]=c; self.mem[i+3]=d;
@@ -122,10 +123,10 @@ impl IsaacRng {
     #[inline]
     #[allow(unsigned_negation)]
     fn isaac(&mut self) {
-        self.c += 1;
+        self.c = self.c.wrapping_add(1);
         // abbreviations
         let mut a = self.a;
-        let mut b = self.b + self.c;
+        let mut b = self.b.wrapping_add(self.c);
 
         static MIDPOINT: uint = (RAND_SIZE / 2) as uint;
 
@@ -142,11 +143,11 @@ impl IsaacRng {
                     let mix = a << $shift as uint;
 
                     let x = self.mem[base  + mr_offset];
-                    a = (a ^ mix) + self.mem[base + m2_offset];
-                    let y = ind!(x) + a + b;
+                    a = (a ^ mix).wrapping_add(self.mem[base + m2_offset]);
+                    let y = ind!(x).wrapping_add(a).wrapping_add(b);
                     self.mem[base + mr_offset] = y;
 
-                    b = ind!(y >> RAND_SIZE_LEN as uint) + x;
+                    b = ind!(y >> RAND_SIZE_LEN as uint).wrapping_add(x);
                     self.rsl[base + mr_offset] = b;
                 }}
             }
@@ -157,11 +158,11 @@ impl IsaacRng {
                     let mix = a >> $shift as uint;
 
                     let x = self.mem[base  + mr_offset];
-                    a = (a ^ mix) + self.mem[base + m2_offset];
-                    let y = ind!(x) + a + b;
+                    a = (a ^ mix).wrapping_add(self.mem[base + m2_offset]);
+                    let y = ind!(x).wrapping_add(a).wrapping_add(b);
                     self.mem[base + mr_offset] = y;
 
-                    b = ind!(y >> RAND_SIZE_LEN as uint) + x;
+                    b = ind!(y >> RAND_SIZE_LEN as uint).wrapping_add(x);
                     self.rsl[base + mr_offset] = b;
                 }}
             }
@@ -304,7 +305,7 @@ impl Isaac64Rng {
     fn init(&mut self, use_rsl: bool) {
         macro_rules! init {
             ($var:ident) => (
-                let mut $var = 0x9e3779b97f4a7c13;
+                let mut $var = 0x9e3779b97f4a7c13u64;
             )
         }
         init!(a); init!(b); init!(c); init!(d);
@@ -312,14 +313,14 @@ impl Isaac64Rng {
 
         macro_rules! mix {
             () => {{
-                a-=e; f^=h>>9;  h+=a;
-                b-=f; g^=a<<9;  a+=b;
-                c-=g; h^=b>>23; b+=c;
-                d-=h; a^=c<<15; c+=d;
-                e-=a; b^=d>>14; d+=e;
-                f-=b; c^=e<<20; e+=f;
-                g-=c; d^=f>>17; f+=g;
-                h-=d; e^=g<<14; g+=h;
+                a=a.wrapping_sub(e); f^=h>>9;  h=h.wrapping_add(a);
+                b=b.wrapping_sub(f); g^=a<<9;  a=a.wrapping_add(b);
+                c=c.wrapping_sub(g); h^=b>>23; b=b.wrapping_add(c);
+                d=d.wrapping_sub(h); a^=c<<15; c=c.wrapping_add(d);
+                e=e.wrapping_sub(a); b^=d>>14; d=d.wrapping_add(e);
+                f=f.wrapping_sub(b); c^=e<<20; e=e.wrapping_add(f);
+                g=g.wrapping_sub(c); d^=f>>17; f=f.wrapping_add(g);
+                h=h.wrapping_sub(d); e^=g<<14; g=g.wrapping_add(h);
             }}
         }
 
@@ -331,10 +332,10 @@ impl Isaac64Rng {
             macro_rules! memloop {
                 ($arr:expr) => {{
                     for i in range(0, RAND_SIZE_64 / 8).map(|i| i * 8) {
-                        a+=$arr[i  ]; b+=$arr[i+1];
-                        c+=$arr[i+2]; d+=$arr[i+3];
-                        e+=$arr[i+4]; f+=$arr[i+5];
-                        g+=$arr[i+6]; h+=$arr[i+7];
+                        a=a.wrapping_add($arr[i  ]); b=b.wrapping_add($arr[i+1]);
+                        c=c.wrapping_add($arr[i+2]); d=d.wrapping_add($arr[i+3]);
+                        e=e.wrapping_add($arr[i+4]); f=f.wrapping_add($arr[i+5]);
+                        g=g.wrapping_add($arr[i+6]); h=h.wrapping_add($arr[i+7]);
                         mix!();
                         self.mem[i  ]=a; self.mem[i+1]=b;
                         self.mem[i+2]=c; self.mem[i+3]=d;
@@ -361,10 +362,10 @@ impl Isaac64Rng {
 
     /// Refills the output buffer (`self.rsl`)
     fn isaac64(&mut self) {
-        self.c += 1;
+        self.c = self.c.wrapping_add(1);
         // abbreviations
         let mut a = self.a;
-        let mut b = self.b + self.c;
+        let mut b = self.b.wrapping_add(self.c);
         const MIDPOINT: uint =  RAND_SIZE_64 / 2;
         const MP_VEC: [(uint, uint); 2] = [(0,MIDPOINT), (MIDPOINT, 0)];
         macro_rules! ind {
@@ -384,11 +385,11 @@ impl Isaac64Rng {
 
                         unsafe {
                             let x = *self.mem.get_unchecked(base + mr_offset);
-                            a = mix + *self.mem.get_unchecked(base + m2_offset);
-                            let y = ind!(x) + a + b;
+                            a = mix.wrapping_add(*self.mem.get_unchecked(base + m2_offset));
+                            let y = ind!(x).wrapping_add(a).wrapping_add(b);
                             *self.mem.get_unchecked_mut(base + mr_offset) = y;
 
-                            b = ind!(y >> RAND_SIZE_64_LEN) + x;
+                            b = ind!(y >> RAND_SIZE_64_LEN).wrapping_add(x);
                             *self.rsl.get_unchecked_mut(base + mr_offset) = b;
                         }
                     }}
@@ -402,11 +403,11 @@ impl Isaac64Rng {
 
                         unsafe {
                             let x = *self.mem.get_unchecked(base + mr_offset);
-                            a = mix + *self.mem.get_unchecked(base + m2_offset);
-                            let y = ind!(x) + a + b;
+                            a = mix.wrapping_add(*self.mem.get_unchecked(base + m2_offset));
+                            let y = ind!(x).wrapping_add(a).wrapping_add(b);
                             *self.mem.get_unchecked_mut(base + mr_offset) = y;
 
-                            b = ind!(y >> RAND_SIZE_64_LEN) + x;
+                            b = ind!(y >> RAND_SIZE_64_LEN).wrapping_add(x);
                             *self.rsl.get_unchecked_mut(base + mr_offset) = b;
                         }
                     }}
@@ -68,5 +68,7 @@ register_diagnostics! {
     E0174,
     E0177,
     E0178,
-    E0179
+    E0179,
+    E0185,
+    E0186
 }
@@ -124,6 +124,7 @@ pub mod util {
     pub mod nodemap;
     pub mod snapshot_vec;
     pub mod lev_distance;
+    pub mod trace;
 }
 
 pub mod lib {
@@ -27,17 +27,20 @@
 use self::MethodContext::*;
 
 use metadata::csearch;
+use middle::cfg;
 use middle::def::*;
-use middle::subst::Substs;
+use middle::subst::{self, Substs};
 use middle::ty::{self, Ty};
 use middle::{def, pat_util, stability};
 use middle::const_eval::{eval_const_expr_partial, const_int, const_uint};
 use util::ppaux::{ty_to_string};
 use util::nodemap::{FnvHashMap, NodeSet};
-use lint::{Context, LintPass, LintArray, Lint};
+use lint::{Context, LintPass, LintArray, Lint, LintSuggestion};
 
 use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::iter::repeat;
 use std::num::SignedInt;
+use std::rc::Rc;
 use std::{cmp, slice};
 use std::{i8, i16, i32, i64, u8, u16, u32, u64, f32, f64};
 
@@ -197,7 +200,7 @@ impl LintPass for TypeLimits {
                             if let ast::LitInt(shift, _) = lit.node { shift >= bits }
                             else { false }
                         } else {
-                            match eval_const_expr_partial(cx.tcx, &**r) {
+                            match eval_const_expr_partial(cx.tcx, &**r, None) {
                                 Ok(const_int(shift)) => { shift as u64 >= bits },
                                 Ok(const_uint(shift)) => { shift >= bits },
                                 _ => { false }
@@ -667,6 +670,7 @@ impl LintPass for UnusedAttributes {
             "stable",
             "unstable",
             "rustc_on_unimplemented",
+            "const_fn",
 
             // FIXME: #19470 this shouldn't be needed forever
             "old_orphan_check",
@@ -1110,9 +1114,18 @@ impl UnusedParens {
         if let ast::ExprParen(ref inner) = value.node {
             let necessary = struct_lit_needs_parens && contains_exterior_struct_lit(&**inner);
             if !necessary {
-                cx.span_lint(UNUSED_PARENS, value.span,
-                             &format!("unnecessary parentheses around {}",
-                                     msg)[])
+                let msg = format!("unnecessary parentheses around {}", msg);
+                match cx.sess().codemap().span_to_snippet(inner.span) {
+                    Some(snippet) => {
+                        cx.span_lint_with_suggestion(UNUSED_PARENS, value.span, &msg[],
+                                                     LintSuggestion {
+                            span: value.span,
+                            msg: "remove the parentheses".to_string(),
+                            replacement: snippet,
+                        })
+                    }
+                    None => cx.span_lint(UNUSED_PARENS, value.span, &msg[]),
+                }
             }
         }
 
@@ -1251,9 +1264,22 @@ impl LintPass for NonShorthandFieldPatterns {
                                                 == Some(&def::DefLocal(fieldpat.node.pat.id))) {
                 if let ast::PatIdent(_, ident, None) = fieldpat.node.pat.node {
                     if ident.node.as_str() == fieldpat.node.ident.as_str() {
-                        cx.span_lint(NON_SHORTHAND_FIELD_PATTERNS, fieldpat.span,
-                                     &format!("the `{}:` in this pattern is redundant and can \
-                                              be removed", ident.node.as_str())[])
+                        let msg = format!("the `{}:` in this pattern is redundant and can \
+                                           be removed", ident.node.as_str());
+                        let pat_span = fieldpat.node.pat.span;
+                        match cx.sess().codemap().span_to_snippet(pat_span) {
+                            Some(snippet) => {
+                                cx.span_lint_with_suggestion(NON_SHORTHAND_FIELD_PATTERNS,
+                                                             fieldpat.span, &msg[],
+                                                             LintSuggestion {
+                                    span: fieldpat.span,
+                                    msg: "use the shorthand".to_string(),
+                                    replacement: snippet,
+                                })
+                            }
+                            None => cx.span_lint(NON_SHORTHAND_FIELD_PATTERNS,
+                                                 fieldpat.span, &msg[]),
+                        }
                     }
                 }
             }
@@ -1629,7 +1655,7 @@ impl LintPass for MissingCopyImplementations {
 declare_lint! {
     DEPRECATED,
     Warn,
-    "detects use of #[deprecated] items"
+    "detects use of #[deprecated] and #[deprecated(...)] items"
 }
 
 declare_lint! {
@@ -1639,7 +1665,8 @@ declare_lint! {
 }
 
 /// Checks for use of items with `#[deprecated]`, `#[unstable]` and
-/// `#[unstable]` attributes, or no stability attribute.
+/// `#[unstable]` attributes, or no stability attribute, as well as of items
+/// with a `#[deprecated(...)]` attribute.
 #[derive(Copy)]
 pub struct Stability { this_crate_staged: bool }
 
@@ -1647,6 +1674,22 @@ impl Stability {
     pub fn new() -> Stability { Stability { this_crate_staged: false } }
 
     fn lint(&self, cx: &Context, id: ast::DefId, span: Span) {
+        // Unlike the other stability levels, deprecation is honored whether
+        // or not the crate uses the staged API.
+        let depr = stability::lookup_deprecation(cx.tcx, id);
+        if let Some(ref depr) = depr {
+            let mut msg = String::from_str("use of deprecated item");
+            if let Some(ref since) = depr.since {
+                msg.push_str(&format!(" (deprecated since {})", *since)[]);
+            }
+            if let Some(ref note) = depr.note {
+                msg.push_str(&format!(": {}", *note)[]);
+            }
+            if let Some(ref replacement) = depr.replacement {
+                msg.push_str(&format!("; use `{}` instead", *replacement)[]);
+            }
+            cx.span_lint(DEPRECATED, span, &msg[]);
+        }
 
         let ref stability = stability::lookup(cx.tcx, id);
         let cross_crate = !ast_util::is_local(id);
@@ -1662,6 +1705,9 @@ impl Stability {
             None if cross_crate => (UNSTABLE, "unmarked"),
             Some(attr::Stability { level: attr::Unstable, .. }) if cross_crate =>
                 (UNSTABLE, "unstable"),
+            // already reported above
+            Some(attr::Stability { level: attr::Deprecated, .. }) if depr.is_some() =>
+                return,
             Some(attr::Stability { level: attr::Deprecated, .. }) =>
                 (DEPRECATED, "deprecated"),
             _ => return
@@ -1794,6 +1840,176 @@ impl LintPass for Stability {
     }
 }
 
+declare_lint! {
+    pub UNCONDITIONAL_RECURSION,
+    Warn,
+    "functions that cannot return without calling themselves"
+}
+
+/// Checks for functions and methods that call themselves on every path
+/// through their body, e.g. an `impl Show` whose `fmt` formats `self`.
+#[derive(Copy)]
+pub struct UnconditionalRecursion;
+
+impl LintPass for UnconditionalRecursion {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(UNCONDITIONAL_RECURSION)
+    }
+
+    fn check_fn(&mut self, cx: &Context,
+                fk: visit::FnKind, _: &ast::FnDecl,
+                blk: &ast::Block, sp: Span, id: ast::NodeId) {
+        // Closures can't name themselves, so they can't recur.
+        if let visit::FkFnBlock = fk { return }
+
+        let this_fn = ast_util::local_def(id);
+        let this_trait_method = trait_method_of(cx.tcx, this_fn);
+
+        // Look for a path from the entry of the function to its exit which
+        // doesn't go through a call of the function itself, treating such
+        // calls as dead ends. If there is none, the function can't return
+        // without recurring first.
+        //
+        // Calls of diverging functions and loops without a `break` don't
+        // reach the exit either, so a function which may only panic instead
+        // of recurring, like
+        //
+        //     fn f(x: uint) { assert!(x > 0); f(x - 1) }
+        //
+        // is caught as well, which is almost certainly a mistake too.
+        let cfg = cfg::CFG::new(cx.tcx, blk);
+        let mut visited = repeat(false).take(cfg.graph.all_nodes().len()).collect::<Vec<_>>();
+        let mut work_queue = vec![cfg.entry];
+        let mut self_calls = vec![];
+        while let Some(idx) = work_queue.pop() {
+            if idx == cfg.exit {
+                return;
+            }
+            if visited[idx.node_id()] {
+                continue;
+            }
+            visited[idx.node_id()] = true;
+
+            let node_id = cfg.graph.node_data(idx).id;
+            if node_id != ast::DUMMY_NODE_ID &&
+               is_self_call(cx.tcx, this_fn, &this_trait_method, node_id) {
+                self_calls.push(cx.tcx.map.span(node_id));
+                continue;
+            }
+
+            cfg.graph.each_outgoing_edge(idx, |_, edge| {
+                if !visited[edge.target().node_id()] {
+                    work_queue.push(edge.target());
+                }
+                true
+            });
+        }
+
+        // A function which never returns, but doesn't call itself either,
+        // is fine.
+        if self_calls.is_empty() {
+            return;
+        }
+        self_calls.sort_by(|a, b| a.lo.0.cmp(&b.lo.0));
+        let notes = self_calls.iter().map(|&call| (call, "recursive call site"))
+                              .collect::<Vec<_>>();
+        cx.span_lint_with_notes(UNCONDITIONAL_RECURSION, sp,
+                                "function cannot return without recurring",
+                                &notes[]);
+
+        /// The trait method the function `did` is the default of, or
+        /// implements, along with the trait reference of the impl in the
+        /// latter case.
+        fn trait_method_of<'tcx>(tcx: &ty::ctxt<'tcx>, did: ast::DefId)
+                                 -> Option<(ast::DefId, Option<Rc<ty::TraitRef<'tcx>>>)> {
+            match ty::impl_of_method(tcx, did) {
+                Some(impl_did) => {
+                    ty::impl_trait_ref(tcx, impl_did).and_then(|trait_ref| {
+                        ty::trait_item_of_item(tcx, did).map(|item| {
+                            (item.def_id(), Some(trait_ref))
+                        })
+                    })
+                }
+                None if ty::trait_of_item(tcx, did).is_some() => Some((did, None)),
+                None => None,
+            }
+        }
+
+        /// Whether the expression `id` calls the function `this_fn`, either
+        /// directly or by dispatching to it through its trait.
+        fn is_self_call<'tcx>(tcx: &ty::ctxt<'tcx>,
+                              this_fn: ast::DefId,
+                              this_trait_method: &Option<(ast::DefId,
+                                                          Option<Rc<ty::TraitRef<'tcx>>>)>,
+                              id: ast::NodeId) -> bool {
+            let expr = match tcx.map.find(id) {
+                Some(ast_map::NodeExpr(expr)) => expr,
+                _ => return false,
+            };
+
+            // Method calls and overloaded operators.
+            match tcx.method_map.borrow().get(&ty::MethodCall::expr(id)) {
+                Some(method) => return match method.origin {
+                    ty::MethodStatic(did) => did == this_fn,
+                    ty::MethodTypeParam(ty::MethodParam { ref trait_ref, method_num }) => {
+                        let did = ty::trait_item(tcx, trait_ref.def_id, method_num).def_id();
+                        dispatches_to_self(this_trait_method, did, trait_ref.substs)
+                    }
+                    // Which method is called through a trait object isn't
+                    // known until runtime.
+                    ty::MethodTraitObject(..) |
+                    ty::MethodStaticUnboxedClosure(..) => false,
+                },
+                None => {}
+            }
+
+            // Calls of paths, including `Trait::method(self)`.
+            let callee = match expr.node {
+                ast::ExprCall(ref callee, _) => callee,
+                _ => return false,
+            };
+            let did = match tcx.def_map.borrow().get(&callee.id) {
+                Some(&DefFn(did, _)) | Some(&DefStaticMethod(did, _)) |
+                Some(&DefMethod(did, _, _)) => did,
+                _ => return false,
+            };
+            did == this_fn || {
+                let substs = ty::node_id_item_substs(tcx, callee.id).substs;
+                dispatches_to_self(this_trait_method, did, &substs)
+            }
+        }
+
+        /// Whether calling the trait method `callee` with `substs` ends up
+        /// in the method `this_trait_method` describes.
+        fn dispatches_to_self<'tcx>(this_trait_method: &Option<(ast::DefId,
+                                                                Option<Rc<ty::TraitRef<'tcx>>>)>,
+                                    callee: ast::DefId,
+                                    substs: &Substs<'tcx>) -> bool {
+            match *this_trait_method {
+                Some((did, _)) if did != callee => false,
+                // A method of an impl is called if the trait is used at the
+                // types the impl is for.
+                Some((_, Some(ref impl_trait_ref))) => {
+                    [subst::TypeSpace, subst::SelfSpace].iter().all(|&space| {
+                        substs.types.get_slice(space) ==
+                            impl_trait_ref.substs.types.get_slice(space)
+                    })
+                }
+                // A default method is called if the call dispatches on
+                // `Self`.
+                Some((_, None)) => match substs.self_ty() {
+                    Some(ty) => match ty.sty {
+                        ty::ty_param(ty::ParamTy { space: subst::SelfSpace, .. }) => true,
+                        _ => false,
+                    },
+                    None => false,
+                },
+                None => false,
+            }
+        }
+    }
+}
+
 declare_lint! {
     pub UNUSED_IMPORTS,
     Warn,
@@ -0,0 +1,176 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lint config files.
+//!
+//! A lint config file sets the levels of lints and lint groups the same way
+//! the `-A`/`-W`/`-D`/`-F` flags do, one level per line:
+//!
+//! ```text
+//! # Comments start with `#`.
+//! deny = unused_imports, unused_variables
+//! warn = missing_docs
+//!
+//! # Levels for the items in the files under `src/generated`, relative to
+//! # the directory of this file.
+//! [src/generated]
+//! allow = missing_docs, bad_style
+//! ```
+//!
+//! The file is added to the codemap, so that diagnostics about it, and the
+//! "lint level defined here" notes of lints whose level it set, point into
+//! it. Lint names are resolved by `LintStore::process_config_file`.
+
+use lint::Level;
+use session::Session;
+
+use std::io::File;
+use std::io::fs::PathExtensions;
+use std::os;
+use syntax::codemap::{Span, BytePos, mk_sp};
+
+/// The name of the lint config file looked for next to the crate root.
+pub const DEFAULT_FILE_NAME: &'static str = "rustc-lints.cfg";
+
+/// A lint or lint group set to a level by a config file.
+pub struct LevelSetting {
+    /// The name of the lint or lint group, with dashes replaced by
+    /// underscores.
+    pub name: String,
+    pub level: Level,
+    /// Where the name appears in the config file.
+    pub span: Span,
+}
+
+/// The levels applying to the files under `path`.
+pub struct PathLevels {
+    /// An absolute path.
+    pub path: Path,
+    pub levels: Vec<LevelSetting>,
+}
+
+pub struct LintConfig {
+    /// The levels applying to the whole crate, in the order given.
+    pub levels: Vec<LevelSetting>,
+    /// The per-path levels, in the order given.
+    pub paths: Vec<PathLevels>,
+}
+
+/// The config file to use: the one given with `--lint-config`, or the one
+/// next to the crate root if it exists.
+pub fn find(sess: &Session) -> Option<Path> {
+    if let Some(ref path) = sess.opts.lint_config {
+        return Some(path.clone());
+    }
+    if !sess.unstable_options() {
+        return None;
+    }
+    sess.local_crate_source_file.as_ref().and_then(|file| {
+        let path = file.dir_path().join(DEFAULT_FILE_NAME);
+        if path.exists() { Some(path) } else { None }
+    })
+}
+
+/// Reads and parses the config file at `path`, reporting any errors to
+/// `sess`.
+pub fn load(sess: &Session, path: &Path) -> Option<LintConfig> {
+    let src = match File::open(path).read_to_string() {
+        Ok(src) => src,
+        Err(e) => {
+            sess.err(&format!("couldn't read lint config file {}: {}",
+                              path.display(), e)[]);
+            return None;
+        }
+    };
+    let dir = match os::make_absolute(path) {
+        Ok(path) => path.dir_path(),
+        Err(e) => {
+            sess.err(&format!("couldn't find the directory of lint config file {}: {}",
+                              path.display(), e)[]);
+            return None;
+        }
+    };
+
+    // Like any source file, the config file is then listed by --dep-info, as
+    // the crate has to be rebuilt when it changes.
+    let filemap = sess.codemap().new_filemap(path.as_str().unwrap_or("<lint config>")
+                                                 .to_string(),
+                                             src.clone());
+    let sp = |&: lo: uint, hi: uint| {
+        mk_sp(filemap.start_pos + BytePos(lo as u32), filemap.start_pos + BytePos(hi as u32))
+    };
+
+    let mut config = LintConfig { levels: Vec::new(), paths: Vec::new() };
+    let mut line_start = 0u;
+    for line in src.split('\n') {
+        let start = line_start;
+        line_start += line.len() + 1;
+
+        let content = match line.find('#') {
+            Some(i) => &line[..i],
+            None => line,
+        };
+        let trimmed = content.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let lo = start + (content.len() - content.trim_left().len());
+        let hi = lo + trimmed.len();
+
+        if trimmed.starts_with("[") {
+            if !trimmed.ends_with("]") || trimmed.len() < 3 {
+                sess.span_err(sp(lo, hi), "expected a path in brackets, like `[src/foo]`");
+                continue;
+            }
+            config.paths.push(PathLevels {
+                path: dir.join(trimmed[1..trimmed.len() - 1].trim()),
+                levels: Vec::new(),
+            });
+            continue;
+        }
+
+        let eq = match trimmed.find('=') {
+            Some(eq) => eq,
+            None => {
+                sess.span_err(sp(lo, hi), "expected a lint level, like `deny = unused_imports`");
+                continue;
+            }
+        };
+        let key = trimmed[..eq].trim();
+        let level = match Level::from_str(key) {
+            Some(level) => level,
+            None => {
+                sess.span_err(sp(lo, lo + key.len()),
+                              &format!("unknown lint level `{}`; expected `allow`, `warn`, \
+                                        `deny` or `forbid`", key)[]);
+                continue;
+            }
+        };
+
+        let levels = match config.paths.last_mut() {
+            Some(path) => &mut path.levels,
+            None => &mut config.levels,
+        };
+        let mut pos = lo + eq + 1;
+        for part in trimmed[eq + 1..].split(',') {
+            let name = part.trim();
+            if !name.is_empty() {
+                let name_lo = pos + (part.len() - part.trim_left().len());
+                levels.push(LevelSetting {
+                    name: name.replace("-", "_"),
+                    level: level,
+                    span: sp(name_lo, name_lo + name.len()),
+                });
+            }
+            pos += part.len() + 1;
+        }
+    }
+    Some(config)
+}
@@ -30,16 +30,20 @@ use middle::ty::{self, Ty};
 use session::{early_error, Session};
 use session::config::UnstableFeatures;
 use lint::{Level, LevelSource, Lint, LintId, LintArray, LintPass, LintPassObject};
-use lint::{Default, CommandLine, Node, Allow, Warn, Deny, Forbid, ReleaseChannel};
+use lint::{LintSource, LintSuggestion};
+use lint::{Default, CommandLine, ConfigFile, Node, Allow, Warn, Deny, Forbid, ReleaseChannel};
 use lint::builtin;
+use lint::config_file;
 use util::nodemap::FnvHashMap;
 
 use std::cell::RefCell;
 use std::mem;
+use std::os;
 use syntax::ast_util::IdVisitingOperation;
 use syntax::attr::AttrMetaMethods;
 use syntax::attr;
 use syntax::codemap::Span;
+use syntax::diagnostic::MachineApplicable;
 use syntax::visit::{Visitor, FnKind};
 use syntax::parse::token::InternedString;
 use syntax::{ast, ast_util, visit};
@@ -67,6 +71,10 @@ pub struct LintStore {
     /// Map of registered lint groups to what lints they expand to. The bool
     /// is true if the lint group was added by a plugin.
     lint_groups: FnvHashMap<&'static str, (Vec<LintId>, bool)>,
+
+    /// The levels a lint config file gives for the files under a path,
+    /// along with where in the config file each was given.
+    path_levels: Vec<(Path, Vec<(LintId, Level, Span)>)>,
 }
 
 /// The targed of the `by_name` map, which accounts for renaming/deprecation.
@@ -101,6 +109,7 @@ impl LintStore {
             by_name: FnvHashMap::new(),
             levels: FnvHashMap::new(),
             lint_groups: FnvHashMap::new(),
+            path_levels: vec!(),
         }
     }
 
@@ -211,6 +220,7 @@ impl LintStore {
                      UnusedAllocation,
                      MissingCopyImplementations,
                      UnstableFeatures,
+                     UnconditionalRecursion,
         );
 
         add_builtin_with_new!(sess,
@@ -278,6 +288,55 @@ impl LintStore {
         }
     }
 
+    fn find_lint_or_group(&self, lint_name: &str, sess: &Session, span: Span)
+                          -> Option<Vec<LintId>> {
+        match self.find_lint(lint_name, sess, Some(span)) {
+            Some(lint_id) => Some(vec![lint_id]),
+            None => self.lint_groups.get(lint_name).map(|&(ref v, _)| v.clone()),
+        }
+    }
+
+    /// Applies the levels given by the lint config file, if there is one.
+    /// This must happen before the command line flags are processed, as
+    /// those take precedence.
+    pub fn process_config_file(&mut self, sess: &Session) {
+        let config = match config_file::find(sess) {
+            Some(path) => match config_file::load(sess, &path) {
+                Some(config) => config,
+                None => return,
+            },
+            None => return,
+        };
+
+        for setting in config.levels.iter() {
+            match self.find_lint_or_group(&setting.name[], sess, setting.span) {
+                Some(v) => {
+                    for lint_id in v.into_iter() {
+                        self.set_level(lint_id, (setting.level, ConfigFile(setting.span)));
+                    }
+                }
+                None => sess.span_err(setting.span,
+                                      &format!("unknown lint: `{}`", setting.name)[]),
+            }
+        }
+
+        for path_levels in config.paths.iter() {
+            let mut levels = vec![];
+            for setting in path_levels.levels.iter() {
+                match self.find_lint_or_group(&setting.name[], sess, setting.span) {
+                    Some(v) => {
+                        levels.extend(v.into_iter().map(|lint_id| {
+                            (lint_id, setting.level, setting.span)
+                        }));
+                    }
+                    None => sess.span_err(setting.span,
+                                          &format!("unknown lint: `{}`", setting.name)[]),
+                }
+            }
+            self.path_levels.push((path_levels.path.clone(), levels));
+        }
+    }
+
     pub fn process_command_line(&mut self, sess: &Session) {
         for &(ref lint_name, level) in sess.opts.lint_opts.iter() {
             match self.find_lint(&lint_name[], sess, None) {
@@ -350,6 +409,18 @@ pub struct Context<'a, 'tcx: 'a> {
     /// Level of lints for certain NodeIds, stored here because the body of
     /// the lint needs to run in trans.
     node_levels: RefCell<FnvHashMap<(ast::NodeId, LintId), LevelSource>>,
+
+    /// The index into `lints.path_levels` of the levels that apply to each
+    /// source file, by file name.
+    file_path_levels: FnvHashMap<String, Option<uint>>,
+
+    /// The index into `lints.path_levels` of the levels currently in
+    /// effect, if any.
+    current_path_levels: Option<uint>,
+
+    /// The levels set by `current_path_levels`, each with the level it
+    /// replaced.
+    path_pushed: Vec<(LintId, LevelSource, LevelSource)>,
 }
 
 /// Convenience macro for calling a `LintPass` method on every pass in the context.
@@ -422,7 +493,7 @@ pub fn raw_emit_lint(sess: &Session, lint: &'static Lint,
                         Allow => panic!()
                     }, name.replace("_", "-"))
         },
-        Node(src) => {
+        Node(src) | ConfigFile(src) => {
             def = Some(src);
             msg.to_string()
         }
@@ -469,6 +540,9 @@ impl<'a, 'tcx> Context<'a, 'tcx> {
             lints: lint_store,
             level_stack: vec![],
             node_levels: RefCell::new(FnvHashMap::new()),
+            file_path_levels: FnvHashMap::new(),
+            current_path_levels: None,
+            path_pushed: vec![],
         }
     }
 
@@ -483,9 +557,11 @@ impl<'a, 'tcx> Context<'a, 'tcx> {
         self.lints.levels.get(&LintId::of(lint)).map_or(Allow, |&(lvl, _)| lvl)
     }
 
-    fn lookup_and_emit(&self, lint: &'static Lint, span: Option<Span>, msg: &str) {
+    /// Emit a lint, returning whether it was actually printed.
+    fn lookup_and_emit(&self, lint: &'static Lint, span: Option<Span>, msg: &str,
+                       suggestion: Option<LintSuggestion>) -> bool {
         let (level, src) = match self.lints.levels.get(&LintId::of(lint)) {
-            None => return,
+            None => return false,
             Some(&(Warn, src)) => {
                 let lint_id = LintId::of(builtin::WARNINGS);
                 (self.lints.get_level_source(lint_id).0, src)
@@ -494,16 +570,47 @@ impl<'a, 'tcx> Context<'a, 'tcx> {
         };
 
         raw_emit_lint(&self.tcx.sess, lint, (level, src), span, msg);
+
+        let emitted = match level {
+            Allow => false,
+            Warn => self.tcx.sess.can_print_warnings,
+            Deny | Forbid => true,
+        };
+        if emitted {
+            for s in suggestion.into_iter() {
+                self.tcx.sess.span_suggestion(s.span, &s.msg[], s.replacement,
+                                              MachineApplicable);
+            }
+        }
+        emitted
     }
 
     /// Emit a lint at the appropriate level, with no associated span.
     pub fn lint(&self, lint: &'static Lint, msg: &str) {
-        self.lookup_and_emit(lint, None, msg);
+        self.lookup_and_emit(lint, None, msg, None);
     }
 
     /// Emit a lint at the appropriate level, for a particular span.
     pub fn span_lint(&self, lint: &'static Lint, span: Span, msg: &str) {
-        self.lookup_and_emit(lint, Some(span), msg);
+        self.lookup_and_emit(lint, Some(span), msg, None);
+    }
+
+    /// Emit a lint at the appropriate level, for a particular span, followed
+    /// by notes pointing at related code.
+    pub fn span_lint_with_notes(&self, lint: &'static Lint, span: Span, msg: &str,
+                                notes: &[(Span, &str)]) {
+        if self.lookup_and_emit(lint, Some(span), msg, None) {
+            for &(note_span, note) in notes.iter() {
+                self.tcx.sess.span_note(note_span, note);
+            }
+        }
+    }
+
+    /// Emit a lint at the appropriate level, for a particular span, followed
+    /// by a suggested fix.
+    pub fn span_lint_with_suggestion(&self, lint: &'static Lint, span: Span, msg: &str,
+                                     suggestion: LintSuggestion) {
+        self.lookup_and_emit(lint, Some(span), msg, Some(suggestion));
     }
 
     /// Merge the lints specified by any lint attributes into the
@@ -548,18 +655,8 @@ impl<'a, 'tcx> Context<'a, 'tcx> {
             };
 
             for (lint_id, level, span) in v.into_iter() {
-                let now = self.lints.get_level_source(lint_id).0;
-                if now == Forbid && level != Forbid {
-                    let lint_name = lint_id.as_str();
-                    self.tcx.sess.span_err(span,
-                                           &format!("{}({}) overruled by outer forbid({})",
-                                                   level.as_str(), lint_name,
-                                                   lint_name)[]);
-                } else if now != level {
-                    let src = self.lints.get_level_source(lint_id).1;
-                    self.level_stack.push((lint_id, (now, src)));
+                if self.push_level(lint_id, level, Node(span), span) {
                     pushed += 1;
-                    self.lints.set_level(lint_id, (level, Node(span)));
                 }
             }
         }
@@ -575,6 +672,103 @@ impl<'a, 'tcx> Context<'a, 'tcx> {
         }
     }
 
+    /// Set the level of a lint until the level stack is rolled back,
+    /// returning whether the previous level was pushed onto it.
+    fn push_level(&mut self, lint_id: LintId, level: Level,
+                  source: LintSource, span: Span) -> bool {
+        let now = self.lints.get_level_source(lint_id).0;
+        if now == Forbid && level != Forbid {
+            let lint_name = lint_id.as_str();
+            self.tcx.sess.span_err(span,
+                                   &format!("{}({}) overruled by outer forbid({})",
+                                           level.as_str(), lint_name,
+                                           lint_name)[]);
+            false
+        } else if now != level {
+            let src = self.lints.get_level_source(lint_id).1;
+            self.level_stack.push((lint_id, (now, src)));
+            self.lints.set_level(lint_id, (level, source));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The index of the per-path levels of the lint config file which apply
+    /// to the file `span` is in; the levels of the most specific path win.
+    fn path_levels_index(&mut self, span: Span) -> Option<uint> {
+        if self.lints.path_levels.is_empty() {
+            return None;
+        }
+        let filemap = self.tcx.sess.codemap().lookup_byte_offset(span.lo).fm;
+        if !filemap.is_real_file() {
+            return None;
+        }
+        let name = filemap.original_name().to_string();
+        if let Some(&index) = self.file_path_levels.get(&name) {
+            return index;
+        }
+
+        let index = os::make_absolute(&Path::new(&name[])).ok().and_then(|file| {
+            self.lints.path_levels.iter().enumerate().filter(|&(_, &(ref path, _))| {
+                path.is_ancestor_of(&file)
+            }).max_by(|&(_, &(ref path, _))| path.as_vec().len()).map(|(i, _)| i)
+        });
+        self.file_path_levels.insert(name, index);
+        index
+    }
+
+    /// Merge the levels the lint config file gives for the file `span` is
+    /// in, call the provided function, then reset the lints in effect to
+    /// their previous state.
+    fn with_path_levels<F>(&mut self, span: Span, f: F) where
+        F: FnOnce(&mut Context),
+    {
+        let index = self.path_levels_index(span);
+        if index == self.current_path_levels {
+            f(self);
+            return;
+        }
+
+        // The levels given for the path of the enclosing file don't apply
+        // to this one, so they are undone, except where an attribute in
+        // between has changed them again.
+        let mut pushed = 0u;
+        let outer_pushed = mem::replace(&mut self.path_pushed, vec![]);
+        for &(lint_id, set, replaced) in outer_pushed.iter().rev() {
+            let now = self.lints.get_level_source(lint_id);
+            if now == set {
+                self.level_stack.push((lint_id, now));
+                self.lints.set_level(lint_id, replaced);
+                pushed += 1;
+            }
+        }
+        if let Some(index) = index {
+            let levels = self.lints.path_levels[index].1.clone();
+            for &(lint_id, level, span) in levels.iter() {
+                // The command line takes precedence over the config file.
+                if self.lints.get_level_source(lint_id).1 == CommandLine {
+                    continue;
+                }
+                if self.push_level(lint_id, level, ConfigFile(span), span) {
+                    let replaced = self.level_stack.last().unwrap().1;
+                    self.path_pushed.push((lint_id, (level, ConfigFile(span)), replaced));
+                    pushed += 1;
+                }
+            }
+        }
+        let outer = mem::replace(&mut self.current_path_levels, index);
+
+        f(self);
+
+        self.current_path_levels = outer;
+        self.path_pushed = outer_pushed;
+        for _ in range(0, pushed) {
+            let (lint, lvlsrc) = self.level_stack.pop().unwrap();
+            self.lints.set_level(lint, lvlsrc);
+        }
+    }
+
     fn visit_ids<F>(&mut self, f: F) where
         F: FnOnce(&mut ast_util::IdVisitor<Context>)
     {
@@ -589,10 +783,12 @@ impl<'a, 'tcx> Context<'a, 'tcx> {
 
 impl<'a, 'tcx, 'v> Visitor<'v> for Context<'a, 'tcx> {
     fn visit_item(&mut self, it: &ast::Item) {
-        self.with_lint_attrs(&it.attrs[], |cx| {
-            run_lints!(cx, check_item, it);
-            cx.visit_ids(|v| v.visit_item(it));
-            visit::walk_item(cx, it);
+        self.with_path_levels(it.span, |cx| {
+            cx.with_lint_attrs(&it.attrs[], |cx| {
+                run_lints!(cx, check_item, it);
+                cx.visit_ids(|v| v.visit_item(it));
+                visit::walk_item(cx, it);
+            })
         })
     }
 
@@ -763,8 +959,8 @@ impl<'a, 'tcx> IdVisitingOperation for Context<'a, 'tcx> {
         match self.tcx.sess.lints.borrow_mut().remove(&id) {
             None => {}
             Some(lints) => {
-                for (lint_id, span, msg) in lints.into_iter() {
-                    self.span_lint(lint_id.lint, span, &msg[])
+                for (lint_id, span, msg, suggestion) in lints.into_iter() {
+                    self.lookup_and_emit(lint_id.lint, Some(span), &msg[], suggestion);
                 }
             }
         }
@@ -831,7 +1027,7 @@ pub fn check_crate(tcx: &ty::ctxt,
     // If we missed any lints added to the session, then there's a bug somewhere
     // in the iteration code.
     for (id, v) in tcx.sess.lints.borrow().iter() {
-        for &(lint, span, ref msg) in v.iter() {
+        for &(lint, span, ref msg, _) in v.iter() {
             tcx.sess.span_bug(span,
                               format!("unprocessed lint {} at {}: {}",
                                       lint.as_str(), tcx.map.node_to_string(*id), *msg).as_slice())
@@ -249,12 +249,30 @@ pub enum LintSource {
     /// Lint level was set by a command-line flag.
     CommandLine,
 
+    /// Lint level was set by a lint config file.
+    ConfigFile(Span),
+
     /// Lint level was set by the release channel.
     ReleaseChannel
 }
 
 pub type LevelSource = (Level, LintSource);
 
+/// A fix for the problem reported by a lint. It is shown (and with
+/// `-Z apply-suggestions` applied) whenever the lint is emitted, so it has to
+/// be correct whenever the lint is.
+#[derive(Clone)]
+pub struct LintSuggestion {
+    /// The code to replace.
+    pub span: Span,
+    /// The help message introducing the suggestion.
+    pub msg: String,
+    /// The code to replace it with.
+    pub replacement: String,
+}
+
 pub mod builtin;
 
+pub mod config_file;
+
 mod context;
@@ -39,16 +39,11 @@ pub const tag_items_data_parent_item: uint = 0x0a;
 
 pub const tag_items_data_item_is_tuple_struct_ctor: uint = 0x0b;
 
+// An index of the items in a doc: pairs of big-endian u32s, the node id of an
+// item and the position of its doc, sorted by node id. Tags 0x0d-0x10 used
+// to belong to the hash table this replaced.
 pub const tag_index: uint = 0x0c;
 
-pub const tag_index_buckets: uint = 0x0d;
-
-pub const tag_index_buckets_bucket: uint = 0x0e;
-
-pub const tag_index_buckets_bucket_elt: uint = 0x0f;
-
-pub const tag_index_table: uint = 0x10;
-
 pub const tag_meta_item_name_value: uint = 0x11;
 
 pub const tag_meta_item_name: uint = 0x12;
@@ -265,3 +260,5 @@ pub const tag_polarity: uint = 0xb4;
 pub const tag_macro_defs: uint = 0xb5;
 pub const tag_macro_def: uint = 0xb6;
 pub const tag_macro_def_body: uint = 0xb7;
+
+pub const tag_items_data_item_deprecation: uint = 0xb8;
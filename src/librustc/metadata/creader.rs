@@ -20,7 +20,9 @@ use metadata::cstore::{CStore, CrateSource, MetadataBlob};
 use metadata::decoder;
 use metadata::loader;
 use metadata::loader::CratePaths;
+use util::nodemap::NodeSet;
 
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use syntax::ast;
 use syntax::abi;
@@ -378,6 +380,9 @@ impl<'a> CrateReader<'a> {
             cnum_map: cnum_map,
             cnum: cnum,
             span: span,
+            record_lookups: self.sess.meta_stats(),
+            item_lookups: Cell::new(0),
+            items_looked_up: RefCell::new(NodeSet::new()),
         });
 
         let source = cstore::CrateSource {
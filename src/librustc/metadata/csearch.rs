@@ -376,6 +376,13 @@ pub fn get_stability(cstore: &cstore::CStore,
     decoder::get_stability(&*cdata, def.node)
 }
 
+pub fn get_deprecation(cstore: &cstore::CStore,
+                       def: ast::DefId)
+                       -> Option<attr::Deprecation> {
+    let cdata = cstore.get_crate_data(def.krate);
+    decoder::get_deprecation(&*cdata, def.node)
+}
+
 pub fn is_staged_api(cstore: &cstore::CStore, def: ast::DefId) -> bool {
     let cdata = cstore.get_crate_data(def.krate);
     let attrs = decoder::get_crate_attributes(cdata.data());
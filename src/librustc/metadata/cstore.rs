@@ -20,9 +20,9 @@ pub use self::NativeLibraryKind::*;
 use back::svh::Svh;
 use metadata::decoder;
 use metadata::loader;
-use util::nodemap::{FnvHashMap, NodeMap};
+use util::nodemap::{FnvHashMap, NodeMap, NodeSet};
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use flate::Bytes;
 use syntax::ast;
@@ -38,6 +38,8 @@ pub type cnum_map = FnvHashMap<ast::CrateNum, ast::CrateNum>;
 pub enum MetadataBlob {
     MetadataVec(Bytes),
     MetadataArchive(loader::ArchiveMetadata),
+    /// Uncompressed metadata read straight out of a dylib.
+    MetadataObject(loader::ObjectMetadata),
 }
 
 pub struct crate_metadata {
@@ -46,6 +48,11 @@ pub struct crate_metadata {
     pub cnum_map: cnum_map,
     pub cnum: ast::CrateNum,
     pub span: Span,
+    /// How often items were looked up in the metadata, and which distinct
+    /// items were, for `-Z meta-stats`. Lookups are only recorded if `record_lookups` is set.
+    pub record_lookups: bool,
+    pub item_lookups: Cell<uint>,
+    pub items_looked_up: RefCell<NodeSet>,
 }
 
 #[derive(Copy, Show, PartialEq, Clone)]
@@ -117,6 +124,23 @@ impl CStore {
         }
     }
 
+    /// Prints how many of the items in the metadata of each crate were
+    /// looked up, for `-Z meta-stats`.
+    pub fn print_lookup_stats(&self) {
+        let metas = self.metas.borrow();
+        let mut cnums = metas.keys().map(|&k| k).collect::<Vec<_>>();
+        cnums.sort();
+        println!("metadata lookup stats:");
+        for cnum in cnums.iter() {
+            let cdata = metas.get(cnum).unwrap();
+            println!("{:>22}: {} of {} items looked up ({} lookups)",
+                     cdata.name,
+                     cdata.items_looked_up.borrow().len(),
+                     decoder::get_item_count(cdata.data()),
+                     cdata.item_lookups.get());
+        }
+    }
+
     /// Like `iter_crate_data`, but passes source paths (if available) as well.
     pub fn iter_crate_data_origins<I>(&self, mut i: I) where
         I: FnMut(ast::CrateNum, &crate_metadata, Option<CrateSource>),
@@ -225,6 +249,14 @@ impl crate_metadata {
     pub fn data<'a>(&'a self) -> &'a [u8] { self.data.as_slice() }
     pub fn name(&self) -> String { decoder::get_crate_name(self.data()) }
     pub fn hash(&self) -> Svh { decoder::get_crate_hash(self.data()) }
+
+    pub fn record_item_lookup(&self, id: ast::NodeId) {
+        if !self.record_lookups {
+            return;
+        }
+        self.item_lookups.set(self.item_lookups.get() + 1);
+        self.items_looked_up.borrow_mut().insert(id);
+    }
 }
 
 impl MetadataBlob {
@@ -232,6 +264,7 @@ impl MetadataBlob {
         let slice = match *self {
             MetadataVec(ref vec) => vec.as_slice(),
             MetadataArchive(ref ar) => ar.as_slice(),
+            MetadataObject(ref obj) => obj.as_slice(),
         };
         if slice.len() < 4 {
             &[] // corrupt metadata
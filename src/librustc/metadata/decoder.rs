@@ -33,7 +33,6 @@ use middle::ty::{self, Ty};
 use middle::astencode::vtable_decoder_helpers;
 
 use std::collections::HashMap;
-use std::hash::{self, Hash, SipHasher};
 use std::io::extensions::u64_from_be_bytes;
 use std::io;
 use std::num::FromPrimitive;
@@ -43,6 +42,7 @@ use std::str;
 use rbml::reader;
 use rbml;
 use serialize::Decodable;
+use serialize::json;
 use syntax::ast_map;
 use syntax::attr;
 use syntax::parse::token::{IdentInterner, special_idents};
@@ -60,40 +60,35 @@ pub type Cmd<'a> = &'a crate_metadata;
 // what crate that's in and give us a def_id that makes sense for the current
 // build.
 
-fn lookup_hash<'a, F>(d: rbml::Doc<'a>, mut eq_fn: F, hash: u64) -> Option<rbml::Doc<'a>> where
-    F: FnMut(&[u8]) -> bool,
-{
-    let index = reader::get_doc(d, tag_index);
-    let table = reader::get_doc(index, tag_index_table);
-    let hash_pos = table.start + (hash % 256 * 4) as uint;
-    let pos = u64_from_be_bytes(d.data, hash_pos, 4) as uint;
-    let tagged_doc = reader::doc_at(d.data, pos).unwrap();
-
-    let belt = tag_index_buckets_bucket_elt;
+pub fn maybe_find_item<'a>(item_id: ast::NodeId,
+                           items: rbml::Doc<'a>) -> Option<rbml::Doc<'a>> {
+    let index = reader::get_doc(items, tag_index);
+    let entry_at = |&: i: uint| {
+        let pos = index.start + i * 8;
+        (u64_from_be_bytes(index.data, pos, 4) as ast::NodeId,
+         u64_from_be_bytes(index.data, pos + 4, 4) as uint)
+    };
 
-    let mut ret = None;
-    reader::tagged_docs(tagged_doc.doc, belt, |elt| {
-        let pos = u64_from_be_bytes(elt.data, elt.start, 4) as uint;
-        if eq_fn(&elt.data[(elt.start + 4) .. elt.end]) {
-            ret = Some(reader::doc_at(d.data, pos).unwrap().doc);
-            false
+    // The entries are sorted by node id.
+    let (mut lo, mut hi) = (0u, (index.end - index.start) / 8);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let (id, pos) = entry_at(mid);
+        if id == item_id {
+            return Some(reader::doc_at(items.data, pos).unwrap().doc);
+        } else if id < item_id {
+            lo = mid + 1;
         } else {
-            true
+            hi = mid;
         }
-    });
-    ret
+    }
+    None
 }
 
-pub fn maybe_find_item<'a>(item_id: ast::NodeId,
-                           items: rbml::Doc<'a>) -> Option<rbml::Doc<'a>> {
-    fn eq_item(bytes: &[u8], item_id: ast::NodeId) -> bool {
-        return u64_from_be_bytes(
-            &bytes[0u..4u], 0u, 4u) as ast::NodeId
-            == item_id;
-    }
-    lookup_hash(items,
-                |a| eq_item(a, item_id),
-                hash::hash::<i64, SipHasher>(&(item_id as i64)))
+/// The number of items in the index of `items`.
+fn item_count(items: rbml::Doc) -> uint {
+    let index = reader::get_doc(items, tag_index);
+    (index.end - index.start) / 8
 }
 
 fn find_item<'a>(item_id: ast::NodeId, items: rbml::Doc<'a>) -> rbml::Doc<'a> {
@@ -103,13 +98,19 @@ fn find_item<'a>(item_id: ast::NodeId, items: rbml::Doc<'a>) -> rbml::Doc<'a> {
     }
 }
 
-// Looks up an item in the given metadata and returns an rbml doc pointing
-// to the item data.
-fn lookup_item<'a>(item_id: ast::NodeId, data: &'a [u8]) -> rbml::Doc<'a> {
-    let items = reader::get_doc(rbml::Doc::new(data), tag_items);
+// Looks up an item in the metadata of the given crate and returns an rbml doc
+// pointing to the item data. Only the index and the item itself are read.
+fn lookup_item<'a>(item_id: ast::NodeId, cdata: Cmd<'a>) -> rbml::Doc<'a> {
+    cdata.record_item_lookup(item_id);
+    let items = reader::get_doc(rbml::Doc::new(cdata.data()), tag_items);
     find_item(item_id, items)
 }
 
+/// The number of items in the metadata `data`.
+pub fn get_item_count(data: &[u8]) -> uint {
+    item_count(reader::get_doc(rbml::Doc::new(data), tag_items))
+}
+
 #[derive(PartialEq)]
 enum Family {
     ImmStatic,             // c
@@ -386,7 +387,7 @@ pub fn get_trait_def<'tcx>(cdata: Cmd,
                            item_id: ast::NodeId,
                            tcx: &ty::ctxt<'tcx>) -> ty::TraitDef<'tcx>
 {
-    let item_doc = lookup_item(item_id, cdata.data());
+    let item_doc = lookup_item(item_id, cdata);
     let generics = doc_generics(item_doc, tcx, cdata, tag_item_generics);
     let bounds = trait_def_bounds(item_doc, tcx, cdata);
     let unsafety = parse_unsafety(item_doc);
@@ -404,7 +405,7 @@ pub fn get_trait_def<'tcx>(cdata: Cmd,
 pub fn get_type<'tcx>(cdata: Cmd, id: ast::NodeId, tcx: &ty::ctxt<'tcx>)
     -> ty::TypeScheme<'tcx> {
 
-    let item = lookup_item(id, cdata.data());
+    let item = lookup_item(id, cdata);
 
     let t = item_type(ast::DefId { krate: cdata.cnum, node: id }, item, tcx,
                       cdata);
@@ -418,15 +419,23 @@ pub fn get_type<'tcx>(cdata: Cmd, id: ast::NodeId, tcx: &ty::ctxt<'tcx>)
 }
 
 pub fn get_stability(cdata: Cmd, id: ast::NodeId) -> Option<attr::Stability> {
-    let item = lookup_item(id, cdata.data());
+    let item = lookup_item(id, cdata);
     reader::maybe_get_doc(item, tag_items_data_item_stability).map(|doc| {
         let mut decoder = reader::Decoder::new(doc);
         Decodable::decode(&mut decoder).unwrap()
     })
 }
 
+pub fn get_deprecation(cdata: Cmd, id: ast::NodeId) -> Option<attr::Deprecation> {
+    let item = lookup_item(id, cdata);
+    reader::maybe_get_doc(item, tag_items_data_item_deprecation).map(|doc| {
+        let mut decoder = reader::Decoder::new(doc);
+        Decodable::decode(&mut decoder).unwrap()
+    })
+}
+
 pub fn get_repr_attrs(cdata: Cmd, id: ast::NodeId) -> Vec<attr::ReprAttr> {
-    let item = lookup_item(id, cdata.data());
+    let item = lookup_item(id, cdata);
     match reader::maybe_get_doc(item, tag_items_data_item_repr).map(|doc| {
         let mut decoder = reader::Decoder::new(doc);
         Decodable::decode(&mut decoder).unwrap()
@@ -441,7 +450,7 @@ pub fn get_impl_trait<'tcx>(cdata: Cmd,
                             tcx: &ty::ctxt<'tcx>)
                             -> Option<Rc<ty::TraitRef<'tcx>>>
 {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     let fam = item_family(item_doc);
     match fam {
         Family::Impl => {
@@ -458,7 +467,7 @@ pub fn get_impl_vtables<'tcx>(cdata: Cmd,
                               tcx: &ty::ctxt<'tcx>)
                               -> ty::vtable_res<'tcx>
 {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     let vtables_doc = reader::get_doc(item_doc, tag_item_impl_vtables);
     let mut decoder = reader::Decoder::new(vtables_doc);
     decoder.read_vtable_res(tcx, cdata)
@@ -466,7 +475,8 @@ pub fn get_impl_vtables<'tcx>(cdata: Cmd,
 
 
 pub fn get_symbol(data: &[u8], id: ast::NodeId) -> String {
-    return item_symbol(lookup_item(id, data));
+    let items = reader::get_doc(rbml::Doc::new(data), tag_items);
+    return item_symbol(find_item(id, items));
 }
 
 // Something that a name can resolve to.
@@ -672,7 +682,7 @@ pub fn maybe_get_item_ast<'tcx>(cdata: Cmd, tcx: &ty::ctxt<'tcx>, id: ast::NodeI
                                 mut decode_inlined_item: DecodeInlinedItem)
                                 -> csearch::found_ast<'tcx> {
     debug!("Looking up item: {}", id);
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     let path = item_path(item_doc).init().to_vec();
     match decode_inlined_item(cdata, tcx, path, item_doc) {
         Ok(ii) => csearch::found(ii),
@@ -680,7 +690,7 @@ pub fn maybe_get_item_ast<'tcx>(cdata: Cmd, tcx: &ty::ctxt<'tcx>, id: ast::NodeI
             match item_parent_item(item_doc) {
                 Some(did) => {
                     let did = translate_def_id(cdata, did);
-                    let parent_item = lookup_item(did.node, cdata.data());
+                    let parent_item = lookup_item(did.node, cdata);
                     match decode_inlined_item(cdata, tcx, path, parent_item) {
                         Ok(ii) => csearch::found_parent(did, ii),
                         Err(_) => csearch::not_found
@@ -696,11 +706,9 @@ pub fn get_enum_variant_defs(intr: &IdentInterner,
                              cdata: Cmd,
                              id: ast::NodeId)
                              -> Vec<(def::Def, ast::Name, ast::Visibility)> {
-    let data = cdata.data();
-    let items = reader::get_doc(rbml::Doc::new(data), tag_items);
-    let item = find_item(id, items);
+    let item = lookup_item(id, cdata);
     enum_variant_ids(item, cdata).iter().map(|did| {
-        let item = find_item(did.node, items);
+        let item = lookup_item(did.node, cdata);
         let name = item_name(intr, item);
         let visibility = item_visibility(item);
         match item_to_def_like(item, *did, cdata.cnum) {
@@ -712,12 +720,10 @@ pub fn get_enum_variant_defs(intr: &IdentInterner,
 
 pub fn get_enum_variants<'tcx>(intr: Rc<IdentInterner>, cdata: Cmd, id: ast::NodeId,
                                tcx: &ty::ctxt<'tcx>) -> Vec<Rc<ty::VariantInfo<'tcx>>> {
-    let data = cdata.data();
-    let items = reader::get_doc(rbml::Doc::new(data), tag_items);
-    let item = find_item(id, items);
+    let item = lookup_item(id, cdata);
     let mut disr_val = 0;
     enum_variant_ids(item, cdata).iter().map(|did| {
-        let item = find_item(did.node, items);
+        let item = lookup_item(did.node, cdata);
         let ctor_ty = item_type(ast::DefId { krate: cdata.cnum, node: id},
                                 item, tcx, cdata);
         let name = item_name(&*intr, item);
@@ -807,7 +813,7 @@ pub fn get_trait_name(intr: Rc<IdentInterner>,
                       cdata: Cmd,
                       id: ast::NodeId)
                       -> ast::Name {
-    let doc = lookup_item(id, cdata.data());
+    let doc = lookup_item(id, cdata);
     item_name(&*intr, doc)
 }
 
@@ -815,7 +821,7 @@ pub fn get_trait_item_name_and_kind(intr: Rc<IdentInterner>,
                                     cdata: Cmd,
                                     id: ast::NodeId)
                                     -> (ast::Name, def::TraitItemKind) {
-    let doc = lookup_item(id, cdata.data());
+    let doc = lookup_item(id, cdata);
     let name = item_name(&*intr, doc);
     match item_sort(doc) {
         'r' | 'p' => {
@@ -835,13 +841,13 @@ pub fn get_impl_or_trait_item<'tcx>(intr: Rc<IdentInterner>,
                                     id: ast::NodeId,
                                     tcx: &ty::ctxt<'tcx>)
                                     -> ty::ImplOrTraitItem<'tcx> {
-    let method_doc = lookup_item(id, cdata.data());
+    let method_doc = lookup_item(id, cdata);
 
     let def_id = item_def_id(method_doc, cdata);
 
     let container_id = item_reqd_and_translated_parent_item(cdata.cnum,
                                                             method_doc);
-    let container_doc = lookup_item(container_id.node, cdata.data());
+    let container_doc = lookup_item(container_id.node, cdata);
     let container = match item_family(container_doc) {
         Trait => TraitContainer(container_id),
         _ => ImplContainer(container_id),
@@ -881,8 +887,7 @@ pub fn get_impl_or_trait_item<'tcx>(intr: Rc<IdentInterner>,
 
 pub fn get_trait_item_def_ids(cdata: Cmd, id: ast::NodeId)
                               -> Vec<ty::ImplOrTraitItemId> {
-    let data = cdata.data();
-    let item = lookup_item(id, data);
+    let item = lookup_item(id, cdata);
     let mut result = Vec::new();
     reader::tagged_docs(item, tag_item_trait_item, |mth| {
         let def_id = item_def_id(mth, cdata);
@@ -897,8 +902,7 @@ pub fn get_trait_item_def_ids(cdata: Cmd, id: ast::NodeId)
 }
 
 pub fn get_item_variances(cdata: Cmd, id: ast::NodeId) -> ty::ItemVariances {
-    let data = cdata.data();
-    let item_doc = lookup_item(id, data);
+    let item_doc = lookup_item(id, cdata);
     let variance_doc = reader::get_doc(item_doc, tag_item_variances);
     let mut decoder = reader::Decoder::new(variance_doc);
     Decodable::decode(&mut decoder).unwrap()
@@ -909,13 +913,12 @@ pub fn get_provided_trait_methods<'tcx>(intr: Rc<IdentInterner>,
                                         id: ast::NodeId,
                                         tcx: &ty::ctxt<'tcx>)
                                         -> Vec<Rc<ty::Method<'tcx>>> {
-    let data = cdata.data();
-    let item = lookup_item(id, data);
+    let item = lookup_item(id, cdata);
     let mut result = Vec::new();
 
     reader::tagged_docs(item, tag_item_trait_item, |mth_id| {
         let did = item_def_id(mth_id, cdata);
-        let mth = lookup_item(did.node, data);
+        let mth = lookup_item(did.node, cdata);
 
         if item_sort(mth) == 'p' {
             let trait_item = get_impl_or_trait_item(intr.clone(),
@@ -939,7 +942,7 @@ pub fn get_provided_trait_methods<'tcx>(intr: Rc<IdentInterner>,
 pub fn get_supertraits<'tcx>(cdata: Cmd, id: ast::NodeId, tcx: &ty::ctxt<'tcx>)
                              -> Vec<Rc<ty::TraitRef<'tcx>>> {
     let mut results = Vec::new();
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     reader::tagged_docs(item_doc, tag_item_super_trait_ref, |trait_doc| {
         // NB. Only reads the ones that *aren't* builtin-bounds. See also
         // get_trait_def() for collecting the builtin bounds.
@@ -955,7 +958,7 @@ pub fn get_supertraits<'tcx>(cdata: Cmd, id: ast::NodeId, tcx: &ty::ctxt<'tcx>)
 
 pub fn get_type_name_if_impl(cdata: Cmd,
                              node_id: ast::NodeId) -> Option<ast::Name> {
-    let item = lookup_item(node_id, cdata.data());
+    let item = lookup_item(node_id, cdata);
     if item_family(item) != Impl {
         return None;
     }
@@ -973,7 +976,7 @@ pub fn get_methods_if_impl(intr: Rc<IdentInterner>,
                                   cdata: Cmd,
                                   node_id: ast::NodeId)
                                -> Option<Vec<MethodInfo> > {
-    let item = lookup_item(node_id, cdata.data());
+    let item = lookup_item(node_id, cdata);
     if item_family(item) != Impl {
         return None;
     }
@@ -993,7 +996,7 @@ pub fn get_methods_if_impl(intr: Rc<IdentInterner>,
 
     let mut impl_methods = Vec::new();
     for impl_method_id in impl_method_ids.iter() {
-        let impl_method_doc = lookup_item(impl_method_id.node, cdata.data());
+        let impl_method_doc = lookup_item(impl_method_id.node, cdata);
         let family = item_family(impl_method_doc);
         match family {
             StaticMethod | Method => {
@@ -1016,7 +1019,7 @@ pub fn get_tuple_struct_definition_if_ctor(cdata: Cmd,
                                            node_id: ast::NodeId)
     -> Option<ast::DefId>
 {
-    let item = lookup_item(node_id, cdata.data());
+    let item = lookup_item(node_id, cdata);
     let mut ret = None;
     reader::tagged_docs(item, tag_items_data_item_is_tuple_struct_ctor, |_| {
         ret = Some(item_reqd_and_translated_parent_item(cdata.cnum, item));
@@ -1035,7 +1038,7 @@ pub fn get_item_attrs<F>(cdata: Cmd,
     // look at the definition
     let node_id = get_tuple_struct_definition_if_ctor(cdata, orig_node_id);
     let node_id = node_id.map(|x| x.node).unwrap_or(orig_node_id);
-    let item = lookup_item(node_id, cdata.data());
+    let item = lookup_item(node_id, cdata);
     f(get_attributes(item));
 }
 
@@ -1062,8 +1065,7 @@ fn struct_field_family_to_visibility(family: Family) -> ast::Visibility {
 
 pub fn get_struct_fields(intr: Rc<IdentInterner>, cdata: Cmd, id: ast::NodeId)
     -> Vec<ty::field_ty> {
-    let data = cdata.data();
-    let item = lookup_item(id, data);
+    let item = lookup_item(id, cdata);
     let mut result = Vec::new();
     reader::tagged_docs(item, tag_item_field, |an_item| {
         let f = item_family(an_item);
@@ -1248,6 +1250,173 @@ pub fn list_crate_metadata(bytes: &[u8], out: &mut io::Writer) -> io::IoResult<(
     list_crate_deps(bytes, out)
 }
 
+// The following data types are provided just for serialisation, by
+// `list_crate_metadata_json`.
+
+#[derive(RustcEncodable)]
+struct CrateSummary {
+    name: String,
+    hash: String,
+    triple: Option<String>,
+    dependencies: Vec<DependencySummary>,
+    /// Public items, in the order they are encoded in.
+    items: Vec<ItemSummary>,
+    lang_items: Vec<LangItemSummary>,
+    native_libraries: Vec<NativeLibrarySummary>,
+    macros: Vec<MacroSummary>,
+    /// Paths of the items whose AST is included for inlining across crates.
+    inlined_items: Vec<String>,
+}
+
+#[derive(RustcEncodable)]
+struct DependencySummary {
+    crate_num: ast::CrateNum,
+    name: String,
+    hash: String,
+}
+
+#[derive(RustcEncodable)]
+struct ItemSummary {
+    node_id: ast::NodeId,
+    path: String,
+    kind: &'static str,
+}
+
+#[derive(RustcEncodable)]
+struct LangItemSummary {
+    name: &'static str,
+    node_id: ast::NodeId,
+}
+
+#[derive(RustcEncodable)]
+struct NativeLibrarySummary {
+    name: String,
+    /// "static", "framework" or "dylib".
+    kind: &'static str,
+}
+
+#[derive(RustcEncodable)]
+struct MacroSummary {
+    name: String,
+    body: String,
+}
+
+fn family_name(family: Family) -> &'static str {
+    match family {
+        Constant => "const",
+        ImmStatic => "static",
+        MutStatic => "static mut",
+        Fn => "fn",
+        CtorFn => "struct constructor",
+        StaticMethod => "static method",
+        Method => "method",
+        Type => "type",
+        ForeignType => "foreign type",
+        Mod => "mod",
+        ForeignMod => "foreign mod",
+        Enum => "enum",
+        TupleVariant | StructVariant => "variant",
+        Impl => "impl",
+        Trait => "trait",
+        Struct => "struct",
+        PublicField | InheritedField => "field",
+    }
+}
+
+/// Dumps what is known about the crate from its metadata `bytes` as a JSON
+/// object, for inspecting libraries whose contents are in question.
+pub fn list_crate_metadata_json(bytes: &[u8], out: &mut io::Writer) -> io::IoResult<()> {
+    let root = rbml::Doc::new(bytes);
+    let name = get_crate_name(bytes);
+
+    let dependencies = get_crate_deps(bytes).into_iter().map(|dep| {
+        DependencySummary {
+            crate_num: dep.cnum,
+            name: dep.name,
+            hash: dep.hash.as_str().to_string(),
+        }
+    }).collect();
+
+    let mut items = Vec::new();
+    let mut inlined_items = Vec::new();
+    let items_data = reader::get_doc(reader::get_doc(root, tag_items), tag_items_data);
+    reader::tagged_docs(items_data, tag_items_data_item, |item_doc| {
+        // The crate root itself has no path.
+        if reader::maybe_get_doc(item_doc, tag_path).is_none() {
+            return true;
+        }
+        let path = item_path(item_doc);
+        let path = if path.is_empty() {
+            name.clone()
+        } else {
+            format!("{}::{}", name, ast_map::path_to_string(path.into_iter()))
+        };
+        if reader::maybe_get_doc(item_doc, tag_ast).is_some() {
+            inlined_items.push(path.clone());
+        }
+        if item_visibility(item_doc) == ast::Public {
+            let def_id = reader::get_doc(item_doc, tag_def_id);
+            items.push(ItemSummary {
+                node_id: reader::with_doc_data(def_id, parse_def_id).node,
+                path: path,
+                kind: family_name(item_family(item_doc)),
+            });
+        }
+        true
+    });
+
+    let mut crate_lang_items = Vec::new();
+    reader::tagged_docs(reader::get_doc(root, tag_lang_items), tag_lang_items_item, |item_doc| {
+        let id = reader::doc_as_u32(reader::get_doc(item_doc, tag_lang_items_item_id));
+        let node_id = reader::doc_as_u32(reader::get_doc(item_doc, tag_lang_items_item_node_id));
+        crate_lang_items.push(LangItemSummary {
+            name: lang_items::LanguageItems::item_name(id as uint),
+            node_id: node_id as ast::NodeId,
+        });
+        true
+    });
+
+    let mut native_libraries = Vec::new();
+    let libraries = reader::get_doc(root, tag_native_libraries);
+    reader::tagged_docs(libraries, tag_native_libraries_lib, |lib_doc| {
+        let kind_doc = reader::get_doc(lib_doc, tag_native_libraries_kind);
+        let name_doc = reader::get_doc(lib_doc, tag_native_libraries_name);
+        let kind: cstore::NativeLibraryKind =
+            FromPrimitive::from_u32(reader::doc_as_u32(kind_doc)).unwrap();
+        native_libraries.push(NativeLibrarySummary {
+            name: name_doc.as_str().to_string(),
+            kind: match kind {
+                cstore::NativeStatic => "static",
+                cstore::NativeFramework => "framework",
+                cstore::NativeUnknown => "dylib",
+            },
+        });
+        true
+    });
+
+    let mut macros = Vec::new();
+    each_exported_macro(bytes, &*token::get_ident_interner(), |name, _, body| {
+        macros.push(MacroSummary {
+            name: token::get_name(name).get().to_string(),
+            body: body,
+        });
+        true
+    });
+
+    let summary = CrateSummary {
+        name: name.clone(),
+        hash: get_crate_hash(bytes).as_str().to_string(),
+        triple: get_crate_triple(bytes),
+        dependencies: dependencies,
+        items: items,
+        lang_items: crate_lang_items,
+        native_libraries: native_libraries,
+        macros: macros,
+        inlined_items: inlined_items,
+    };
+    writeln!(out, "{}", json::as_pretty_json(&summary))
+}
+
 // Translates a def_id from an external crate to a def_id for the current
 // compilation environment. We use this when trying to load types from
 // external crates - if those types further refer to types in other crates
@@ -1284,7 +1453,7 @@ pub fn each_implementation_for_type<F>(cdata: Cmd,
                                        mut callback: F)
     where F: FnMut(ast::DefId),
 {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     reader::tagged_docs(item_doc,
                         tag_items_data_item_inherent_impl,
                         |impl_doc| {
@@ -1299,7 +1468,7 @@ pub fn each_implementation_for_trait<F>(cdata: Cmd,
                                         mut callback: F) where
     F: FnMut(ast::DefId),
 {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
 
     let _ = reader::tagged_docs(item_doc,
                                 tag_items_data_item_extension_impl,
@@ -1312,13 +1481,13 @@ pub fn each_implementation_for_trait<F>(cdata: Cmd,
 
 pub fn get_trait_of_item(cdata: Cmd, id: ast::NodeId, tcx: &ty::ctxt)
                          -> Option<ast::DefId> {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     let parent_item_id = match item_parent_item(item_doc) {
         None => return None,
         Some(item_id) => item_id,
     };
     let parent_item_id = translate_def_id(cdata, parent_item_id);
-    let parent_item_doc = lookup_item(parent_item_id.node, cdata.data());
+    let parent_item_doc = lookup_item(parent_item_id.node, cdata);
     match item_family(parent_item_doc) {
         Trait => Some(item_def_id(parent_item_doc, cdata)),
         Impl => {
@@ -1406,7 +1575,7 @@ pub fn get_missing_lang_items(cdata: Cmd)
 
 pub fn get_method_arg_names(cdata: Cmd, id: ast::NodeId) -> Vec<String> {
     let mut ret = Vec::new();
-    let method_doc = lookup_item(id, cdata.data());
+    let method_doc = lookup_item(id, cdata);
     match reader::maybe_get_doc(method_doc, tag_method_argument_names) {
         Some(args_doc) => {
             reader::tagged_docs(args_doc, tag_method_argument_name, |name_doc| {
@@ -1434,7 +1603,7 @@ pub fn get_reachable_extern_fns(cdata: Cmd) -> Vec<ast::DefId> {
 }
 
 pub fn is_typedef(cdata: Cmd, id: ast::NodeId) -> bool {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     match item_family(item_doc) {
         Type => true,
         _ => false,
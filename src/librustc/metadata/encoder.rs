@@ -29,7 +29,6 @@ use util::nodemap::{FnvHashMap, NodeMap, NodeSet};
 
 use serialize::Encodable;
 use std::cell::RefCell;
-use std::hash::{Hash, Hasher, SipHasher};
 use syntax::abi;
 use syntax::ast::{self, DefId, NodeId};
 use syntax::ast_map::{PathElem, PathElems};
@@ -336,6 +335,8 @@ fn encode_enum_variant_info(ecx: &EncodeContext,
 
         let stab = stability::lookup(ecx.tcx, ast_util::local_def(variant.node.id));
         encode_stability(rbml_w, stab);
+        let depr = stability::lookup_deprecation(ecx.tcx, ast_util::local_def(variant.node.id));
+        encode_deprecation(rbml_w, depr);
 
         match variant.node.kind {
             ast::TupleVariantKind(_) => {},
@@ -346,7 +347,7 @@ fn encode_enum_variant_info(ecx: &EncodeContext,
                                                  &fields[],
                                                  index);
                 encode_struct_fields(rbml_w, &fields[], def_id);
-                encode_index(rbml_w, idx, write_i64);
+                encode_index(rbml_w, idx);
             }
         }
         if (*vi)[i].disr_val != disr_val {
@@ -589,6 +590,8 @@ fn encode_info_for_mod(ecx: &EncodeContext,
 
     let stab = stability::lookup(ecx.tcx, ast_util::local_def(id));
     encode_stability(rbml_w, stab);
+    let depr = stability::lookup_deprecation(ecx.tcx, ast_util::local_def(id));
+    encode_deprecation(rbml_w, depr);
 
     // Encode the reexports of this module, if this module is public.
     if vis == ast::Public {
@@ -716,6 +719,8 @@ fn encode_info_for_struct(ecx: &EncodeContext,
 
         let stab = stability::lookup(ecx.tcx, field.id);
         encode_stability(rbml_w, stab);
+        let depr = stability::lookup_deprecation(ecx.tcx, field.id);
+        encode_deprecation(rbml_w, depr);
 
         rbml_w.end_tag();
     }
@@ -748,6 +753,8 @@ fn encode_info_for_struct_ctor(ecx: &EncodeContext,
 
     let stab = stability::lookup(ecx.tcx, ast_util::local_def(ctor_id));
     encode_stability(rbml_w, stab);
+    let depr = stability::lookup_deprecation(ecx.tcx, ast_util::local_def(ctor_id));
+    encode_deprecation(rbml_w, depr);
 
     // indicate that this is a tuple struct ctor, because downstream users will normally want
     // the tuple struct definition, but without this there is no way for them to tell that
@@ -854,6 +861,8 @@ fn encode_info_for_method<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
 
     let stab = stability::lookup(ecx.tcx, m.def_id);
     encode_stability(rbml_w, stab);
+    let depr = stability::lookup_deprecation(ecx.tcx, m.def_id);
+    encode_deprecation(rbml_w, depr);
 
     // The type for methods gets encoded twice, which is unfortunate.
     let pty = lookup_item_type(ecx.tcx, m.def_id);
@@ -904,6 +913,8 @@ fn encode_info_for_associated_type(ecx: &EncodeContext,
 
     let stab = stability::lookup(ecx.tcx, associated_type.def_id);
     encode_stability(rbml_w, stab);
+    let depr = stability::lookup_deprecation(ecx.tcx, associated_type.def_id);
+    encode_deprecation(rbml_w, depr);
 
     let elem = ast_map::PathName(associated_type.name);
     encode_path(rbml_w, impl_path.chain(Some(elem).into_iter()));
@@ -1007,6 +1018,14 @@ fn encode_stability(rbml_w: &mut Encoder, stab_opt: Option<attr::Stability>) {
     });
 }
 
+fn encode_deprecation(rbml_w: &mut Encoder, depr_opt: Option<attr::Deprecation>) {
+    depr_opt.map(|depr| {
+        rbml_w.start_tag(tag_items_data_item_deprecation);
+        depr.encode(rbml_w).unwrap();
+        rbml_w.end_tag();
+    });
+}
+
 fn encode_info_for_item(ecx: &EncodeContext,
                         rbml_w: &mut Encoder,
                         item: &ast::Item,
@@ -1028,6 +1047,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
 
     let def_id = local_def(item.id);
     let stab = stability::lookup(tcx, ast_util::local_def(item.id));
+    let depr = stability::lookup_deprecation(tcx, ast_util::local_def(item.id));
 
     match item.node {
       ast::ItemStatic(_, m, _) => {
@@ -1045,6 +1065,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_path(rbml_w, path);
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         encode_attributes(rbml_w, &item.attrs[]);
         rbml_w.end_tag();
       }
@@ -1060,6 +1081,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_inlined_item(ecx, rbml_w, IIItemRef(item));
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         rbml_w.end_tag();
       }
       ast::ItemFn(ref decl, _, _, ref generics, _) => {
@@ -1072,7 +1094,10 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_name(rbml_w, item.ident.name);
         encode_path(rbml_w, path);
         encode_attributes(rbml_w, &item.attrs[]);
-        if tps_len > 0u || should_inline(&item.attrs[]) {
+        // The bodies of constant functions are needed to evaluate calls to
+        // them in the constant expressions of other crates.
+        if tps_len > 0u || should_inline(&item.attrs[]) ||
+           attr::contains_name(&item.attrs[], "const_fn") {
             encode_inlined_item(ecx, rbml_w, IIItemRef(item));
         }
         if tps_len == 0 {
@@ -1080,6 +1105,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         }
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         encode_method_argument_names(rbml_w, &**decl);
         rbml_w.end_tag();
       }
@@ -1110,6 +1136,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         }
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         rbml_w.end_tag();
       }
       ast::ItemTy(..) => {
@@ -1122,6 +1149,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_path(rbml_w, path);
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         rbml_w.end_tag();
       }
       ast::ItemEnum(ref enum_definition, _) => {
@@ -1146,6 +1174,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
 
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         rbml_w.end_tag();
 
         encode_enum_variant_info(ecx,
@@ -1180,6 +1209,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_attributes(rbml_w, &item.attrs[]);
         encode_path(rbml_w, path.clone());
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         encode_visibility(rbml_w, vis);
         encode_repr_attrs(rbml_w, ecx, &item.attrs[]);
 
@@ -1194,7 +1224,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_inherent_implementations(ecx, rbml_w, def_id);
 
         /* Each class has its own index -- encode it */
-        encode_index(rbml_w, idx, write_i64);
+        encode_index(rbml_w, idx);
         rbml_w.end_tag();
 
         // If this is a tuple-like struct, encode the type of the constructor.
@@ -1249,6 +1279,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         }
         encode_path(rbml_w, path.clone());
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         rbml_w.end_tag();
 
         // Iterate down the trait items, emitting them. We rely on the
@@ -1325,6 +1356,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_attributes(rbml_w, &item.attrs[]);
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         for &method_def_id in ty::trait_item_def_ids(tcx, def_id).iter() {
             rbml_w.start_tag(tag_item_trait_item);
             match method_def_id {
@@ -1371,6 +1403,8 @@ fn encode_info_for_item(ecx: &EncodeContext,
 
             let stab = stability::lookup(tcx, item_def_id.def_id());
             encode_stability(rbml_w, stab);
+            let depr = stability::lookup_deprecation(tcx, item_def_id.def_id());
+            encode_deprecation(rbml_w, depr);
 
             let trait_item_type =
                 ty::impl_or_trait_item(tcx, item_def_id.def_id());
@@ -1488,6 +1522,8 @@ fn encode_info_for_foreign_item(ecx: &EncodeContext,
         encode_attributes(rbml_w, &*nitem.attrs);
         let stab = stability::lookup(ecx.tcx, ast_util::local_def(nitem.id));
         encode_stability(rbml_w, stab);
+        let depr = stability::lookup_deprecation(ecx.tcx, ast_util::local_def(nitem.id));
+        encode_deprecation(rbml_w, depr);
         encode_symbol(ecx, rbml_w, nitem.id);
       }
       ast::ForeignItemStatic(_, mutbl) => {
@@ -1501,6 +1537,8 @@ fn encode_info_for_foreign_item(ecx: &EncodeContext,
         encode_attributes(rbml_w, &*nitem.attrs);
         let stab = stability::lookup(ecx.tcx, ast_util::local_def(nitem.id));
         encode_stability(rbml_w, stab);
+        let depr = stability::lookup_deprecation(ecx.tcx, ast_util::local_def(nitem.id));
+        encode_deprecation(rbml_w, depr);
         encode_symbol(ecx, rbml_w, nitem.id);
         encode_name(rbml_w, nitem.ident.name);
       }
@@ -1595,51 +1633,18 @@ fn encode_info_for_items(ecx: &EncodeContext,
 
 // Path and definition ID indexing
 
-fn encode_index<T, F>(rbml_w: &mut Encoder, index: Vec<entry<T>>, mut write_fn: F) where
-    F: FnMut(&mut SeekableMemWriter, &T),
-    T: Hash<SipHasher>,
-{
-    let mut buckets: Vec<Vec<entry<T>>> = range(0, 256u16).map(|_| Vec::new()).collect();
-    for elt in index.into_iter() {
-        let mut s = SipHasher::new();
-        elt.val.hash(&mut s);
-        let h = s.finish() as uint;
-        (&mut buckets[h % 256]).push(elt);
-    }
-
+fn encode_index(rbml_w: &mut Encoder, mut index: Vec<entry<i64>>) {
+    // Sorted, so that items can be looked up with a binary search without
+    // decoding anything else.
+    index.sort_by(|a, b| a.val.cmp(&b.val));
     rbml_w.start_tag(tag_index);
-    let mut bucket_locs = Vec::new();
-    rbml_w.start_tag(tag_index_buckets);
-    for bucket in buckets.iter() {
-        bucket_locs.push(rbml_w.writer.tell().unwrap());
-        rbml_w.start_tag(tag_index_buckets_bucket);
-        for elt in bucket.iter() {
-            rbml_w.start_tag(tag_index_buckets_bucket_elt);
-            assert!(elt.pos < 0xffff_ffff);
-            {
-                let wr: &mut SeekableMemWriter = rbml_w.writer;
-                wr.write_be_u32(elt.pos as u32);
-            }
-            write_fn(rbml_w.writer, &elt.val);
-            rbml_w.end_tag();
-        }
-        rbml_w.end_tag();
-    }
-    rbml_w.end_tag();
-    rbml_w.start_tag(tag_index_table);
-    for pos in bucket_locs.iter() {
-        assert!(*pos < 0xffff_ffff);
+    for elt in index.iter() {
+        assert!(elt.val < 0x7fff_ffff && elt.pos < 0xffff_ffff);
         let wr: &mut SeekableMemWriter = rbml_w.writer;
-        wr.write_be_u32(*pos as u32);
+        wr.write_be_u32(elt.val as u32);
+        wr.write_be_u32(elt.pos as u32);
     }
     rbml_w.end_tag();
-    rbml_w.end_tag();
-}
-
-fn write_i64(writer: &mut SeekableMemWriter, &n: &i64) {
-    let wr: &mut SeekableMemWriter = writer;
-    assert!(n < 0x7fff_ffff);
-    wr.write_be_u32(n as u32);
 }
 
 fn encode_meta_item(rbml_w: &mut Encoder, mi: &ast::MetaItem) {
@@ -2026,7 +2031,12 @@ fn encode_dylib_dependency_formats(rbml_w: &mut Encoder, ecx: &EncodeContext) {
 
 // NB: Increment this as you change the metadata encoding version.
 #[allow(non_upper_case_globals)]
-pub const metadata_encoding_version : &'static [u8] = &[b'r', b'u', b's', b't', 0, 0, 0, 1 ];
+pub const metadata_encoding_version : &'static [u8] = &[b'r', b'u', b's', b't', 0, 0, 0, 2 ];
+
+// In the metadata section of dylibs, the version is followed by one of these,
+// saying whether the rest of the section is compressed.
+pub const METADATA_UNCOMPRESSED: u8 = 0;
+pub const METADATA_DEFLATED: u8 = 1;
 
 pub fn encode_metadata(parms: EncodeParams, krate: &ast::Crate) -> Vec<u8> {
     let mut wr = SeekableMemWriter::new();
@@ -2177,7 +2187,7 @@ fn encode_metadata_inner(wr: &mut SeekableMemWriter,
     stats.item_bytes = rbml_w.writer.tell().unwrap() - i;
 
     i = rbml_w.writer.tell().unwrap();
-    encode_index(&mut rbml_w, items_index, write_i64);
+    encode_index(&mut rbml_w, items_index);
     stats.index_bytes = rbml_w.writer.tell().unwrap() - i;
     rbml_w.end_tag();
 
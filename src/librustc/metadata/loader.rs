@@ -218,7 +218,7 @@ use session::Session;
 use llvm;
 use llvm::{False, ObjectFile, mk_section_iter};
 use llvm::archive_ro::ArchiveRO;
-use metadata::cstore::{MetadataBlob, MetadataVec, MetadataArchive};
+use metadata::cstore::{MetadataBlob, MetadataVec, MetadataArchive, MetadataObject};
 use metadata::decoder;
 use metadata::encoder;
 use metadata::filesearch::{FileSearch, FileMatches, FileDoesntMatch};
@@ -271,6 +271,12 @@ pub struct ArchiveMetadata {
     data: *const [u8],
 }
 
+pub struct ObjectMetadata {
+    _object: ObjectFile,
+    // points into the metadata section of self._object
+    data: *const [u8],
+}
+
 pub struct CratePaths {
     pub ident: String,
     pub dylib: Option<Path>,
@@ -688,6 +694,10 @@ impl ArchiveMetadata {
     pub fn as_slice<'a>(&'a self) -> &'a [u8] { unsafe { &*self.data } }
 }
 
+impl ObjectMetadata {
+    pub fn as_slice<'a>(&'a self) -> &'a [u8] { unsafe { &*self.data } }
+}
+
 // Just a small wrapper to time how long reading metadata takes.
 fn get_metadata_section(is_osx: bool, filename: &Path) -> Result<MetadataBlob, String> {
     let mut ret = None;
@@ -760,13 +770,35 @@ fn get_metadata_section_imp(is_osx: bool, filename: &Path) -> Result<MetadataBlo
                                         filename.display())));
                 }
 
-                let cvbuf1 = cvbuf.offset(vlen as int);
-                debug!("inflating {} bytes of compressed metadata",
-                       csz - vlen);
-                let bytes = slice::from_raw_buf(&cvbuf1, csz-vlen);
-                match flate::inflate_bytes(bytes) {
-                    Some(inflated) => return Ok(MetadataVec(inflated)),
-                    None => {}
+                if csz <= vlen {
+                    return Err(format!("metadata is truncated: '{}'", filename.display()));
+                }
+                let flag = *cvbuf.offset(vlen as int);
+                let cvbuf1 = cvbuf.offset(vlen as int + 1);
+                let bytes = slice::from_raw_buf(&cvbuf1, csz - vlen - 1);
+                match flag {
+                    encoder::METADATA_UNCOMPRESSED => {
+                        debug!("using {} bytes of uncompressed metadata", bytes.len());
+                        // The section stays mapped for as long as the object
+                        // file is alive, so there's no need to copy it.
+                        let data = bytes as *const [u8];
+                        drop(si);
+                        return Ok(MetadataObject(ObjectMetadata {
+                            _object: of,
+                            data: data,
+                        }));
+                    }
+                    encoder::METADATA_DEFLATED => {
+                        debug!("inflating {} bytes of compressed metadata", bytes.len());
+                        match flate::inflate_bytes(bytes) {
+                            Some(inflated) => return Ok(MetadataVec(inflated)),
+                            None => {}
+                        }
+                    }
+                    _ => {
+                        return Err(format!("unknown metadata encoding found: '{}'",
+                                           filename.display()));
+                    }
                 }
             }
             llvm::LLVMMoveToNextSection(si.llsi);
@@ -791,6 +823,13 @@ pub fn read_meta_section_name(is_osx: bool) -> &'static str {
     }
 }
 
+// Like `list_file_metadata`, but dumping the metadata as JSON
+pub fn list_file_metadata_json(is_osx: bool, path: &Path,
+                               out: &mut io::Writer) -> Result<(), String> {
+    let bytes = try!(get_metadata_section(is_osx, path));
+    decoder::list_crate_metadata_json(bytes.as_slice(), out).map_err(|e| e.to_string())
+}
+
 // A diagnostic function for dumping crate metadata to an output stream
 pub fn list_file_metadata(is_osx: bool, path: &Path,
                           out: &mut io::Writer) -> io::IoResult<()> {
@@ -9,16 +9,22 @@
 // except according to those terms.
 
 
+use middle::astconv_util::ast_ty_to_prim_ty;
+use middle::const_eval;
 use middle::def::*;
 use middle::ty;
 use util::ppaux;
 
 use syntax::ast;
+use syntax::attr;
 use syntax::visit::{self, Visitor};
 
 struct CheckCrateVisitor<'a, 'tcx: 'a> {
     tcx: &'a ty::ctxt<'tcx>,
-    in_const: bool
+    in_const: bool,
+    /// Whether we are in the body of a constant function, which can refer
+    /// to the function's arguments.
+    in_const_fn: bool,
 }
 
 impl<'a, 'tcx> CheckCrateVisitor<'a, 'tcx> {
@@ -43,6 +49,11 @@ impl<'a, 'tcx, 'v> Visitor<'v> for CheckCrateVisitor<'a, 'tcx> {
             ast::ItemStatic(_, _, ref ex) |
             ast::ItemConst(_, ref ex) => {
                 self.inside_const(|v| v.visit_expr(&**ex));
+                check_const_eval(self, &**ex);
+            }
+            ast::ItemFn(ref decl, _, _, ref generics, ref body)
+                    if attr::contains_name(&i.attrs[], "const_fn") => {
+                check_const_fn(self, i, &**decl, generics, &**body);
             }
             ast::ItemEnum(ref enum_definition, _) => {
                 self.inside_const(|v| {
@@ -72,11 +83,71 @@ impl<'a, 'tcx, 'v> Visitor<'v> for CheckCrateVisitor<'a, 'tcx> {
 }
 
 pub fn check_crate(tcx: &ty::ctxt) {
-    visit::walk_crate(&mut CheckCrateVisitor { tcx: tcx, in_const: false },
+    visit::walk_crate(&mut CheckCrateVisitor { tcx: tcx, in_const: false, in_const_fn: false },
                       tcx.map.krate());
     tcx.sess.abort_if_errors();
 }
 
+/// Reports the errors the integer or boolean constant expression `e` would
+/// have if it were evaluated at run time, such as overflow.
+fn check_const_eval(v: &mut CheckCrateVisitor, e: &ast::Expr) {
+    let ty = ty::expr_ty(v.tcx, e);
+    if !ty::type_is_integral(ty) && !ty::type_is_bool(ty) {
+        return;
+    }
+    match const_eval::eval_const_expr_partial(v.tcx, e, Some(ty)) {
+        Err(ref err) if err.kind.is_arithmetic_error() => {
+            span_err!(v.tcx.sess, err.span, E0186,
+                      "constant evaluation error: {}", *err);
+        }
+        _ => {}
+    }
+}
+
+/// Checks that the function marked `#[const_fn]` is simple enough to be
+/// evaluated at compile time: it takes and returns integers or booleans,
+/// isn't generic, and its body is a single constant expression.
+fn check_const_fn(v: &mut CheckCrateVisitor, item: &ast::Item, decl: &ast::FnDecl,
+                  generics: &ast::Generics, body: &ast::Block) {
+    let tcx = v.tcx;
+    let is_const_ty = |&: ty: &ast::Ty| match ast_ty_to_prim_ty(tcx, ty) {
+        Some(ty) => ty::type_is_integral(ty) || ty::type_is_bool(ty),
+        None => false
+    };
+
+    if generics.is_parameterized() {
+        span_err!(tcx.sess, item.span, E0185,
+                  "constant functions cannot have type or lifetime parameters");
+    }
+    for input in decl.inputs.iter() {
+        match input.pat.node {
+            ast::PatIdent(ast::BindByValue(_), _, None) => {}
+            _ => span_err!(tcx.sess, input.pat.span, E0185,
+                           "arguments of constant functions must be plain bindings")
+        }
+        if !is_const_ty(&*input.ty) {
+            span_err!(tcx.sess, input.ty.span, E0185,
+                      "arguments of constant functions must be integers or `bool`");
+        }
+    }
+    match decl.output {
+        ast::Return(ref ty) if is_const_ty(&**ty) => {}
+        ref output => span_err!(tcx.sess, output.span(), E0185,
+                                "constant functions must return an integer or `bool`")
+    }
+
+    match body.expr {
+        Some(ref expr) if body.stmts.is_empty() => {
+            let was_const_fn = v.in_const_fn;
+            v.in_const_fn = true;
+            v.inside_const(|v| v.visit_expr(&**expr));
+            v.in_const_fn = was_const_fn;
+        }
+        _ => span_err!(tcx.sess, body.span, E0185,
+                       "the body of a constant function must be a single expression")
+    }
+}
+
 fn check_expr(v: &mut CheckCrateVisitor, e: &ast::Expr) {
     match e.node {
         ast::ExprUnary(ast::UnDeref, _) => {}
@@ -117,6 +188,8 @@ fn check_expr(v: &mut CheckCrateVisitor, e: &ast::Expr) {
                 DefFn(..) | DefStaticMethod(..) | DefMethod(..) |
                 DefStruct(_) | DefVariant(_, _, _) => {}
 
+                DefLocal(_) if v.in_const_fn => {}
+
                 def => {
                     debug!("(checking const) found bad def: {:?}", def);
                     span_err!(v.tcx.sess, e.span, E0014,
@@ -128,38 +201,19 @@ fn check_expr(v: &mut CheckCrateVisitor, e: &ast::Expr) {
         ast::ExprCall(ref callee, _) => {
             match v.tcx.def_map.borrow()[callee.id] {
                 DefStruct(..) | DefVariant(..) => {}    // OK.
+                DefFn(def_id, _) if const_eval::is_const_fn(v.tcx, def_id) => {}
                 _ => {
                     span_err!(v.tcx.sess, e.span, E0015,
                               "function calls in constants are limited to \
-                               struct and enum constructors");
-                }
-            }
-        }
-        ast::ExprBlock(ref block) => {
-            // Check all statements in the block
-            for stmt in block.stmts.iter() {
-                let block_span_err = |&: span|
-                    span_err!(v.tcx.sess, span, E0016,
-                              "blocks in constants are limited to items and \
-                               tail expressions");
-                match stmt.node {
-                    ast::StmtDecl(ref decl, _) => {
-                        match decl.node {
-                            ast::DeclLocal(_) => block_span_err(decl.span),
-
-                            // Item statements are allowed
-                            ast::DeclItem(_) => {}
-                        }
-                    }
-                    ast::StmtExpr(ref expr, _) => block_span_err(expr.span),
-                    ast::StmtSemi(ref semi, _) => block_span_err(semi.span),
-                    ast::StmtMac(..) => {
-                        v.tcx.sess.span_bug(e.span, "unexpanded statement \
-                                                     macro in const?!")
-                    }
+                               struct and enum constructors and constant \
+                               functions");
                 }
             }
         }
+        ast::ExprBlock(ref block) => check_block_stmts(v, e, &**block),
+        // Constant functions can choose between values, e.g. to end their
+        // recursion.
+        ast::ExprIf(_, ref then, _) if v.in_const_fn => check_block_stmts(v, e, &**then),
         ast::ExprVec(_) |
         ast::ExprAddrOf(ast::MutImmutable, _) |
         ast::ExprParen(..) |
@@ -185,3 +239,31 @@ fn check_expr(v: &mut CheckCrateVisitor, e: &ast::Expr) {
                        "constant contains unimplemented expression type")
     }
 }
+
+/// Checks that the block `block` of the constant expression `e` has no
+/// statements other than items.
+fn check_block_stmts(v: &mut CheckCrateVisitor, e: &ast::Expr, block: &ast::Block) {
+    // Check all statements in the block
+    for stmt in block.stmts.iter() {
+        let block_span_err = |&: span|
+            span_err!(v.tcx.sess, span, E0016,
+                      "blocks in constants are limited to items and \
+                       tail expressions");
+        match stmt.node {
+            ast::StmtDecl(ref decl, _) => {
+                match decl.node {
+                    ast::DeclLocal(_) => block_span_err(decl.span),
+
+                    // Item statements are allowed
+                    ast::DeclItem(_) => {}
+                }
+            }
+            ast::StmtExpr(ref expr, _) => block_span_err(expr.span),
+            ast::StmtSemi(ref semi, _) => block_span_err(semi.span),
+            ast::StmtMac(..) => {
+                v.tcx.sess.span_bug(e.span, "unexpanded statement \
+                                             macro in const?!")
+            }
+        }
+    }
+}
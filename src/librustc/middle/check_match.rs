@@ -122,10 +122,20 @@ pub enum Constructor {
 #[derive(Clone, PartialEq)]
 enum Usefulness {
     Useful,
-    UsefulWithWitness(Vec<P<Pat>>),
+    /// The vectors of patterns that are matched by the vector being checked
+    /// but by no row of the matrix. Witnesses may leave out trailing
+    /// patterns, which are then wildcards.
+    UsefulWithWitness(Vec<Vec<P<Pat>>>),
     NotUseful
 }
 
+/// The most witnesses of a non-exhaustive match that are computed; past this,
+/// the error only says that there are at least as many.
+const MAX_WITNESSES: uint = 100;
+
+/// The most witnesses of a non-exhaustive match that are listed in the error.
+const MAX_WITNESSES_SHOWN: uint = 3;
+
 #[derive(Copy)]
 enum WitnessPreference {
     ConstructWitness,
@@ -354,15 +364,17 @@ fn raw_pat<'a>(p: &'a Pat) -> &'a Pat {
 
 fn check_exhaustive(cx: &MatchCheckCtxt, sp: Span, matrix: &Matrix) {
     match is_useful(cx, matrix, &[DUMMY_WILD_PAT], ConstructWitness) {
-        UsefulWithWitness(pats) => {
-            let witness = match &pats[] {
-                [ref witness] => &**witness,
-                [] => DUMMY_WILD_PAT,
-                _ => unreachable!()
-            };
+        UsefulWithWitness(witnesses) => {
+            let witnesses: Vec<_> = witnesses.iter().map(|pats| {
+                match &pats[] {
+                    [ref witness] => pat_to_string(&**witness),
+                    [] => pat_to_string(DUMMY_WILD_PAT),
+                    _ => unreachable!()
+                }
+            }).collect();
             span_err!(cx.tcx.sess, sp, E0004,
-                "non-exhaustive patterns: `{}` not covered",
-                pat_to_string(witness)
+                "non-exhaustive patterns: {} not covered",
+                list_witnesses(&witnesses[])
             );
         }
         NotUseful => {
@@ -372,6 +384,24 @@ fn check_exhaustive(cx: &MatchCheckCtxt, sp: Span, matrix: &Matrix) {
     }
 }
 
+/// Lists the first few witnesses of a non-exhaustive match, e.g.
+/// "`A`, `B` and `C`" or "`A`, `B`, `C` and 4 more".
+fn list_witnesses(witnesses: &[String]) -> String {
+    let quoted: Vec<_> = witnesses.iter()
+                                  .take(MAX_WITNESSES_SHOWN)
+                                  .map(|w| format!("`{}`", w))
+                                  .collect();
+    if witnesses.len() > MAX_WITNESSES_SHOWN {
+        let more = witnesses.len() - MAX_WITNESSES_SHOWN;
+        format!("{} and {}{} more", quoted.connect(", "),
+                if witnesses.len() >= MAX_WITNESSES { "at least " } else { "" }, more)
+    } else if quoted.len() > 1 {
+        format!("{} and {}", quoted.init().connect(", "), quoted.last().unwrap())
+    } else {
+        quoted.connect("")
+    }
+}
+
 fn const_val_to_expr(value: &const_val) -> P<ast::Expr> {
     let node = match value {
         &const_bool(b) => ast::LitBool(b),
@@ -518,11 +548,16 @@ fn construct_witness(cx: &MatchCheckCtxt, ctor: &Constructor,
     })
 }
 
-fn missing_constructor(cx: &MatchCheckCtxt, &Matrix(ref rows): &Matrix,
-                       left_ty: Ty, max_slice_length: uint) -> Option<Constructor> {
-    let used_constructors: Vec<Constructor> = rows.iter()
+fn used_constructors(cx: &MatchCheckCtxt, &Matrix(ref rows): &Matrix,
+                     left_ty: Ty, max_slice_length: uint) -> Vec<Constructor> {
+    rows.iter()
         .flat_map(|row| pat_constructors(cx, row[0], left_ty, max_slice_length).into_iter())
-        .collect();
+        .collect()
+}
+
+fn missing_constructor(cx: &MatchCheckCtxt, matrix: &Matrix,
+                       left_ty: Ty, max_slice_length: uint) -> Option<Constructor> {
+    let used_constructors = used_constructors(cx, matrix, left_ty, max_slice_length);
     all_constructors(cx, left_ty, max_slice_length)
         .into_iter()
         .find(|c| !used_constructors.contains(c))
@@ -577,7 +612,7 @@ fn is_useful(cx: &MatchCheckCtxt,
     debug!("{:?}", matrix);
     if rows.len() == 0u {
         return match witness {
-            ConstructWitness => UsefulWithWitness(vec!()),
+            ConstructWitness => UsefulWithWitness(vec![vec![]]),
             LeaveOutWitness => Useful
         };
     }
@@ -602,46 +637,15 @@ fn is_useful(cx: &MatchCheckCtxt,
 
     let constructors = pat_constructors(cx, v[0], left_ty, max_slice_length);
     if constructors.is_empty() {
-        match missing_constructor(cx, matrix, left_ty, max_slice_length) {
-            None => {
-                all_constructors(cx, left_ty, max_slice_length).into_iter().map(|c| {
-                    match is_useful_specialized(cx, matrix, v, c.clone(), left_ty, witness) {
-                        UsefulWithWitness(pats) => UsefulWithWitness({
-                            let arity = constructor_arity(cx, &c, left_ty);
-                            let mut result = {
-                                let pat_slice = &pats[];
-                                let subpats: Vec<_> = range(0, arity).map(|i| {
-                                    pat_slice.get(i).map_or(DUMMY_WILD_PAT, |p| &**p)
-                                }).collect();
-                                vec![construct_witness(cx, &c, subpats, left_ty)]
-                            };
-                            result.extend(pats.into_iter().skip(arity));
-                            result
-                        }),
-                        result => result
-                    }
-                }).find(|result| result != &NotUseful).unwrap_or(NotUseful)
-            },
-
-            Some(constructor) => {
-                let matrix = rows.iter().filter_map(|r| {
-                    if pat_is_binding_or_wild(&cx.tcx.def_map, raw_pat(r[0])) {
-                        Some(r.tail().to_vec())
-                    } else {
-                        None
-                    }
-                }).collect();
-                match is_useful(cx, &matrix, v.tail(), witness) {
-                    UsefulWithWitness(pats) => {
-                        let arity = constructor_arity(cx, &constructor, left_ty);
-                        let wild_pats: Vec<_> = repeat(DUMMY_WILD_PAT).take(arity).collect();
-                        let enum_pat = construct_witness(cx, &constructor, wild_pats, left_ty);
-                        let mut new_pats = vec![enum_pat];
-                        new_pats.extend(pats.into_iter());
-                        UsefulWithWitness(new_pats)
-                    },
-                    result => result
+        match witness {
+            ConstructWitness => missing_witnesses(cx, matrix, v, left_ty, max_slice_length),
+            LeaveOutWitness => match missing_constructor(cx, matrix, left_ty, max_slice_length) {
+                None => {
+                    all_constructors(cx, left_ty, max_slice_length).into_iter().map(|c| {
+                        is_useful_specialized(cx, matrix, v, c, left_ty, witness)
+                    }).find(|result| result != &NotUseful).unwrap_or(NotUseful)
                 }
+                Some(_) => is_useful(cx, &default_matrix(cx, matrix), v.tail(), witness)
             }
         }
     } else {
@@ -651,6 +655,91 @@ fn is_useful(cx: &MatchCheckCtxt,
     }
 }
 
+/// Finds the witnesses showing that `v`, whose first pattern is a wildcard,
+/// is useful in relation to `matrix`, by going through each constructor of
+/// the first column in turn.
+fn missing_witnesses(cx: &MatchCheckCtxt, matrix: &Matrix, v: &[&Pat],
+                     left_ty: Ty, max_slice_length: uint) -> Usefulness {
+    let used_constructors = used_constructors(cx, matrix, left_ty, max_slice_length);
+    let wildcard_witnesses = |&:| {
+        match is_useful(cx, &default_matrix(cx, matrix), v.tail(), ConstructWitness) {
+            UsefulWithWitness(witnesses) => witnesses,
+            _ => vec![]
+        }
+    };
+
+    let mut witnesses = vec![];
+    if used_constructors.is_empty() {
+        // None of the rows look into the first column, so a wildcard says
+        // more than listing every constructor would.
+        for pats in wildcard_witnesses().into_iter() {
+            let mut witness = vec![P(DUMMY_WILD_PAT.clone())];
+            witness.extend(pats.into_iter());
+            witnesses.push(witness);
+        }
+        return if witnesses.is_empty() { NotUseful } else { UsefulWithWitness(witnesses) };
+    }
+
+    // The witnesses for the constructors no row uses only differ in their
+    // first pattern, so the rest of them are only computed once.
+    let mut rest = None;
+    for c in all_constructors(cx, left_ty, max_slice_length).into_iter() {
+        if witnesses.len() >= MAX_WITNESSES {
+            break;
+        }
+        if used_constructors.contains(&c) {
+            if let UsefulWithWitness(found) =
+                    is_useful_specialized(cx, matrix, v, c.clone(), left_ty, ConstructWitness) {
+                witnesses.extend(found.into_iter().map(|pats| {
+                    apply_constructor(cx, &c, pats, left_ty)
+                }));
+            }
+        } else {
+            if rest.is_none() {
+                rest = Some(wildcard_witnesses());
+            }
+            let arity = constructor_arity(cx, &c, left_ty);
+            let wild_pats: Vec<_> = repeat(DUMMY_WILD_PAT).take(arity).collect();
+            let pat = construct_witness(cx, &c, wild_pats, left_ty);
+            for pats in rest.as_ref().unwrap().iter() {
+                let mut witness = vec![pat.clone()];
+                witness.extend(pats.iter().cloned());
+                witnesses.push(witness);
+            }
+        }
+    }
+    witnesses.truncate(MAX_WITNESSES);
+    if witnesses.is_empty() { NotUseful } else { UsefulWithWitness(witnesses) }
+}
+
+/// Turns a witness for the matrix specialized to `ctor` back into one for
+/// the matrix itself, by wrapping its first patterns in `ctor`.
+fn apply_constructor(cx: &MatchCheckCtxt, ctor: &Constructor,
+                     pats: Vec<P<Pat>>, left_ty: Ty) -> Vec<P<Pat>> {
+    let arity = constructor_arity(cx, ctor, left_ty);
+    let mut result = {
+        let pat_slice = &pats[];
+        let subpats: Vec<_> = range(0, arity).map(|i| {
+            pat_slice.get(i).map_or(DUMMY_WILD_PAT, |p| &**p)
+        }).collect();
+        vec![construct_witness(cx, ctor, subpats, left_ty)]
+    };
+    result.extend(pats.into_iter().skip(arity));
+    result
+}
+
+/// The rows of `matrix` that match any value in their first column, without
+/// that column.
+fn default_matrix<'a>(cx: &MatchCheckCtxt, &Matrix(ref rows): &Matrix<'a>) -> Matrix<'a> {
+    rows.iter().filter_map(|r| {
+        if pat_is_binding_or_wild(&cx.tcx.def_map, raw_pat(r[0])) {
+            Some(r.tail().to_vec())
+        } else {
+            None
+        }
+    }).collect()
+}
+
 fn is_useful_specialized(cx: &MatchCheckCtxt, &Matrix(ref m): &Matrix,
                          v: &[&Pat], ctor: Constructor, lty: Ty,
                          witness: WitnessPreference) -> Usefulness {
@@ -983,9 +1072,9 @@ fn is_refutable<A, F>(cx: &MatchCheckCtxt, pat: &Pat, refutable: F) -> Option<A>
 {
     let pats = Matrix(vec!(vec!(pat)));
     match is_useful(cx, &pats, &[DUMMY_WILD_PAT], ConstructWitness) {
-        UsefulWithWitness(pats) => {
-            assert_eq!(pats.len(), 1);
-            Some(refutable(&*pats[0]))
+        UsefulWithWitness(witnesses) => {
+            assert_eq!(witnesses[0].len(), 1);
+            Some(refutable(&*witnesses[0][0]))
         },
         NotUseful => None,
         Useful => unreachable!()
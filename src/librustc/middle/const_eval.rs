@@ -13,21 +13,26 @@
 
 pub use self::const_val::*;
 pub use self::constness::*;
+pub use self::ErrKind::*;
 
 use metadata::csearch;
 use middle::{astencode, def};
 use middle::pat_util::def_to_path;
-use middle::ty::{self};
+use middle::ty::{self, Ty};
 use middle::astconv_util::{ast_ty_to_prim_ty};
-use util::nodemap::DefIdMap;
+use util::nodemap::{DefIdMap, NodeMap};
 
 use syntax::ast::{self, Expr};
+use syntax::codemap::Span;
 use syntax::parse::token::InternedString;
 use syntax::ptr::P;
 use syntax::visit::{self, Visitor};
-use syntax::{ast_map, ast_util, codemap};
+use syntax::{ast_map, ast_util, attr, codemap};
 
 use std::collections::hash_map::Entry::Vacant;
+use std::fmt;
+use std::i64;
+use std::num::Int;
 use std::rc::Rc;
 
 //
@@ -185,6 +190,48 @@ pub fn lookup_const_by_id<'a>(tcx: &'a ty::ctxt, def_id: ast::DefId)
     }
 }
 
+/// Whether `def_id` is a function marked `#[const_fn]`, whose calls can be
+/// evaluated at compile time.
+pub fn is_const_fn(tcx: &ty::ctxt, def_id: ast::DefId) -> bool {
+    ty::has_attr(tcx, def_id, "const_fn")
+}
+
+/// Looks up the item of the constant function `def_id`. The items of
+/// constant functions are always exported with the metadata of their crate,
+/// so that they can be evaluated by other crates as well.
+pub fn lookup_const_fn_by_id<'a>(tcx: &'a ty::ctxt, def_id: ast::DefId)
+                                 -> Option<&'a ast::Item> {
+    let item = if ast_util::is_local(def_id) {
+        match tcx.map.find(def_id.node) {
+            Some(ast_map::NodeItem(it)) => it,
+            _ => return None
+        }
+    } else {
+        match tcx.extern_const_fns.borrow().get(&def_id) {
+            Some(&ast::DUMMY_NODE_ID) => return None,
+            Some(&item_id) => return Some(tcx.map.expect_item(item_id)),
+            None => {}
+        }
+        let item_id = match csearch::maybe_get_item_ast(tcx, def_id,
+            box |a, b, c, d| astencode::decode_inlined_item(a, b, c, d)) {
+            csearch::found(&ast::IIItem(ref item)) => match item.node {
+                ast::ItemFn(..) if attr::contains_name(&item.attrs[], "const_fn") => {
+                    Some(item.id)
+                }
+                _ => None
+            },
+            _ => None
+        };
+        tcx.extern_const_fns.borrow_mut().insert(def_id,
+                                                 item_id.unwrap_or(ast::DUMMY_NODE_ID));
+        return item_id.map(|id| tcx.map.expect_item(id));
+    };
+    match item.node {
+        ast::ItemFn(..) if attr::contains_name(&item.attrs[], "const_fn") => Some(item),
+        _ => None
+    }
+}
+
 struct ConstEvalVisitor<'a, 'tcx: 'a> {
     tcx: &'a ty::ctxt<'tcx>,
     ccache: constness_cache,
@@ -220,6 +267,15 @@ impl<'a, 'tcx> ConstEvalVisitor<'a, 'tcx> {
                 join_all(cs)
             }
 
+            ast::ExprCall(ref callee, ref args) => {
+                match self.tcx.def_map.borrow().get(&callee.id) {
+                    Some(&def::DefFn(def_id, _)) if is_const_fn(self.tcx, def_id) => {
+                        join_all(args.iter().map(|e| self.classify(&**e)))
+                    }
+                    _ => non_const
+                }
+            }
+
             ast::ExprCast(ref base, _) => {
                 let ty = ty::expr_ty(self.tcx, e);
                 let base = self.classify(&**base);
@@ -304,6 +360,106 @@ pub enum const_val {
     const_bool(bool)
 }
 
+/// An error evaluating a constant expression, located at the innermost
+/// sub-expression that caused it.
+#[derive(Clone, PartialEq, Show)]
+pub struct ConstEvalErr {
+    pub span: Span,
+    pub kind: ErrKind,
+}
+
+#[derive(Clone, PartialEq, Show)]
+pub enum ErrKind {
+    CannotCast,
+    CannotCastTo(&'static str),
+    InvalidOpForBools,
+    InvalidOpForFloats,
+    InvalidOpForIntUint,
+    InvalidOpForUintInt,
+    NegateOnString,
+    NegateOnBoolean,
+    NegateOnBinary,
+    NotOnFloatOrString,
+    MiscBinaryOp,
+    NonConstPath,
+    NonConstStruct,
+    MissingStructField,
+    TupleIndexOutOfBounds,
+    NonConstFnCall,
+    ConstFnRecursionLimit,
+    UnsupportedExpr,
+
+    NegateWithOverflow,
+    AddWithOverflow,
+    SubWithOverflow,
+    MulWithOverflow,
+    DivWithOverflow,
+    RemWithOverflow,
+    ShlWithOverflow,
+    ShrWithOverflow,
+    DivideByZero,
+    RemainderByZero,
+}
+
+impl ErrKind {
+    /// Whether this is an error the expression would also have at run time,
+    /// rather than one of an expression that can't be evaluated at compile
+    /// time at all.
+    pub fn is_arithmetic_error(&self) -> bool {
+        match *self {
+            NegateWithOverflow | AddWithOverflow | SubWithOverflow |
+            MulWithOverflow | DivWithOverflow | RemWithOverflow |
+            ShlWithOverflow | ShrWithOverflow | DivideByZero |
+            RemainderByZero => true,
+            _ => false
+        }
+    }
+}
+
+impl fmt::String for ErrKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match *self {
+            CannotCast => "can't cast this type",
+            CannotCastTo(ty) => return write!(f, "can't cast this type to {}", ty),
+            InvalidOpForBools => "can't do this op on bools",
+            InvalidOpForFloats => "can't do this op on floats",
+            InvalidOpForIntUint => "can't do this op on an int and uint",
+            InvalidOpForUintInt => "can't do this op on a uint and int",
+            NegateOnString => "negate on string",
+            NegateOnBoolean => "negate on boolean",
+            NegateOnBinary => "negate on binary literal",
+            NotOnFloatOrString => "not on float or string",
+            MiscBinaryOp => "bad operands for binary",
+            NonConstPath => "non-constant path in constant expr",
+            NonConstStruct => "non-constant struct in constant expr",
+            MissingStructField => "nonexistent struct field",
+            TupleIndexOutOfBounds => "tuple index out of bounds",
+            NonConstFnCall => "non-constant function call in constant expr",
+            ConstFnRecursionLimit => "reached the recursion limit while evaluating \
+                                      constant function calls",
+            UnsupportedExpr => "unsupported constant expr",
+
+            NegateWithOverflow => "attempted to negate with overflow",
+            AddWithOverflow => "attempted to add with overflow",
+            SubWithOverflow => "attempted to subtract with overflow",
+            MulWithOverflow => "attempted to multiply with overflow",
+            DivWithOverflow => "attempted to divide with overflow",
+            RemWithOverflow => "attempted remainder with overflow",
+            ShlWithOverflow => "attempted to shift left with overflow",
+            ShrWithOverflow => "attempted to shift right with overflow",
+            DivideByZero => "attempted to divide by zero",
+            RemainderByZero => "attempted remainder with a divisor of zero",
+        };
+        f.pad(msg)
+    }
+}
+
+impl fmt::String for ConstEvalErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::String::fmt(&self.kind, f)
+    }
+}
+
 pub fn const_expr_to_pat(tcx: &ty::ctxt, expr: &Expr) -> P<ast::Pat> {
     let pat = match expr.node {
         ast::ExprTup(ref exprs) =>
@@ -362,37 +518,92 @@ pub fn const_expr_to_pat(tcx: &ty::ctxt, expr: &Expr) -> P<ast::Pat> {
 }
 
 pub fn eval_const_expr(tcx: &ty::ctxt, e: &Expr) -> const_val {
-    match eval_const_expr_partial(tcx, e) {
+    match eval_const_expr_partial(tcx, e, None) {
         Ok(r) => r,
-        Err(s) => tcx.sess.span_fatal(e.span, &s[])
+        Err(err) => tcx.sess.span_fatal(err.span, &err.to_string()[])
     }
 }
 
-pub fn eval_const_expr_partial(tcx: &ty::ctxt, e: &Expr) -> Result<const_val, String> {
-    fn fromb(b: bool) -> Result<const_val, String> { Ok(const_int(b as i64)) }
+/// Evaluates the constant expression `e`, tracking the type of every integer
+/// so that an operation overflowing it is an error, as it would be at run
+/// time. `ty_hint` is the type `e` is expected to have, which is used
+/// wherever type checking hasn't recorded the types of the expressions yet,
+/// e.g. in the array lengths of item signatures.
+pub fn eval_const_expr_partial<'tcx>(tcx: &ty::ctxt<'tcx>,
+                                     e: &Expr,
+                                     ty_hint: Option<Ty<'tcx>>)
+                                     -> Result<const_val, ConstEvalErr> {
+    let cx = EvalCtxt {
+        tcx: tcx,
+        args: NodeMap::new(),
+        depth: 0,
+    };
+    eval(&cx, e, ty_hint)
+}
+
+/// How deeply calls to constant functions may be nested.
+const MAX_CONST_FN_DEPTH: uint = 64;
+
+struct EvalCtxt<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    /// The arguments of the constant function being evaluated, keyed by the
+    /// id of the pattern binding them.
+    args: NodeMap<const_val>,
+    /// The number of constant function calls being evaluated.
+    depth: uint,
+}
+
+fn eval<'a, 'tcx>(cx: &EvalCtxt<'a, 'tcx>,
+                  e: &Expr,
+                  ty_hint: Option<Ty<'tcx>>)
+                  -> Result<const_val, ConstEvalErr> {
+    let tcx = cx.tcx;
+    let err = |&: kind: ErrKind| -> Result<const_val, ConstEvalErr> {
+        Err(ConstEvalErr { span: e.span, kind: kind })
+    };
+    let ety = ty::expr_ty_opt(tcx, e).or(ty_hint);
+
+    fn fromb(b: bool) -> Result<const_val, ErrKind> { Ok(const_int(b as i64)) }
     match e.node {
       ast::ExprUnary(ast::UnNeg, ref inner) => {
-        match eval_const_expr_partial(tcx, &**inner) {
-          Ok(const_float(f)) => Ok(const_float(-f)),
-          Ok(const_int(i)) => Ok(const_int(-i)),
-          Ok(const_uint(i)) => Ok(const_uint(-i)),
-          Ok(const_str(_)) => Err("negate on string".to_string()),
-          Ok(const_bool(_)) => Err("negate on boolean".to_string()),
-          ref err => ((*err).clone())
+        match try!(eval(cx, &**inner, ety)) {
+          const_float(f) => Ok(const_float(-f)),
+          // `-9223372036854775808` is `i64::MIN` rather than an overflow;
+          // the range of literals is left to the `overflowing_literals` lint.
+          const_int(i) if i == i64::MIN && is_lit(&**inner) => Ok(const_int(i)),
+          const_int(i) => {
+            if i == i64::MIN || !fits_int(tcx, -i, ety) {
+                return err(NegateWithOverflow);
+            }
+            Ok(const_int(-i))
+          }
+          const_uint(i) => Ok(const_uint(truncate_uint(tcx, -i, ety))),
+          const_str(_) => err(NegateOnString),
+          const_bool(_) => err(NegateOnBoolean),
+          const_binary(_) => err(NegateOnBinary),
         }
       }
       ast::ExprUnary(ast::UnNot, ref inner) => {
-        match eval_const_expr_partial(tcx, &**inner) {
-          Ok(const_int(i)) => Ok(const_int(!i)),
-          Ok(const_uint(i)) => Ok(const_uint(!i)),
-          Ok(const_bool(b)) => Ok(const_bool(!b)),
-          _ => Err("not on float or string".to_string())
+        match try!(eval(cx, &**inner, ety)) {
+          const_int(i) => Ok(const_int(!i)),
+          const_uint(i) => Ok(const_uint(truncate_uint(tcx, !i, ety))),
+          const_bool(b) => Ok(const_bool(!b)),
+          _ => err(NotOnFloatOrString)
         }
       }
       ast::ExprBinary(op, ref a, ref b) => {
-        match (eval_const_expr_partial(tcx, &**a),
-               eval_const_expr_partial(tcx, &**b)) {
-          (Ok(const_float(a)), Ok(const_float(b))) => {
+        // The operands of comparisons have their own type, and the amount
+        // of a shift can be of any integer type.
+        let (a_hint, b_hint) = match op {
+            ast::BiEq | ast::BiLt | ast::BiLe |
+            ast::BiNe | ast::BiGe | ast::BiGt => (None, None),
+            ast::BiShl | ast::BiShr => (ety, None),
+            _ => (ety, ety)
+        };
+        let a = try!(eval(cx, &**a, a_hint));
+        let b = try!(eval(cx, &**b, b_hint));
+        let result = match (a, b) {
+          (const_float(a), const_float(b)) => {
             match op {
               ast::BiAdd => Ok(const_float(a + b)),
               ast::BiSub => Ok(const_float(a - b)),
@@ -405,79 +616,25 @@ pub fn eval_const_expr_partial(tcx: &ty::ctxt, e: &Expr) -> Result<const_val, St
               ast::BiNe => fromb(a != b),
               ast::BiGe => fromb(a >= b),
               ast::BiGt => fromb(a > b),
-              _ => Err("can't do this op on floats".to_string())
-            }
-          }
-          (Ok(const_int(a)), Ok(const_int(b))) => {
-            match op {
-              ast::BiAdd => Ok(const_int(a + b)),
-              ast::BiSub => Ok(const_int(a - b)),
-              ast::BiMul => Ok(const_int(a * b)),
-              ast::BiDiv if b == 0 => {
-                  Err("attempted to divide by zero".to_string())
-              }
-              ast::BiDiv => Ok(const_int(a / b)),
-              ast::BiRem if b == 0 => {
-                  Err("attempted remainder with a divisor of \
-                       zero".to_string())
-              }
-              ast::BiRem => Ok(const_int(a % b)),
-              ast::BiAnd | ast::BiBitAnd => Ok(const_int(a & b)),
-              ast::BiOr | ast::BiBitOr => Ok(const_int(a | b)),
-              ast::BiBitXor => Ok(const_int(a ^ b)),
-              ast::BiShl => Ok(const_int(a << b as uint)),
-              ast::BiShr => Ok(const_int(a >> b as uint)),
-              ast::BiEq => fromb(a == b),
-              ast::BiLt => fromb(a < b),
-              ast::BiLe => fromb(a <= b),
-              ast::BiNe => fromb(a != b),
-              ast::BiGe => fromb(a >= b),
-              ast::BiGt => fromb(a > b)
-            }
-          }
-          (Ok(const_uint(a)), Ok(const_uint(b))) => {
-            match op {
-              ast::BiAdd => Ok(const_uint(a + b)),
-              ast::BiSub => Ok(const_uint(a - b)),
-              ast::BiMul => Ok(const_uint(a * b)),
-              ast::BiDiv if b == 0 => {
-                  Err("attempted to divide by zero".to_string())
-              }
-              ast::BiDiv => Ok(const_uint(a / b)),
-              ast::BiRem if b == 0 => {
-                  Err("attempted remainder with a divisor of \
-                       zero".to_string())
-              }
-              ast::BiRem => Ok(const_uint(a % b)),
-              ast::BiAnd | ast::BiBitAnd => Ok(const_uint(a & b)),
-              ast::BiOr | ast::BiBitOr => Ok(const_uint(a | b)),
-              ast::BiBitXor => Ok(const_uint(a ^ b)),
-              ast::BiShl => Ok(const_uint(a << b as uint)),
-              ast::BiShr => Ok(const_uint(a >> b as uint)),
-              ast::BiEq => fromb(a == b),
-              ast::BiLt => fromb(a < b),
-              ast::BiLe => fromb(a <= b),
-              ast::BiNe => fromb(a != b),
-              ast::BiGe => fromb(a >= b),
-              ast::BiGt => fromb(a > b),
+              _ => Err(InvalidOpForFloats)
             }
           }
+          (const_int(a), const_int(b)) => eval_int_binop(tcx, op, a, b, ety),
+          (const_uint(a), const_uint(b)) => eval_uint_binop(tcx, op, a, b, ety),
           // shifts can have any integral type as their rhs
-          (Ok(const_int(a)), Ok(const_uint(b))) => {
+          (const_int(a), const_uint(b)) => {
             match op {
-              ast::BiShl => Ok(const_int(a << b as uint)),
-              ast::BiShr => Ok(const_int(a >> b as uint)),
-              _ => Err("can't do this op on an int and uint".to_string())
+              ast::BiShl | ast::BiShr => eval_int_binop(tcx, op, a, b as i64, ety),
+              _ => Err(InvalidOpForIntUint)
             }
           }
-          (Ok(const_uint(a)), Ok(const_int(b))) => {
+          (const_uint(a), const_int(b)) => {
             match op {
-              ast::BiShl => Ok(const_uint(a << b as uint)),
-              ast::BiShr => Ok(const_uint(a >> b as uint)),
-              _ => Err("can't do this op on a uint and int".to_string())
+              ast::BiShl | ast::BiShr => eval_uint_binop(tcx, op, a, b as u64, ety),
+              _ => Err(InvalidOpForUintInt)
             }
           }
-          (Ok(const_bool(a)), Ok(const_bool(b))) => {
+          (const_bool(a), const_bool(b)) => {
             Ok(const_bool(match op {
               ast::BiAnd => a && b,
               ast::BiOr => a || b,
@@ -486,18 +643,18 @@ pub fn eval_const_expr_partial(tcx: &ty::ctxt, e: &Expr) -> Result<const_val, St
               ast::BiBitOr => a | b,
               ast::BiEq => a == b,
               ast::BiNe => a != b,
-              _ => return Err("can't do this op on bools".to_string())
+              _ => return err(InvalidOpForBools)
              }))
           }
-          _ => Err("bad operands for binary".to_string())
-        }
+          _ => Err(MiscBinaryOp)
+        };
+        result.or_else(|kind| err(kind))
       }
       ast::ExprCast(ref base, ref target_ty) => {
         // This tends to get called w/o the type actually having been
         // populated in the ctxt, which was causing things to blow up
         // (#5900). Fall back to doing a limited lookup to get past it.
-        let ety = ty::expr_ty_opt(tcx, e)
-                .or_else(|| ast_ty_to_prim_ty(tcx, &**target_ty))
+        let ety = ety.or_else(|| ast_ty_to_prim_ty(tcx, &**target_ty))
                 .unwrap_or_else(|| {
                     tcx.sess.span_fatal(target_ty.span,
                                         "target type not found for const cast")
@@ -517,57 +674,122 @@ pub fn eval_const_expr_partial(tcx: &ty::ctxt, e: &Expr) -> Result<const_val, St
                         const_uint(u) => Ok($const_type(u as $intermediate_ty as $target_ty)),
                         const_int(i) => Ok($const_type(i as $intermediate_ty as $target_ty)),
                         const_float(f) => Ok($const_type(f as $intermediate_ty as $target_ty)),
-                        _ => Err(concat!(
-                            "can't cast this type to ", stringify!($const_type)
-                        ).to_string())
+                        _ => err(CannotCastTo(stringify!($const_type)))
                     }
                 },)*
-                _ => Err("can't cast this type".to_string())
+                _ => err(CannotCast)
             })
         }
 
-        eval_const_expr_partial(tcx, &**base)
-            .and_then(|val| define_casts!(val, {
-                ty::ty_int(ast::TyIs(_)) => (int, const_int, i64),
-                ty::ty_int(ast::TyI8) => (i8, const_int, i64),
-                ty::ty_int(ast::TyI16) => (i16, const_int, i64),
-                ty::ty_int(ast::TyI32) => (i32, const_int, i64),
-                ty::ty_int(ast::TyI64) => (i64, const_int, i64),
-                ty::ty_uint(ast::TyUs(_)) => (uint, const_uint, u64),
-                ty::ty_uint(ast::TyU8) => (u8, const_uint, u64),
-                ty::ty_uint(ast::TyU16) => (u16, const_uint, u64),
-                ty::ty_uint(ast::TyU32) => (u32, const_uint, u64),
-                ty::ty_uint(ast::TyU64) => (u64, const_uint, u64),
-                ty::ty_float(ast::TyF32) => (f32, const_float, f64),
-                ty::ty_float(ast::TyF64) => (f64, const_float, f64)
-            }))
+        let val = try!(eval(cx, &**base, None));
+        define_casts!(val, {
+            ty::ty_int(ast::TyIs(_)) => (int, const_int, i64),
+            ty::ty_int(ast::TyI8) => (i8, const_int, i64),
+            ty::ty_int(ast::TyI16) => (i16, const_int, i64),
+            ty::ty_int(ast::TyI32) => (i32, const_int, i64),
+            ty::ty_int(ast::TyI64) => (i64, const_int, i64),
+            ty::ty_uint(ast::TyUs(_)) => (uint, const_uint, u64),
+            ty::ty_uint(ast::TyU8) => (u8, const_uint, u64),
+            ty::ty_uint(ast::TyU16) => (u16, const_uint, u64),
+            ty::ty_uint(ast::TyU32) => (u32, const_uint, u64),
+            ty::ty_uint(ast::TyU64) => (u64, const_uint, u64),
+            ty::ty_float(ast::TyF32) => (f32, const_float, f64),
+            ty::ty_float(ast::TyF64) => (f64, const_float, f64)
+        })
       }
       ast::ExprPath(_) => {
+          let opt_def = tcx.def_map.borrow().get(&e.id).cloned();
+          if let Some(def::DefLocal(id)) = opt_def {
+              return match cx.args.get(&id) {
+                  Some(val) => Ok(val.clone()),
+                  None => err(NonConstPath)
+              };
+          }
           match lookup_const(tcx, e) {
-              Some(actual_e) => eval_const_expr_partial(tcx, &*actual_e),
-              None => Err("non-constant path in constant expr".to_string())
+              Some(actual_e) => eval(cx, &*actual_e, ety),
+              None => err(NonConstPath)
           }
       }
-      ast::ExprLit(ref lit) => Ok(lit_to_const(&**lit)),
-      ast::ExprParen(ref e)     => eval_const_expr_partial(tcx, &**e),
+      ast::ExprLit(ref lit) => {
+          // Unsuffixed literals take the type they are inferred to have.
+          match (lit_to_const(&**lit), ety.map(|ty| &ty.sty)) {
+              (const_int(i), Some(&ty::ty_uint(_))) if i >= 0 => Ok(const_uint(i as u64)),
+              (val, _) => Ok(val)
+          }
+      }
+      ast::ExprParen(ref e)     => eval(cx, &**e, ety),
       ast::ExprBlock(ref block) => {
         match block.expr {
-            Some(ref expr) => eval_const_expr_partial(tcx, &**expr),
+            Some(ref expr) => eval(cx, &**expr, ety),
             None => Ok(const_int(0i64))
         }
       }
+      ast::ExprIf(ref cond, ref then, ref opt_else) => {
+        // Only the branch taken is evaluated, so that the recursion of a
+        // constant function can end.
+        let taken = match try!(eval(cx, &**cond, None)) {
+            const_bool(b) => b,
+            // Comparisons evaluate to integers.
+            const_int(i) => i != 0,
+            _ => return err(UnsupportedExpr)
+        };
+        let branch = if taken {
+            then.expr.as_ref()
+        } else {
+            opt_else.as_ref()
+        };
+        match branch {
+            Some(expr) => eval(cx, &**expr, ety),
+            None => err(UnsupportedExpr)
+        }
+      }
+      ast::ExprCall(ref callee, ref args) => {
+        let opt_def = tcx.def_map.borrow().get(&callee.id).cloned();
+        let item = match opt_def {
+            Some(def::DefFn(def_id, _)) => lookup_const_fn_by_id(tcx, def_id),
+            _ => None
+        };
+        let (decl, body) = match item.map(|item| &item.node) {
+            Some(&ast::ItemFn(ref decl, _, _, _, ref body)) => (&**decl, &**body),
+            _ => return err(NonConstFnCall)
+        };
+        if cx.depth >= MAX_CONST_FN_DEPTH {
+            return err(ConstFnRecursionLimit);
+        }
+
+        // The arguments are evaluated in the caller, and bound to the
+        // parameters for the evaluation of the body.
+        let mut callee_args = NodeMap::new();
+        for (arg, input) in args.iter().zip(decl.inputs.iter()) {
+            let val = try!(eval(cx, &**arg, ast_ty_to_prim_ty(tcx, &*input.ty)));
+            callee_args.insert(input.pat.id, val);
+        }
+        let ret_ty = match decl.output {
+            ast::Return(ref ty) => ast_ty_to_prim_ty(tcx, &**ty),
+            ast::NoReturn(_) => None
+        };
+        let callee_cx = EvalCtxt {
+            tcx: tcx,
+            args: callee_args,
+            depth: cx.depth + 1,
+        };
+        match body.expr {
+            Some(ref expr) => eval(&callee_cx, &**expr, ret_ty),
+            None => err(NonConstFnCall)
+        }
+      }
       ast::ExprTupField(ref base, index) => {
         // Get the base tuple if it is constant
         if let Some(&ast::ExprTup(ref fields)) = lookup_const(tcx, &**base).map(|s| &s.node) {
             // Check that the given index is within bounds and evaluate its value
             if fields.len() > index.node {
-                return eval_const_expr_partial(tcx, &*fields[index.node])
+                return eval(cx, &*fields[index.node], ety)
             } else {
-                return Err("tuple index out of bounds".to_string())
+                return err(TupleIndexOutOfBounds)
             }
         }
 
-        Err("non-constant struct in constant expr".to_string())
+        err(NonConstStruct)
       }
       ast::ExprField(ref base, field_name) => {
         // Get the base expression if it is a struct and it is constant
@@ -576,15 +798,158 @@ pub fn eval_const_expr_partial(tcx: &ty::ctxt, e: &Expr) -> Result<const_val, St
             // Check that the given field exists and evaluate it
             if let Some(f) = fields.iter().find(|f|
                                            f.ident.node.as_str() == field_name.node.as_str()) {
-                return eval_const_expr_partial(tcx, &*f.expr)
+                return eval(cx, &*f.expr, ety)
             } else {
-                return Err("nonexistent struct field".to_string())
+                return err(MissingStructField)
             }
         }
 
-        Err("non-constant struct in constant expr".to_string())
+        err(NonConstStruct)
+      }
+      _ => err(UnsupportedExpr)
+    }
+}
+
+fn is_lit(e: &Expr) -> bool {
+    match e.node {
+        ast::ExprLit(_) => true,
+        _ => false
+    }
+}
+
+fn eval_int_binop(tcx: &ty::ctxt, op: ast::BinOp, a: i64, b: i64, ty: Option<Ty>)
+                  -> Result<const_val, ErrKind> {
+    fn fromb(b: bool) -> Result<const_val, ErrKind> { Ok(const_int(b as i64)) }
+    let checked = |&: r: Option<i64>, kind: ErrKind| match r {
+        Some(r) if fits_int(tcx, r, ty) => Ok(const_int(r)),
+        _ => Err(kind)
+    };
+    let bits = int_ty_bits(tcx, ty).unwrap_or(64);
+    match op {
+      ast::BiAdd => checked(a.checked_add(b), AddWithOverflow),
+      ast::BiSub => checked(a.checked_sub(b), SubWithOverflow),
+      ast::BiMul => checked(a.checked_mul(b), MulWithOverflow),
+      ast::BiDiv if b == 0 => Err(DivideByZero),
+      ast::BiDiv => checked(a.checked_div(b), DivWithOverflow),
+      ast::BiRem if b == 0 => Err(RemainderByZero),
+      // The remainder itself is always 0 here, but the division overflows.
+      ast::BiRem if b == -1 && (a == i64::MIN || !fits_int(tcx, -a, ty)) => {
+          Err(RemWithOverflow)
       }
-      _ => Err("unsupported constant expr".to_string())
+      ast::BiRem => Ok(const_int(a % b)),
+      ast::BiAnd | ast::BiBitAnd => Ok(const_int(a & b)),
+      ast::BiOr | ast::BiBitOr => Ok(const_int(a | b)),
+      ast::BiBitXor => Ok(const_int(a ^ b)),
+      ast::BiShl if b < 0 || b as u64 >= bits => Err(ShlWithOverflow),
+      ast::BiShl => Ok(const_int(truncate_int(tcx, a << b as uint, ty))),
+      ast::BiShr if b < 0 || b as u64 >= bits => Err(ShrWithOverflow),
+      ast::BiShr => Ok(const_int(a >> b as uint)),
+      ast::BiEq => fromb(a == b),
+      ast::BiLt => fromb(a < b),
+      ast::BiLe => fromb(a <= b),
+      ast::BiNe => fromb(a != b),
+      ast::BiGe => fromb(a >= b),
+      ast::BiGt => fromb(a > b)
+    }
+}
+
+fn eval_uint_binop(tcx: &ty::ctxt, op: ast::BinOp, a: u64, b: u64, ty: Option<Ty>)
+                   -> Result<const_val, ErrKind> {
+    fn fromb(b: bool) -> Result<const_val, ErrKind> { Ok(const_int(b as i64)) }
+    let checked = |&: r: Option<u64>, kind: ErrKind| match r {
+        Some(r) if fits_uint(tcx, r, ty) => Ok(const_uint(r)),
+        _ => Err(kind)
+    };
+    let bits = uint_ty_bits(tcx, ty).unwrap_or(64);
+    match op {
+      ast::BiAdd => checked(a.checked_add(b), AddWithOverflow),
+      ast::BiSub => checked(a.checked_sub(b), SubWithOverflow),
+      ast::BiMul => checked(a.checked_mul(b), MulWithOverflow),
+      ast::BiDiv if b == 0 => Err(DivideByZero),
+      ast::BiDiv => Ok(const_uint(a / b)),
+      ast::BiRem if b == 0 => Err(RemainderByZero),
+      ast::BiRem => Ok(const_uint(a % b)),
+      ast::BiAnd | ast::BiBitAnd => Ok(const_uint(a & b)),
+      ast::BiOr | ast::BiBitOr => Ok(const_uint(a | b)),
+      ast::BiBitXor => Ok(const_uint(a ^ b)),
+      ast::BiShl if b >= bits => Err(ShlWithOverflow),
+      ast::BiShl => Ok(const_uint(truncate_uint(tcx, a << b as uint, ty))),
+      ast::BiShr if b >= bits => Err(ShrWithOverflow),
+      ast::BiShr => Ok(const_uint(a >> b as uint)),
+      ast::BiEq => fromb(a == b),
+      ast::BiLt => fromb(a < b),
+      ast::BiLe => fromb(a <= b),
+      ast::BiNe => fromb(a != b),
+      ast::BiGe => fromb(a >= b),
+      ast::BiGt => fromb(a > b),
+    }
+}
+
+/// The width in bits of the signed integer type `ty`, if it is one.
+fn int_ty_bits(tcx: &ty::ctxt, ty: Option<Ty>) -> Option<u64> {
+    let t = match ty.map(|ty| &ty.sty) {
+        Some(&ty::ty_int(ast::TyIs(_))) => tcx.sess.target.int_type,
+        Some(&ty::ty_int(t)) => t,
+        _ => return None
+    };
+    Some(match t {
+        ast::TyI8 => 8,
+        ast::TyI16 => 16,
+        ast::TyI32 => 32,
+        ast::TyI64 | ast::TyIs(_) => 64
+    })
+}
+
+/// The width in bits of the unsigned integer type `ty`, if it is one.
+fn uint_ty_bits(tcx: &ty::ctxt, ty: Option<Ty>) -> Option<u64> {
+    let t = match ty.map(|ty| &ty.sty) {
+        Some(&ty::ty_uint(ast::TyUs(_))) => tcx.sess.target.uint_type,
+        Some(&ty::ty_uint(t)) => t,
+        _ => return None
+    };
+    Some(match t {
+        ast::TyU8 => 8,
+        ast::TyU16 => 16,
+        ast::TyU32 => 32,
+        ast::TyU64 | ast::TyUs(_) => 64
+    })
+}
+
+/// Whether `i` is in the range of the signed integer type `ty`. Integers of
+/// unknown type are only limited to 64 bits.
+fn fits_int(tcx: &ty::ctxt, i: i64, ty: Option<Ty>) -> bool {
+    match int_ty_bits(tcx, ty) {
+        Some(bits) if bits < 64 => {
+            let max = (1i64 << (bits - 1) as uint) - 1;
+            i >= -max - 1 && i <= max
+        }
+        _ => true
+    }
+}
+
+fn fits_uint(tcx: &ty::ctxt, u: u64, ty: Option<Ty>) -> bool {
+    match uint_ty_bits(tcx, ty) {
+        Some(bits) if bits < 64 => u >> bits as uint == 0,
+        _ => true
+    }
+}
+
+/// Wraps `i` around to the range of the signed integer type `ty`.
+fn truncate_int(tcx: &ty::ctxt, i: i64, ty: Option<Ty>) -> i64 {
+    match int_ty_bits(tcx, ty) {
+        Some(bits) if bits < 64 => {
+            let unused = (64 - bits) as uint;
+            (i << unused) >> unused
+        }
+        _ => i
+    }
+}
+
+/// Wraps `u` around to the range of the unsigned integer type `ty`.
+fn truncate_uint(tcx: &ty::ctxt, u: u64, ty: Option<Ty>) -> u64 {
+    match uint_ty_bits(tcx, ty) {
+        Some(bits) if bits < 64 => u & ((1u64 << bits as uint) - 1),
+        _ => u
     }
 }
 
@@ -8,9 +8,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! A pass that annotates every item and method with its stability level,
-//! propagating default levels lexically from parent to children ast nodes.
+//! A pass that annotates every item and method with its stability level and
+//! deprecation, propagating them lexically from parent to children ast nodes.
 
+use session::Session;
 use middle::ty;
 use metadata::csearch;
 use syntax::codemap::Span;
@@ -20,7 +21,7 @@ use syntax::ast::{Attribute, Block, Crate, DefId, FnDecl, NodeId, Variant};
 use syntax::ast::{Item, RequiredMethod, ProvidedMethod, TraitItem};
 use syntax::ast::{TypeMethod, Method, Generics, StructField, TypeTraitItem};
 use syntax::ast_util::is_local;
-use syntax::attr::Stability;
+use syntax::attr::{Stability, Deprecation};
 use syntax::visit::{FnKind, FkMethod, Visitor};
 use util::nodemap::{NodeMap, DefIdMap};
 use util::ppaux::Repr;
@@ -32,22 +33,44 @@ pub struct Index {
     // stability for crate-local items; unmarked stability == no entry
     local: NodeMap<Stability>,
     // cache for extern-crate items; unmarked stability == entry with None
-    extern_cache: DefIdMap<Option<Stability>>
+    extern_cache: DefIdMap<Option<Stability>>,
+    // deprecation for crate-local items; not deprecated == no entry
+    local_deprecation: NodeMap<Deprecation>,
+    // cache for extern-crate items; not deprecated == entry with None
+    extern_deprecation_cache: DefIdMap<Option<Deprecation>>,
 }
 
 // A private tree-walker for producing an Index.
-struct Annotator {
+struct Annotator<'a> {
+    sess: &'a Session,
     index: Index,
-    parent: Option<Stability>
+    parent: Option<Stability>,
+    parent_deprecation: Option<Deprecation>,
 }
 
-impl Annotator {
-    // Determine the stability for a node based on its attributes and inherited
-    // stability. The stability is recorded in the index and used as the parent.
+impl<'a> Annotator<'a> {
+    // Determine the stability and deprecation for a node based on its
+    // attributes and inherited values. These are recorded in the index and
+    // used as the parent.
     fn annotate<F>(&mut self, id: NodeId, use_parent: bool,
                    attrs: &Vec<Attribute>, f: F) where
-        F: FnOnce(&mut Annotator),
+        F: FnOnce(&mut Annotator<'a>),
     {
+        let depr = attr::find_deprecation(self.sess.diagnostic(), attrs.as_slice());
+        let parent_deprecation = match depr {
+            Some(depr) => {
+                self.index.local_deprecation.insert(id, depr.clone());
+                replace(&mut self.parent_deprecation, Some(depr))
+            }
+            None => {
+                if use_parent {
+                    self.parent_deprecation.clone()
+                        .map(|depr| self.index.local_deprecation.insert(id, depr));
+                }
+                self.parent_deprecation.clone()
+            }
+        };
+
         match attr::find_stability(attrs.as_slice()) {
             Some(stab) => {
                 self.index.local.insert(id, stab.clone());
@@ -68,10 +91,12 @@ impl Annotator {
                 f(self);
             }
         }
+
+        self.parent_deprecation = parent_deprecation;
     }
 }
 
-impl<'v> Visitor<'v> for Annotator {
+impl<'a, 'v> Visitor<'v> for Annotator<'a> {
     fn visit_item(&mut self, i: &Item) {
         // FIXME (#18969): the following is a hack around the fact
         // that we cannot currently annotate the stability of
@@ -89,8 +114,15 @@ impl<'v> Visitor<'v> for Annotator {
         self.annotate(i.id, use_parent, &i.attrs, |v| visit::walk_item(v, i));
 
         if let ast::ItemStruct(ref sd, _) = i.node {
+            // The constructor shares the attributes of the struct, which
+            // have already been looked at.
             sd.ctor_id.map(|id| {
-                self.annotate(id, true, &i.attrs, |_| {})
+                if let Some(stab) = self.index.local.get(&i.id).cloned() {
+                    self.index.local.insert(id, stab);
+                }
+                if let Some(depr) = self.index.local_deprecation.get(&i.id).cloned() {
+                    self.index.local_deprecation.insert(id, depr);
+                }
             });
         }
     }
@@ -138,13 +170,17 @@ impl<'v> Visitor<'v> for Annotator {
 
 impl Index {
     /// Construct the stability index for a crate being compiled.
-    pub fn build(krate: &Crate) -> Index {
+    pub fn build(sess: &Session, krate: &Crate) -> Index {
         let mut annotator = Annotator {
+            sess: sess,
             index: Index {
                 local: NodeMap::new(),
-                extern_cache: DefIdMap::new()
+                extern_cache: DefIdMap::new(),
+                local_deprecation: NodeMap::new(),
+                extern_deprecation_cache: DefIdMap::new(),
             },
-            parent: None
+            parent: None,
+            parent_deprecation: None,
         };
         annotator.annotate(ast::CRATE_NODE_ID, true, &krate.attrs,
                            |v| visit::walk_crate(v, krate));
@@ -190,6 +226,39 @@ pub fn lookup(tcx: &ty::ctxt, id: DefId) -> Option<Stability> {
     })
 }
 
+/// Lookup the deprecation of a node, loading external crate metadata as
+/// necessary. Unlike stability, deprecation is not limited to crates using
+/// the staged API.
+pub fn lookup_deprecation(tcx: &ty::ctxt, id: DefId) -> Option<Deprecation> {
+    debug!("lookup_deprecation(id={})",
+           id.repr(tcx));
+
+    let depr = if is_local(id) {
+        tcx.stability.borrow().local_deprecation.get(&id.node).cloned()
+    } else {
+        let cached = tcx.stability.borrow().extern_deprecation_cache.get(&id).cloned();
+        match cached {
+            Some(depr) => depr,
+            None => {
+                let depr = csearch::get_deprecation(&tcx.sess.cstore, id);
+                let mut index = tcx.stability.borrow_mut();
+                (*index).extern_deprecation_cache.insert(id, depr.clone());
+                depr
+            }
+        }
+    };
+
+    depr.or_else(|| {
+        // Deprecating a trait method deprecates its implementations.
+        match ty::trait_item_of_item(tcx, id) {
+            Some(ty::MethodTraitItemId(trait_method_id)) if trait_method_id != id => {
+                lookup_deprecation(tcx, trait_method_id)
+            }
+            _ => None
+        }
+    })
+}
+
 pub fn is_staged_api(tcx: &ty::ctxt, id: DefId) -> bool {
     match ty::trait_item_of_item(tcx, id) {
         Some(ty::MethodTraitItemId(trait_method_id))
@@ -19,9 +19,11 @@ use super::{
 };
 
 use fmt_macros::{Parser, Piece, Position};
+use middle::fast_reject;
 use middle::infer::InferCtxt;
-use middle::ty::{self, AsPredicate, ReferencesError, ToPolyTraitRef, TraitRef};
+use middle::ty::{self, AsPredicate, ReferencesError, ToPolyTraitRef, TraitRef, Ty};
 use std::collections::HashMap;
+use syntax::ast;
 use syntax::codemap::{DUMMY_SP, Span};
 use syntax::attr::{AttributeMethods, AttrMetaMethods};
 use util::ppaux::{Repr, UserString};
@@ -184,6 +186,8 @@ pub fn report_selection_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
                            infcx.tcx.sess.span_note(obligation.cause.span,
                                                     s.as_slice());
                         }
+                        note_candidate_impls(infcx, &*trait_ref.0, obligation.cause.span);
+                        note_obligation_cause(infcx, obligation);
                     }
                 }
 
@@ -242,6 +246,99 @@ pub fn report_selection_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
     }
 }
 
+/// The most impls `note_candidate_impls` lists.
+const MAX_CANDIDATE_IMPLS: uint = 4;
+
+/// Points out the impls of the trait of `trait_ref` for types resembling the
+/// one it isn't implemented for: those that `fast_reject` can't tell apart
+/// from it, such as impls for `&Bar` or `Bar<u32>` when `Bar<i32>` was
+/// required, and those for types with the same path from another crate.
+fn note_candidate_impls<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                  trait_ref: &TraitRef<'tcx>,
+                                  span: Span) {
+    let tcx = infcx.tcx;
+    let self_ty = trait_ref.self_ty();
+    let simplified_self_ty = match fast_reject::simplify_type(tcx, peel_pointers(self_ty), true) {
+        Some(simplified) => simplified,
+        None => return,
+    };
+
+    ty::populate_implementations_for_trait_if_necessary(tcx, trait_ref.def_id);
+    let impls = match tcx.trait_impls.borrow().get(&trait_ref.def_id) {
+        Some(impls) => impls.borrow().clone(),
+        None => return,
+    };
+
+    let mut candidates = Vec::new();
+    let mut other_versions = Vec::new();
+    for &impl_def_id in impls.iter() {
+        let impl_self_ty = match ty::impl_trait_ref(tcx, impl_def_id) {
+            Some(impl_trait_ref) => impl_trait_ref.self_ty(),
+            None => continue,
+        };
+        // Blanket impls (`impl<T> Foo for T`) resemble every type, so they
+        // don't tell anybody anything.
+        let simplified_impl_ty = fast_reject::simplify_type(tcx, peel_pointers(impl_self_ty),
+                                                            false);
+        if simplified_impl_ty == Some(simplified_self_ty) {
+            candidates.push(impl_self_ty);
+            continue;
+        }
+        match (nominal_def_id(self_ty), nominal_def_id(impl_self_ty)) {
+            (Some(self_did), Some(impl_did)) if self_did.krate != impl_did.krate &&
+                    ty::item_path_str(tcx, self_did) == ty::item_path_str(tcx, impl_did) => {
+                other_versions.push(impl_did);
+            }
+            _ => {}
+        }
+    }
+
+    let trait_str = trait_ref.user_string(tcx);
+    for candidate in candidates.iter().take(MAX_CANDIDATE_IMPLS) {
+        tcx.sess.span_note(
+            span,
+            format!("the trait `{}` is implemented for the type `{}`",
+                    trait_str,
+                    candidate.user_string(tcx)).as_slice());
+    }
+    if candidates.len() > MAX_CANDIDATE_IMPLS {
+        tcx.sess.span_note(
+            span,
+            format!("... and for {} other similar types",
+                    candidates.len() - MAX_CANDIDATE_IMPLS).as_slice());
+    }
+
+    for &did in other_versions.iter() {
+        let crate_name = tcx.sess.cstore.get_crate_data(did.krate).name.clone();
+        tcx.sess.span_note(
+            span,
+            format!("the trait `{}` is implemented for a different type named `{}`; \
+                     perhaps two different versions of crate `{}` are being used?",
+                    trait_str,
+                    ty::item_path_str(tcx, did),
+                    crate_name).as_slice());
+    }
+}
+
+/// The type `ty` refers to, through any number of references and boxes, so
+/// that an impl for `&Bar` or `Box<Bar>` counts as one for a type like `Bar`.
+fn peel_pointers(ty: Ty) -> Ty {
+    match ty.sty {
+        ty::ty_rptr(_, mt) => peel_pointers(mt.ty),
+        ty::ty_uniq(ty) => peel_pointers(ty),
+        _ => ty,
+    }
+}
+
+/// The struct or enum `ty` is, or refers to.
+fn nominal_def_id(ty: Ty) -> Option<ast::DefId> {
+    match peel_pointers(ty).sty {
+        ty::ty_struct(did, _) | ty::ty_enum(did, _) => Some(did),
+        ty::ty_ptr(mt) => nominal_def_id(mt.ty),
+        _ => None,
+    }
+}
+
 pub fn maybe_report_ambiguity<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
                                         obligation: &PredicateObligation<'tcx>) {
     // Unable to successfully determine, probably means
@@ -780,10 +780,11 @@ pub struct ctxt<'tcx> {
     /// Borrows
     pub upvar_borrow_map: RefCell<UpvarBorrowMap>,
 
-    /// These two caches are used by const_eval when decoding external statics
-    /// and variants that are found.
+    /// These caches are used by const_eval when decoding external statics,
+    /// variants and constant functions that are found.
     pub extern_const_statics: RefCell<DefIdMap<ast::NodeId>>,
     pub extern_const_variants: RefCell<DefIdMap<ast::NodeId>>,
+    pub extern_const_fns: RefCell<DefIdMap<ast::NodeId>>,
 
     pub method_map: MethodMap<'tcx>,
 
@@ -2420,6 +2421,7 @@ pub fn mk_ctxt<'tcx>(s: Session,
         upvar_borrow_map: RefCell::new(FnvHashMap::new()),
         extern_const_statics: RefCell::new(DefIdMap::new()),
         extern_const_variants: RefCell::new(DefIdMap::new()),
+        extern_const_fns: RefCell::new(DefIdMap::new()),
         method_map: RefCell::new(FnvHashMap::new()),
         dependency_formats: RefCell::new(FnvHashMap::new()),
         unboxed_closures: RefCell::new(DefIdMap::new()),
@@ -5340,7 +5342,8 @@ pub fn enum_variants<'tcx>(cx: &ctxt<'tcx>, id: ast::DefId)
 
                                 match variant.node.disr_expr {
                                     Some(ref e) =>
-                                        match const_eval::eval_const_expr_partial(cx, &**e) {
+                                        match const_eval::eval_const_expr_partial(cx, &**e,
+                                                                                  None) {
                                             Ok(const_eval::const_int(val)) => {
                                                 discriminant = val as Disr
                                             }
@@ -5354,7 +5357,7 @@ pub fn enum_variants<'tcx>(cx: &ctxt<'tcx>, id: ast::DefId)
                                             }
                                             Err(ref err) => {
                                                 cx.sess
-                                                  .span_err(e.span,
+                                                  .span_err(err.span,
                                                             &format!("expected constant: {}",
                                                                     *err)[]);
                                             }
@@ -5838,7 +5841,7 @@ pub fn is_binopable<'tcx>(cx: &ctxt<'tcx>, ty: Ty<'tcx>, op: ast::BinOp) -> bool
 
 // Returns the repeat count for a repeating vector expression.
 pub fn eval_repeat_count(tcx: &ctxt, count_expr: &ast::Expr) -> uint {
-    match const_eval::eval_const_expr_partial(tcx, count_expr) {
+    match const_eval::eval_const_expr_partial(tcx, count_expr, Some(tcx.types.uint)) {
         Ok(val) => {
             let found = match val {
                 const_eval::const_uint(count) => return count as uint,
@@ -5858,6 +5861,10 @@ pub fn eval_repeat_count(tcx: &ctxt, count_expr: &ast::Expr) -> uint {
                 "expected positive integer for repeat count, found {}",
                 found)[]);
         }
+        Err(ref err) if err.kind.is_arithmetic_error() => {
+            tcx.sess.span_err(err.span, &format!(
+                "constant evaluation error in repeat count: {}", *err)[]);
+        }
         Err(_) => {
             let found = match count_expr.node {
                 ast::ExprPath(ast::Path {
@@ -14,6 +14,7 @@
 pub use self::EntryFnType::*;
 pub use self::CrateType::*;
 pub use self::Passes::*;
+pub use self::Sanitizer::*;
 pub use self::OptLevel::*;
 pub use self::OutputType::*;
 pub use self::DebugInfoLevel::*;
@@ -105,6 +106,7 @@ pub struct Options {
     pub prints: Vec<PrintRequest>,
     pub cg: CodegenOptions,
     pub color: ColorConfig,
+    pub error_format: ErrorOutputType,
     pub show_span: Option<String>,
     pub externs: HashMap<String, Vec<String>>,
     pub crate_name: Option<String>,
@@ -113,7 +115,20 @@ pub struct Options {
     /// out-of-tree drivers.
     pub alt_std_name: Option<String>,
     /// Indicates how the compiler should treat unstable features
-    pub unstable_features: UnstableFeatures
+    pub unstable_features: UnstableFeatures,
+    /// Path prefixes to replace in the file names recorded in the output,
+    /// as `(from, to)` pairs in command line order.
+    pub remap_path_prefix: Vec<(String, String)>,
+    /// The lint config file given on the command line, if any.
+    pub lint_config: Option<Path>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ErrorOutputType {
+    /// Human-readable text, as rendered by `EmitterWriter`.
+    HumanReadable,
+    /// One JSON object per diagnostic, as rendered by `JsonEmitter`.
+    Json,
 }
 
 #[derive(Clone, Copy)]
@@ -137,6 +152,8 @@ pub enum PrintRequest {
     FileNames,
     Sysroot,
     CrateName,
+    /// The contents of the metadata of the library given as input, as JSON.
+    CrateMetadata,
 }
 
 pub enum Input {
@@ -229,12 +246,15 @@ pub fn basic_options() -> Options {
         prints: Vec::new(),
         cg: basic_codegen_options(),
         color: Auto,
+        error_format: ErrorOutputType::HumanReadable,
         show_span: None,
         externs: HashMap::new(),
         crate_name: None,
         alt_std_name: None,
         libs: Vec::new(),
-        unstable_features: UnstableFeatures::Disallow
+        unstable_features: UnstableFeatures::Disallow,
+        remap_path_prefix: Vec::new(),
+        lint_config: None,
     }
 }
 
@@ -273,6 +293,27 @@ impl Passes {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Show)]
+pub enum Sanitizer {
+    AddressSanitizer,
+    ThreadSanitizer,
+    MemorySanitizer,
+    LeakSanitizer,
+}
+
+impl Sanitizer {
+    /// The name of the runtime library linked into programs built with the
+    /// sanitizer.
+    pub fn runtime_name(&self) -> &'static str {
+        match *self {
+            AddressSanitizer => "asan-rt",
+            ThreadSanitizer => "tsan-rt",
+            MemorySanitizer => "msan-rt",
+            LeakSanitizer => "lsan-rt",
+        }
+    }
+}
+
 /// Declare a macro that will define all CodegenOptions/DebuggingOptions fields and parsers all
 /// at once. The goal of this macro is to define an interface that can be
 /// programmatically used by the option parser in order to initialize the struct
@@ -349,6 +390,7 @@ macro_rules! options {
     mod $mod_desc {
         pub const parse_bool: Option<&'static str> = None;
         pub const parse_opt_bool: Option<&'static str> = None;
+        pub const parse_opt_yes_no: Option<&'static str> = Some("`yes` or `no`");
         pub const parse_string: Option<&'static str> = Some("a string");
         pub const parse_opt_string: Option<&'static str> = Some("a string");
         pub const parse_list: Option<&'static str> = Some("a space-separated list of strings");
@@ -358,11 +400,15 @@ macro_rules! options {
             Some("a space-separated list of passes, or `all`");
         pub const parse_opt_uint: Option<&'static str> =
             Some("a number");
+        pub const parse_sanitizer: Option<&'static str> =
+            Some("one of: `address`, `thread`, `memory` or `leak`");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
         use super::{$struct_name, Passes, SomePasses, AllPasses};
+        use super::{Sanitizer, AddressSanitizer, ThreadSanitizer, MemorySanitizer,
+                    LeakSanitizer};
 
         $(
             pub fn $opt(cg: &mut $struct_name, v: Option<&str>) -> bool {
@@ -384,6 +430,14 @@ macro_rules! options {
             }
         }
 
+        fn parse_opt_yes_no(slot: &mut Option<bool>, v: Option<&str>) -> bool {
+            match v {
+                Some("yes") | None => { *slot = Some(true); true }
+                Some("no") => { *slot = Some(false); true }
+                Some(..) => false,
+            }
+        }
+
         fn parse_opt_string(slot: &mut Option<String>, v: Option<&str>) -> bool {
             match v {
                 Some(s) => { *slot = Some(s.to_string()); true },
@@ -454,6 +508,17 @@ macro_rules! options {
                 }
             }
         }
+
+        fn parse_sanitizer(slot: &mut Option<Sanitizer>, v: Option<&str>) -> bool {
+            *slot = match v {
+                Some("address") => Some(AddressSanitizer),
+                Some("thread") => Some(ThreadSanitizer),
+                Some("memory") => Some(MemorySanitizer),
+                Some("leak") => Some(LeakSanitizer),
+                _ => return false,
+            };
+            true
+        }
     }
 ) }
 
@@ -513,6 +578,14 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
          2 = full debug info with variable and type information"),
     opt_level: Option<uint> = (None, parse_opt_uint,
         "Optimize with possible levels 0-3"),
+    overflow_checks: Option<bool> = (None, parse_opt_yes_no,
+        "panic on arithmetic overflow at run time (the default unless optimizing \
+         or building with `--cfg ndebug`)"),
+    profile_generate: Option<String> = (None, parse_opt_string,
+        "instrument the code to write a profile for profile-guided optimization \
+         to the given directory"),
+    profile_use: Option<String> = (None, parse_opt_string,
+        "optimize using the given profile, as merged by `llvm-profdata merge`"),
 }
 
 
@@ -527,6 +600,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "count where LLVM instrs originate"),
     time_llvm_passes: bool = (false, parse_bool,
         "measure time of each LLVM pass"),
+    trace_passes: Option<String> = (None, parse_opt_string,
+        "write a trace of the time taken by each pass, in the format of \
+         chrome://tracing, to the given file"),
     trans_stats: bool = (false, parse_bool,
         "gather trans statistics"),
     asm_comments: bool = (false, parse_bool,
@@ -543,6 +619,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "count the sizes of aggregate types"),
     meta_stats: bool = (false, parse_bool,
         "gather metadata statistics"),
+    uncompressed_metadata: bool = (false, parse_bool,
+        "don't compress the metadata of dylibs, so that it can be used without \
+         inflating a copy of it"),
     print_link_args: bool = (false, parse_bool,
         "Print the arguments passed to the linker"),
     gc: bool = (false, parse_bool,
@@ -582,6 +661,16 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "Adds unstable command line options to rustc interface"),
     print_enum_sizes: bool = (false, parse_bool,
           "Print the size of enums and their variants"),
+    apply_suggestions: bool = (false, parse_bool,
+          "Rewrite the source files with the fixes suggested by diagnostics"),
+    coverage: bool = (false, parse_bool,
+          "Instrument the code to write gcov coverage data when it exits"),
+    sanitizer: Option<Sanitizer> = (None, parse_sanitizer,
+          "Instrument the code with a sanitizer to detect memory errors, data races, \
+           uses of uninitialized memory or leaks at run time"),
+    thin_lto: bool = (false, parse_bool,
+          "Optimize each codegen unit in parallel, importing the small functions it \
+           calls from the other codegen units and upstream crates"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -753,7 +842,7 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
                  "[asm|llvm-bc|llvm-ir|obj|link|dep-info]"),
         opt::multi("", "print", "Comma separated list of compiler information to \
                                print on stdout",
-                 "[crate-name|file-names|sysroot]"),
+                 "[crate-name|file-names|sysroot|crate-metadata]"),
         opt::flag("g",  "",  "Equivalent to -C debuginfo=2"),
         opt::flag("O", "", "Equivalent to -C opt-level=2"),
         opt::opt("o", "", "Write output to <filename>", "FILENAME"),
@@ -793,6 +882,13 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             auto   = colorize, if output goes to a tty (default);
             always = always colorize output;
             never  = never colorize output", "auto|always|never"),
+        opt::opt_u("", "error-format", "How errors and other messages are produced:
+            human = human-readable text (default);
+            json  = one JSON object per diagnostic", "human|json"),
+        opt::multi_u("", "remap-path-prefix", "Remap source paths starting with FROM \
+                                             to start with TO in all output", "FROM=TO"),
+        opt::opt_u("", "lint-config", "Read lint levels from FILE rather than from \
+                                       rustc-lints.cfg next to the crate root", "FILE"),
 
         // DEPRECATED
         opt::flag("", "print-crate-name", "Output the crate name and exit"),
@@ -994,6 +1090,20 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         }
     };
 
+    // Coverage is mapped back to the source through the line tables. LLVM's
+    // gcov pass needs nothing more: it finds the functions through their
+    // subprograms and the lines through the instructions' locations, both of
+    // which limited debuginfo emits. Types and variables are of no use to it.
+    let debuginfo = if debugging_opts.coverage && debuginfo == NoDebugInfo {
+        LimitedDebugInfo
+    } else {
+        debuginfo
+    };
+
+    if cg.profile_generate.is_some() && cg.profile_use.is_some() {
+        early_error("-C profile-generate and -C profile-use both provided");
+    }
+
     let mut search_paths = SearchPaths::new();
     for s in matches.opt_strs("L").iter() {
         search_paths.add_path(&s[]);
@@ -1049,6 +1159,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             "crate-name" => PrintRequest::CrateName,
             "file-names" => PrintRequest::FileNames,
             "sysroot" => PrintRequest::Sysroot,
+            "crate-metadata" => PrintRequest::CrateMetadata,
             req => {
                 early_error(format!("unknown print request `{}`", req).as_slice())
             }
@@ -1086,6 +1197,23 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         }
     };
 
+    let error_format = match matches.opt_str("error-format").as_ref().map(|s| &s[]) {
+        Some(..) if !debugging_opts.unstable_options => {
+            early_error("the `--error-format` flag is unstable and requires \
+                         `-Z unstable-options`")
+        }
+        Some("human") => ErrorOutputType::HumanReadable,
+        Some("json") => ErrorOutputType::Json,
+
+        None => ErrorOutputType::HumanReadable,
+
+        Some(arg) => {
+            early_error(&format!("argument for --error-format must be human \
+                                 or json (instead was `{}`)",
+                                arg)[])
+        }
+    };
+
     let mut externs = HashMap::new();
     for arg in matches.opt_strs("extern").iter() {
         let mut parts = arg.splitn(1, '=');
@@ -1106,6 +1234,27 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let crate_name = matches.opt_str("crate-name");
 
+    if matches.opt_present("remap-path-prefix") && !debugging_opts.unstable_options {
+        early_error("the `--remap-path-prefix` flag is unstable and requires \
+                     `-Z unstable-options`");
+    }
+    if matches.opt_present("lint-config") && !debugging_opts.unstable_options {
+        early_error("the `--lint-config` flag is unstable and requires \
+                     `-Z unstable-options`");
+    }
+    let lint_config = matches.opt_str("lint-config").map(|p| Path::new(p));
+
+    let remap_path_prefix = matches.opt_strs("remap-path-prefix").into_iter().map(|arg| {
+        // The paths themselves may contain `=`, the replacement is less
+        // likely to.
+        let mut parts = arg.rsplitn(1, '=');
+        let to = parts.next().unwrap();
+        match parts.next() {
+            Some(from) => (from.to_string(), to.to_string()),
+            None => early_error("--remap-path-prefix must contain `=` between FROM and TO"),
+        }
+    }).collect();
+
     Options {
         crate_types: crate_types,
         gc: gc,
@@ -1127,12 +1276,15 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         prints: prints,
         cg: cg,
         color: color,
+        error_format: error_format,
         show_span: None,
         externs: externs,
         crate_name: crate_name,
         alt_std_name: None,
         libs: libs,
-        unstable_features: UnstableFeatures::Disallow
+        unstable_features: UnstableFeatures::Disallow,
+        remap_path_prefix: remap_path_prefix,
+        lint_config: lint_config,
     }
 }
 
@@ -1173,7 +1325,8 @@ impl fmt::Show for CrateType {
 #[cfg(test)]
 mod test {
 
-    use session::config::{build_configuration, optgroups, build_session_options};
+    use session::config::{build_configuration, optgroups, rustc_optgroups};
+    use session::config::{build_session_options, ErrorOutputType};
     use session::build_session;
 
     use getopts::getopts;
@@ -1250,4 +1403,19 @@ mod test {
             assert!(sess.can_print_warnings);
         }
     }
+
+    #[test]
+    fn test_error_format_json() {
+        let groups = rustc_optgroups().into_iter().map(|g| g.opt_group).collect::<Vec<_>>();
+        let matches = getopts(&[
+            "-Zunstable-options".to_string(),
+            "--error-format=json".to_string()
+        ], &groups[]).unwrap();
+        let sessopts = build_session_options(&matches);
+        assert!(sessopts.error_format == ErrorOutputType::Json);
+
+        let matches = getopts(&[], &optgroups()[]).unwrap();
+        let sessopts = build_session_options(&matches);
+        assert!(sessopts.error_format == ErrorOutputType::HumanReadable);
+    }
 }
@@ -18,10 +18,13 @@ use util::nodemap::NodeMap;
 use regex::Regex;
 
 use syntax::ast::NodeId;
+use syntax::attr;
 use syntax::codemap::Span;
-use syntax::diagnostic::{self, Emitter};
+use syntax::diagnostic::{self, Applicability, Emitter};
 use syntax::diagnostics;
 use syntax::feature_gate;
+use syntax::json;
+use syntax::suggestions;
 use syntax::parse;
 use syntax::parse::token;
 use syntax::parse::ParseSess;
@@ -53,7 +56,8 @@ pub struct Session {
     pub local_crate_source_file: Option<Path>,
     pub working_dir: Path,
     pub lint_store: RefCell<lint::LintStore>,
-    pub lints: RefCell<NodeMap<Vec<(lint::LintId, codemap::Span, String)>>>,
+    pub lints: RefCell<NodeMap<Vec<(lint::LintId, codemap::Span, String,
+                                    Option<lint::LintSuggestion>)>>>,
     pub crate_types: RefCell<Vec<config::CrateType>>,
     pub crate_metadata: RefCell<Vec<String>>,
     pub features: RefCell<feature_gate::Features>,
@@ -174,6 +178,10 @@ impl Session {
     pub fn fileline_note(&self, sp: Span, msg: &str) {
         self.diagnostic().fileline_note(sp, msg)
     }
+    pub fn span_suggestion(&self, sp: Span, msg: &str, suggestion: String,
+                           applicability: Applicability) {
+        self.diagnostic().span_suggestion(sp, msg, suggestion, applicability)
+    }
     pub fn note(&self, msg: &str) {
         self.diagnostic().handler().note(msg)
     }
@@ -203,13 +211,23 @@ impl Session {
                     id: ast::NodeId,
                     sp: Span,
                     msg: String) {
+        self.add_lint_with_suggestion(lint, id, sp, msg, None)
+    }
+    /// Like `add_lint`, but if the lint ends up being emitted it is followed
+    /// by the given suggestion.
+    pub fn add_lint_with_suggestion(&self,
+                                    lint: &'static lint::Lint,
+                                    id: ast::NodeId,
+                                    sp: Span,
+                                    msg: String,
+                                    suggestion: Option<lint::LintSuggestion>) {
         let lint_id = lint::LintId::of(lint);
         let mut lints = self.lints.borrow_mut();
         match lints.get_mut(&id) {
-            Some(arr) => { arr.push((lint_id, sp, msg)); return; }
+            Some(arr) => { arr.push((lint_id, sp, msg, suggestion)); return; }
             None => {}
         }
-        lints.insert(id, vec!((lint_id, sp, msg)));
+        lints.insert(id, vec!((lint_id, sp, msg, suggestion)));
     }
     pub fn next_node_id(&self) -> ast::NodeId {
         self.parse_sess.next_node_id()
@@ -254,6 +272,14 @@ impl Session {
     pub fn no_landing_pads(&self) -> bool {
         self.opts.debugging_opts.no_landing_pads
     }
+    /// Whether integer arithmetic is checked for overflow at run time. This
+    /// is the case along with debug assertions, unless optimizing.
+    pub fn overflow_checks(&self) -> bool {
+        self.opts.cg.overflow_checks.unwrap_or_else(|| {
+            self.opts.optimize == config::No &&
+                !attr::contains_name(&self.opts.cfg[], "ndebug")
+        })
+    }
     pub fn unstable_options(&self) -> bool {
         self.opts.debugging_opts.unstable_options
     }
@@ -286,9 +312,22 @@ pub fn build_session(sopts: config::Options,
                      local_crate_source_file: Option<Path>,
                      registry: diagnostics::registry::Registry)
                      -> Session {
-    let codemap = codemap::CodeMap::new();
-    let diagnostic_handler =
-        diagnostic::default_handler(sopts.color, Some(registry));
+    let file_path_mapping = codemap::FilePathMapping::new(sopts.remap_path_prefix.clone());
+    let codemap = codemap::CodeMap::with_file_path_mapping(file_path_mapping);
+    let emitter: Box<Emitter + Send> = match sopts.error_format {
+        config::ErrorOutputType::HumanReadable => {
+            box diagnostic::EmitterWriter::stderr(sopts.color, Some(registry))
+        }
+        config::ErrorOutputType::Json => {
+            box json::JsonEmitter::stderr(Some(registry))
+        }
+    };
+    let emitter: Box<Emitter + Send> = if sopts.debugging_opts.apply_suggestions {
+        box suggestions::SuggestionApplier::new(emitter)
+    } else {
+        emitter
+    };
+    let diagnostic_handler = diagnostic::mk_handler(emitter);
     let span_diagnostic_handler =
         diagnostic::mk_span_handler(diagnostic_handler, codemap);
 
@@ -351,6 +390,28 @@ pub fn build_session_(sopts: config::Options,
         can_print_warnings: can_print_warnings
     };
 
+    // The sanitizer runtimes are only built for this target.
+    if sess.opts.debugging_opts.sanitizer.is_some() &&
+       sess.target.target.llvm_target != "x86_64-unknown-linux-gnu" {
+        sess.err(&format!("-Z sanitizer is not supported on target `{}`",
+                          sess.target.target.llvm_target)[]);
+    }
+
+    // Neither is the profiling runtime instrumented code links against; the
+    // targets it is built for are taken from PROFILER_TARGETS in mk/rt.mk.
+    let profiler_targets = option_env!("CFG_PROFILER_TARGETS").unwrap_or("");
+    let llvm_target = &sess.target.target.llvm_target[];
+    if !profiler_targets.split(' ').any(|t| t == llvm_target) {
+        if sess.opts.cg.profile_generate.is_some() {
+            sess.err(&format!("-C profile-generate is not supported on target `{}`",
+                              llvm_target)[]);
+        }
+        if sess.opts.debugging_opts.coverage {
+            sess.err(&format!("-Z coverage is not supported on target `{}`",
+                              llvm_target)[]);
+        }
+    }
+
     sess.lint_store.borrow_mut().register_builtin(Some(&sess));
     sess
 }
@@ -22,6 +22,8 @@ use syntax::ast;
 use syntax::visit;
 use syntax::visit::Visitor;
 
+use util::trace;
+
 // Useful type to use with `Result<>` indicate that an error has already
 // been reported to the user, so no need to continue checking.
 #[derive(Clone, Copy, Show)]
@@ -31,7 +33,7 @@ pub fn time<T, U, F>(do_it: bool, what: &str, u: U, f: F) -> T where
     F: FnOnce(U) -> T,
 {
     thread_local!(static DEPTH: Cell<uint> = Cell::new(0));
-    if !do_it { return f(u); }
+    if !do_it { return trace::span(what, move || f(u)); }
 
     let old = DEPTH.with(|slot| {
         let r = slot.get();
@@ -45,7 +47,7 @@ pub fn time<T, U, F>(do_it: bool, what: &str, u: U, f: F) -> T where
         let ref mut rvp = rv;
 
         Duration::span(move || {
-            *rvp = Some(f(u.take().unwrap()))
+            *rvp = Some(trace::span(what, move || f(u.take().unwrap())))
         })
     };
     let rv = rv.unwrap();
@@ -15,6 +15,7 @@
 use std::collections::hash_state::{DefaultState};
 use std::collections::{HashMap, HashSet};
 use std::default::Default;
+use std::num::Int;
 use std::hash::{Hasher, Writer};
 use syntax::ast;
 
@@ -88,7 +89,7 @@ impl Writer for FnvHasher {
         let FnvHasher(mut hash) = *self;
         for byte in bytes.iter() {
             hash = hash ^ (*byte as u64);
-            hash = hash * 0x100000001b3;
+            hash = hash.wrapping_mul(0x100000001b3);
         }
         *self = FnvHasher(hash);
     }
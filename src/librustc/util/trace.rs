@@ -0,0 +1,305 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Profiles of a compilation in the Trace Event format, as read by
+//! `chrome://tracing` and other trace viewers.
+//!
+//! With `-Z trace-passes=FILE`, every pass timed by `util::common::time`
+//! becomes a span of the trace, whether or not `-Z time-passes` is given as
+//! well. Each outermost span of a thread, such as a top-level pass or a
+//! codegen unit, is followed by a sample of the memory in use at its end.
+//! A thread only records spans while it is attached to the trace, which the
+//! codegen workers do for the duration of the codegen units they process.
+
+use serialize::json::{Json, ToJson};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::io::{self, File};
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub struct Trace {
+    /// The time the trace was started at, in nanoseconds; all events are
+    /// relative to it.
+    start: u64,
+    events: Mutex<Vec<Json>>,
+    next_tid: AtomicUsize,
+}
+
+thread_local!(static CURRENT: RefCell<Option<(Arc<Trace>, uint)>> = RefCell::new(None));
+
+// How many spans the current thread is in.
+thread_local!(static DEPTH: Cell<uint> = Cell::new(0));
+
+/// The trace started by `record`. It is written out when this is dropped,
+/// which also happens when the compilation is aborted by a fatal error.
+pub struct TraceFile {
+    trace: Arc<Trace>,
+    path: Path,
+    _attached: Attached,
+}
+
+/// Keeps the current thread attached to a trace until dropped.
+pub struct Attached {
+    previous: Option<(Arc<Trace>, uint)>,
+}
+
+/// Starts a new trace, attaching the current thread to it, which is
+/// written to `path` once the returned `TraceFile` is dropped.
+pub fn record(path: Path) -> TraceFile {
+    let trace = Arc::new(Trace {
+        start: precise_time_ns(),
+        events: Mutex::new(Vec::new()),
+        next_tid: AtomicUsize::new(0),
+    });
+    let attached = attach(trace.clone(), "rustc");
+    TraceFile { trace: trace, path: path, _attached: attached }
+}
+
+/// The trace the current thread is attached to, if any.
+pub fn current() -> Option<Arc<Trace>> {
+    CURRENT.with(|c| c.borrow().as_ref().map(|&(ref trace, _)| trace.clone()))
+}
+
+pub fn is_recording() -> bool {
+    CURRENT.with(|c| c.borrow().is_some())
+}
+
+/// Attaches the current thread to `trace`, in which it shows up as
+/// `thread_name`.
+pub fn attach(trace: Arc<Trace>, thread_name: &str) -> Attached {
+    let tid = trace.next_tid.fetch_add(1, Ordering::SeqCst);
+    let mut event = trace.event("thread_name", "M", tid, trace.start);
+    let mut args = BTreeMap::new();
+    args.insert("name".to_string(), thread_name.to_json());
+    event.insert("args".to_string(), Json::Object(args));
+    trace.push(event);
+
+    let previous = CURRENT.with(|c| mem::replace(&mut *c.borrow_mut(), Some((trace, tid))));
+    Attached { previous: previous }
+}
+
+/// Runs `f`, recording it as a span called `name` if the current thread is
+/// attached to a trace.
+pub fn span<T, F>(name: &str, f: F) -> T where
+    F: FnOnce() -> T,
+{
+    let current = CURRENT.with(|c| c.borrow().clone());
+    let (trace, tid) = match current {
+        Some(current) => current,
+        None => return f(),
+    };
+
+    let depth = DEPTH.with(|d| {
+        let depth = d.get();
+        d.set(depth + 1);
+        depth
+    });
+    let start = precise_time_ns();
+    let rv = f();
+    let end = precise_time_ns();
+    DEPTH.with(|d| d.set(depth));
+
+    let mut event = trace.event(name, "X", tid, start);
+    event.insert("dur".to_string(), micros(end - start).to_json());
+    trace.push(event);
+
+    // Reading the memory in use isn't free, and the spans inside of a pass
+    // are too short for it to change much.
+    if depth > 0 {
+        return rv;
+    }
+    if let Some(bytes) = resident_memory() {
+        let mut event = trace.event("memory", "C", tid, end);
+        let mut args = BTreeMap::new();
+        args.insert("resident MB".to_string(), (bytes as f64 / 1e6).to_json());
+        event.insert("args".to_string(), Json::Object(args));
+        trace.push(event);
+    }
+    rv
+}
+
+/// Records an event without a duration, carrying the values `args`, if the
+/// current thread is attached to a trace.
+pub fn instant(name: &str, args: BTreeMap<String, Json>) {
+    let current = CURRENT.with(|c| c.borrow().clone());
+    if let Some((trace, tid)) = current {
+        let mut event = trace.event(name, "i", tid, precise_time_ns());
+        event.insert("s".to_string(), "p".to_json());
+        event.insert("args".to_string(), Json::Object(args));
+        trace.push(event);
+    }
+}
+
+impl Trace {
+    fn event(&self, name: &str, phase: &str, tid: uint, time: u64) -> BTreeMap<String, Json> {
+        let mut event = BTreeMap::new();
+        event.insert("name".to_string(), name.to_json());
+        event.insert("cat".to_string(), "rustc".to_json());
+        event.insert("ph".to_string(), phase.to_json());
+        event.insert("pid".to_string(), 0u.to_json());
+        event.insert("tid".to_string(), tid.to_json());
+        event.insert("ts".to_string(), micros(time - self.start).to_json());
+        event
+    }
+
+    fn push(&self, event: BTreeMap<String, Json>) {
+        self.events.lock().unwrap().push(Json::Object(event));
+    }
+
+    fn write(&self, path: &Path) -> io::IoResult<()> {
+        let mut trace = BTreeMap::new();
+        trace.insert("traceEvents".to_string(),
+                     Json::Array(self.events.lock().unwrap().clone()));
+        trace.insert("displayTimeUnit".to_string(), "ms".to_json());
+        let mut file = try!(File::create(path));
+        write!(&mut file, "{}", Json::Object(trace))
+    }
+}
+
+impl Drop for TraceFile {
+    fn drop(&mut self) {
+        if let Err(e) = self.trace.write(&self.path) {
+            // There is no session to report this to any more.
+            let _ = writeln!(&mut io::stderr(), "error: couldn't write trace to {}: {}",
+                             self.path.display(), e);
+        }
+    }
+}
+
+impl Drop for Attached {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT.with(|c| *c.borrow_mut() = previous);
+    }
+}
+
+fn micros(ns: u64) -> f64 {
+    ns as f64 / 1000.0
+}
+
+/// The resident set size of the process in bytes, where we know how to
+/// find it out.
+#[cfg(target_os = "linux")]
+fn resident_memory() -> Option<u64> {
+    use std::os;
+
+    let statm = match File::open(&Path::new("/proc/self/statm")).read_to_string() {
+        Ok(statm) => statm,
+        Err(..) => return None,
+    };
+    // The second field is the number of resident pages.
+    statm.words().nth(1)
+         .and_then(|pages| pages.parse::<u64>())
+         .map(|pages| pages * os::page_size() as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn resident_memory() -> Option<u64> {
+    None
+}
+
+// FIXME: use the clock from std::time once it is public.
+#[cfg(windows)]
+fn precise_time_ns() -> u64 {
+    use libc;
+
+    let mut ticks_per_s = 0;
+    let mut ticks = 0;
+    unsafe {
+        libc::QueryPerformanceFrequency(&mut ticks_per_s);
+        libc::QueryPerformanceCounter(&mut ticks);
+    }
+    let ticks_per_s = if ticks_per_s == 0 { 1 } else { ticks_per_s };
+    (ticks as u64 * 1000000000) / (ticks_per_s as u64)
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn precise_time_ns() -> u64 {
+    use libc::{c_int, mach_timebase_info};
+
+    extern {
+        fn mach_absolute_time() -> u64;
+        fn mach_timebase_info(info: *mut mach_timebase_info) -> c_int;
+    }
+
+    let mut timebase = mach_timebase_info { numer: 0, denom: 0 };
+    unsafe {
+        mach_timebase_info(&mut timebase);
+        mach_absolute_time() * timebase.numer as u64 / timebase.denom as u64
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "ios")))]
+fn precise_time_ns() -> u64 {
+    use libc::{self, c_int, timespec};
+
+    extern {
+        fn clock_gettime(clk_id: c_int, tp: *mut timespec) -> c_int;
+    }
+
+    let mut ts = timespec { tv_sec: 0, tv_nsec: 0 };
+    unsafe {
+        clock_gettime(libc::CLOCK_MONOTONIC, &mut ts);
+    }
+    (ts.tv_sec as u64) * 1000000000 + (ts.tv_nsec as u64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{record, span, is_recording};
+
+    use serialize::json;
+    use std::io::{File, TempDir};
+
+    #[test]
+    fn test_spans_are_written_on_drop() {
+        let dir = TempDir::new("trace").unwrap();
+        let path = dir.path().join("trace.json");
+        {
+            let _trace = record(path.clone());
+            span("outer", || span("inner", || ()));
+        }
+        assert!(!is_recording());
+
+        let contents = File::open(&path).read_to_string().unwrap();
+        let trace = json::from_str(&contents[]).unwrap();
+        let spans = trace["traceEvents"].as_array().unwrap().iter().filter(|e| {
+            e["ph"].as_string() == Some("X")
+        }).map(|e| e["name"].as_string().unwrap().to_string()).collect::<Vec<_>>();
+        // Spans are recorded as they end.
+        assert_eq!(spans, vec!["inner".to_string(), "outer".to_string()]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_memory_is_sampled_after_outermost_spans() {
+        let dir = TempDir::new("trace").unwrap();
+        let path = dir.path().join("trace.json");
+        {
+            let _trace = record(path.clone());
+            span("first", || span("inner", || ()));
+            span("second", || ());
+        }
+
+        let contents = File::open(&path).read_to_string().unwrap();
+        let trace = json::from_str(&contents[]).unwrap();
+        let phases = trace["traceEvents"].as_array().unwrap().iter().filter_map(|e| {
+            match e["ph"].as_string() {
+                Some("X") => Some(e["name"].as_string().unwrap().to_string()),
+                Some("C") => Some("memory".to_string()),
+                _ => None,
+            }
+        }).collect::<Vec<_>>();
+        assert_eq!(phases, vec!["inner".to_string(), "first".to_string(), "memory".to_string(),
+                                "second".to_string(), "memory".to_string()]);
+    }
+}
@@ -19,13 +19,19 @@
 use self::UseError::*;
 
 use borrowck::*;
+use rustc::middle::def;
 use rustc::middle::expr_use_visitor as euv;
 use rustc::middle::mem_categorization as mc;
+use rustc::middle::pat_util;
 use rustc::middle::region;
 use rustc::middle::ty;
+use rustc::middle::ty_fold;
+use rustc::util::nodemap::NodeSet;
 use rustc::util::ppaux::Repr;
 use syntax::ast;
+use syntax::ast_map;
 use syntax::codemap::Span;
+use syntax::visit::{self, Visitor};
 
 use std::rc::Rc;
 
@@ -554,16 +560,68 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
                 old_loan.span,
                 &format!("{}; {}", borrow_summary, rule_summary)[]);
 
+            let later_use = self.find_later_use(old_loan, new_loan);
+            if let Some(span) = later_use.use_span {
+                self.bccx.span_note(span, "previous borrow is later used here");
+            }
+
             let old_loan_span = self.tcx().map.span(old_loan.kill_scope.node_id());
             self.bccx.span_end_note(old_loan_span,
                                     "previous borrow ends here");
 
+            if let euv::AutoRef = old_loan.cause {
+                match (later_use.receiver_arg, later_use.use_span) {
+                    (Some(arg), _) => {
+                        let snippet = self.tcx().sess.codemap().span_to_snippet(arg);
+                        let suggestion = match snippet {
+                            Some(snippet) => format!(": `let tmp = {};`", snippet),
+                            None => String::new(),
+                        };
+                        self.bccx.span_help(
+                            arg,
+                            &format!("evaluate this argument before the method call \
+                                     borrows `{}` by storing it in a temporary{}",
+                                     ol, suggestion)[]);
+                    }
+                    (None, Some(_)) => {
+                        self.bccx.span_help(
+                            old_loan.span,
+                            &format!("the borrow of `{}` for this method call lasts as \
+                                     long as its result is used; consider moving the \
+                                     uses of the result into a block that ends before \
+                                     `{}` is borrowed again",
+                                     ol, nl)[]);
+                    }
+                    (None, None) => {}
+                }
+            }
+
             return false;
         }
 
         true
     }
 
+    /// Looks for what keeps `old_loan` alive at the point where the
+    /// conflicting `new_loan` is made, by walking the scope `old_loan` is
+    /// killed at.
+    fn find_later_use(&self, old_loan: &Loan<'tcx>, new_loan: &Loan<'tcx>) -> LaterUse {
+        let mut finder = LaterUseFinder {
+            tcx: self.tcx(),
+            old_loan: old_loan,
+            new_loan_span: new_loan.span,
+            holders: NodeSet::new(),
+            result: LaterUse { use_span: None, receiver_arg: None },
+        };
+        match self.tcx().map.find(old_loan.kill_scope.node_id()) {
+            Some(ast_map::NodeBlock(block)) => finder.visit_block(block),
+            Some(ast_map::NodeExpr(expr)) => finder.visit_expr(expr),
+            Some(ast_map::NodeStmt(stmt)) => finder.visit_stmt(stmt),
+            _ => {}
+        }
+        finder.result
+    }
+
     fn is_local_variable_or_arg(&self, cmt: mc::cmt<'tcx>) -> bool {
         match cmt.cat {
           mc::cat_local(_) => true,
@@ -963,3 +1021,143 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
                     self.bccx.loan_path_to_string(loan_path))[]);
     }
 }
+
+/// What keeps a loan alive at the point where a conflicting loan is made, as
+/// far as can be told from the source.
+struct LaterUse {
+    /// The first use after the conflicting loan, within the region of the
+    /// loan, of a local holding a reference derived from the borrow.
+    use_span: Option<Span>,
+
+    /// If the loan is the autoref of a method receiver and the conflicting
+    /// loan occurs in one of the arguments of that call, the argument.
+    receiver_arg: Option<Span>,
+}
+
+/// Walks the scope a loan is killed at in source order, tracking the locals
+/// which hold a reference derived from the borrow (directly, or through
+/// another such local).
+struct LaterUseFinder<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    old_loan: &'a Loan<'tcx>,
+    new_loan_span: Span,
+    holders: NodeSet,
+    result: LaterUse,
+}
+
+impl<'a, 'tcx> LaterUseFinder<'a, 'tcx> {
+    /// Whether the value of `expr` may hold on to the borrow.
+    fn holds_borrow(&self, expr: &ast::Expr) -> bool {
+        if span_contains(expr.span, self.old_loan.span) {
+            return true;
+        }
+        let mut mentions = HolderMentions { finder: self, found: false };
+        mentions.visit_expr(expr);
+        mentions.found
+    }
+
+    fn add_holder(&mut self, id: ast::NodeId) {
+        // A local whose type has no references can't keep a borrow alive.
+        let ty = ty::node_id_to_type(self.tcx, id);
+        if !ty_fold::collect_regions(self.tcx, &ty).is_empty() {
+            self.holders.insert(id);
+        }
+    }
+
+    fn in_loan_region(&self, id: ast::NodeId) -> bool {
+        let scope = match self.old_loan.region {
+            ty::ReScope(scope) => scope,
+            ty::ReFree(ref fr) => fr.scope,
+            _ => return false,
+        };
+        self.tcx.region_maps.is_subscope_of(region::CodeExtent::from_node_id(id), scope)
+    }
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for LaterUseFinder<'a, 'tcx> {
+    fn visit_local(&mut self, local: &'v ast::Local) {
+        visit::walk_local(self, local);
+        if let Some(ref init) = local.init {
+            if self.holds_borrow(&**init) {
+                let mut ids = vec![];
+                pat_util::pat_bindings(&self.tcx.def_map, &*local.pat, |_, id, _, _| {
+                    ids.push(id);
+                });
+                for id in ids.into_iter() {
+                    self.add_holder(id);
+                }
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'v ast::Expr) {
+        match expr.node {
+            ast::ExprAssign(ref lhs, ref rhs) => {
+                if let Some(id) = local_id(self.tcx, &**lhs) {
+                    // Assigning to the local is not a use of it.
+                    self.visit_expr(&**rhs);
+                    if self.holds_borrow(&**rhs) {
+                        self.add_holder(id);
+                    }
+                    return;
+                }
+            }
+
+            ast::ExprPath(..) => {
+                if let Some(id) = local_id(self.tcx, expr) {
+                    if self.result.use_span.is_none() &&
+                       self.holders.contains(&id) &&
+                       expr.span.lo >= self.new_loan_span.hi &&
+                       self.in_loan_region(expr.id) {
+                        self.result.use_span = Some(expr.span);
+                    }
+                }
+            }
+
+            ast::ExprMethodCall(_, _, ref args) => {
+                if args[0].span == self.old_loan.span {
+                    for arg in args[1..].iter() {
+                        if span_contains(arg.span, self.new_loan_span) {
+                            self.result.receiver_arg = Some(arg.span);
+                        }
+                    }
+                }
+            }
+
+            _ => {}
+        }
+
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// Looks for a mention of one of the holders of a loan in an expression.
+struct HolderMentions<'b, 'a: 'b, 'tcx: 'a> {
+    finder: &'b LaterUseFinder<'a, 'tcx>,
+    found: bool,
+}
+
+impl<'b, 'a, 'tcx, 'v> Visitor<'v> for HolderMentions<'b, 'a, 'tcx> {
+    fn visit_expr(&mut self, expr: &'v ast::Expr) {
+        if let Some(id) = local_id(self.finder.tcx, expr) {
+            if self.finder.holders.contains(&id) {
+                self.found = true;
+            }
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+fn local_id(tcx: &ty::ctxt, expr: &ast::Expr) -> Option<ast::NodeId> {
+    match expr.node {
+        ast::ExprPath(..) => match tcx.def_map.borrow().get(&expr.id) {
+            Some(&def::DefLocal(id)) => Some(id),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn span_contains(outer: Span, inner: Span) -> bool {
+    outer.lo <= inner.lo && inner.hi <= outer.hi
+}
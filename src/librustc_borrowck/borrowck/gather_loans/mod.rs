@@ -329,6 +329,7 @@ impl<'a, 'tcx> GatherLoanCtxt<'a, 'tcx> {
                     kind: req_kind,
                     gen_scope: gen_scope,
                     kill_scope: kill_scope,
+                    region: loan_region,
                     span: borrow_span,
                     restricted_paths: restricted_paths,
                     cause: cause,
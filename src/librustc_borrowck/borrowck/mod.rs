@@ -34,6 +34,7 @@ use syntax::ast_map;
 use syntax::ast_map::blocks::{FnLikeNode, FnParts};
 use syntax::ast_util;
 use syntax::codemap::Span;
+use syntax::diagnostic::MaybeIncorrect;
 use syntax::parse::token;
 use syntax::visit;
 use syntax::visit::{Visitor, FnKind};
@@ -268,6 +269,11 @@ pub struct Loan<'tcx> {
     /// which roots the loan-path goes out of scope, whichever happens
     /// faster. See also `GatherLoanCtxt::compute_kill_scope`.
     kill_scope: region::CodeExtent,
+
+    /// The region the borrowed reference must be valid for, as inferred by
+    /// regionck. Uses of the reference within this region keep the loan
+    /// alive.
+    region: ty::Region,
     span: Span,
     cause: euv::LoanCause,
 }
@@ -675,6 +681,25 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
             &format!("re-assignment of immutable variable `{}`",
                     self.loan_path_to_string(lp))[]);
         self.tcx.sess.span_note(assign.span, "prior assignment occurs here");
+        if let LpVar(id) = lp.kind {
+            self.suggest_mut_binding(id);
+        }
+    }
+
+    /// If the variable `id` is bound immutably by value, suggests declaring
+    /// it as mutable instead.
+    fn suggest_mut_binding(&self, id: ast::NodeId) {
+        let pat = match self.tcx.map.find(id) {
+            Some(ast_map::NodeLocal(pat)) | Some(ast_map::NodeArg(pat)) => pat,
+            _ => return,
+        };
+        if let ast::PatIdent(ast::BindByValue(ast::MutImmutable), ident, _) = pat.node {
+            self.tcx.sess.span_suggestion(
+                ident.span,
+                "consider making the variable mutable",
+                format!("mut {}", token::get_ident(ident.node)),
+                MaybeIncorrect);
+        }
     }
 
     pub fn span_err(&self, s: Span, m: &str) {
@@ -852,7 +877,11 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
                                  self by mutable reference");
                         }
                     }
-                    _ => {}
+                    _ => {
+                        if let mc::cat_local(id) = err.cmt.cat {
+                            self.suggest_mut_binding(id);
+                        }
+                    }
                 }
             }
 
@@ -20,6 +20,7 @@ use rustc::plugin::load::Plugins;
 use rustc::plugin::registry::Registry;
 use rustc::plugin;
 use rustc::util::common::time;
+use rustc::util::trace;
 use rustc_borrowck as borrowck;
 use rustc_resolve as resolve;
 use rustc_trans::back::link;
@@ -58,6 +59,11 @@ pub fn compile_input(sess: Session,
         }
     })}
 
+    // Written out when this goes out of scope, however the compilation ends.
+    let _trace = sess.opts.debugging_opts.trace_passes.as_ref().map(|path| {
+        trace::record(Path::new(path.clone()))
+    });
+
     // We need nested scopes here, because the intermediate results can keep
     // large chunks of memory alive and we want to free them as soon as
     // possible to keep the peak memory usage low
@@ -128,6 +134,9 @@ pub fn compile_input(sess: Session,
             println!("Post-trans");
             tcx.print_debug_stats();
         }
+        if tcx.sess.meta_stats() {
+            tcx.sess.cstore.print_lookup_stats();
+        }
 
         // Discard interned strings as they are no longer required.
         token::get_ident_interner().clear();
@@ -431,6 +440,8 @@ pub fn phase_2_configure_and_expand(sess: &Session,
         super::describe_lints(&*sess.lint_store.borrow(), true);
         return None;
     }
+    // Levels from the command line override those of a lint config file.
+    sess.lint_store.borrow_mut().process_config_file(sess);
     sess.lint_store.borrow_mut().process_command_line(sess);
 
     // Abort if there are errors from lint processing or a plugin registrar.
@@ -576,7 +587,7 @@ pub fn phase_3_run_analysis_passes<'tcx>(sess: Session,
          middle::check_loop::check_crate(&sess, krate));
 
     let stability_index = time(time_passes, "stability index", (), |_|
-                               stability::Index::build(krate));
+                               stability::Index::build(&sess, krate));
 
     time(time_passes, "static item recursion checking", (), |_|
          middle::check_static_recursion::check_crate(&sess, krate, &def_map, &ast_map));
@@ -769,7 +780,7 @@ fn write_out_deps(sess: &Session,
         // write Makefile-compatible dependency rules
         let files: Vec<String> = sess.codemap().files.borrow()
                                    .iter().filter(|fmap| fmap.is_real_file())
-                                   .map(|fmap| escape_dep_filename(&fmap.name[]))
+                                   .map(|fmap| escape_dep_filename(fmap.original_name()))
                                    .collect();
         let mut file = try!(io::File::create(&deps_filename));
         for path in out_filenames.iter() {
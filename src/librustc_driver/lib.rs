@@ -528,10 +528,31 @@ fn print_crate_info(sess: &Session,
                     -> bool {
     if sess.opts.prints.len() == 0 { return false }
 
-    let attrs = input.map(|input| parse_crate_attrs(sess, input));
+    // The input of `--print crate-metadata` is a library, not source.
+    let needs_attrs = sess.opts.prints.iter().any(|req| {
+        *req == PrintRequest::FileNames || *req == PrintRequest::CrateName
+    });
+    let attrs = if needs_attrs {
+        input.map(|input| parse_crate_attrs(sess, input))
+    } else {
+        None
+    };
     for req in sess.opts.prints.iter() {
         match *req {
             PrintRequest::Sysroot => println!("{}", sess.sysroot().display()),
+            PrintRequest::CrateMetadata => {
+                let path = match input {
+                    Some(&Input::File(ref path)) => path,
+                    Some(&Input::Str(_)) => early_error("can not list metadata for stdin"),
+                    None => early_error("no input file provided"),
+                };
+                let is_osx = sess.target.target.options.is_like_osx;
+                let mut stdout = io::stdout();
+                if let Err(msg) = metadata::loader::list_file_metadata_json(is_osx, path,
+                                                                           &mut stdout) {
+                    sess.fatal(&msg[]);
+                }
+            }
             PrintRequest::FileNames |
             PrintRequest::CrateName => {
                 let input = match input {
@@ -691,6 +691,8 @@ extern {
     pub fn LLVMAddNamedMetadataOperand(M: ModuleRef,
                                        Str: *const c_char,
                                        Val: ValueRef);
+    pub fn LLVMGetNamedMetadataNumOperands(M: ModuleRef, Str: *const c_char) -> c_uint;
+    pub fn LLVMGetNamedMetadataOperands(M: ModuleRef, Str: *const c_char, Dest: *mut ValueRef);
 
     /* Operations on scalar constants */
     pub fn LLVMConstInt(IntTy: TypeRef, N: c_ulonglong, SignExtend: Bool)
@@ -1700,6 +1702,8 @@ extern {
 
     /// Print the pass timings since static dtors aren't picking them up.
     pub fn LLVMRustPrintPassTimings();
+    /// Like `LLVMRustPrintPassTimings`, but into a string.
+    pub fn LLVMRustPrintPassTimingsToString(s: RustStringRef);
 
     pub fn LLVMStructCreateNamed(C: ContextRef, Name: *const c_char) -> TypeRef;
 
@@ -2018,6 +2022,19 @@ extern {
                                       syms: *const *const c_char,
                                       len: size_t);
     pub fn LLVMRustMarkAllFunctionsNounwind(M: ModuleRef);
+    pub fn LLVMRustInsertProfileCounters(M: ModuleRef, Filename: *const c_char) -> bool;
+    pub fn LLVMRustApplyProfileData(M: ModuleRef, Path: *const c_char) -> bool;
+    pub fn LLVMRustParseBitcode(C: ContextRef, bc: *const c_char, len: size_t) -> ModuleRef;
+    pub fn LLVMRustThinLTOSummarize(M: ModuleRef, s: RustStringRef) -> bool;
+    pub fn LLVMRustThinLTOExtract(M: ModuleRef,
+                                  names: *const *const c_char,
+                                  num_names: size_t,
+                                  s: RustStringRef);
+    pub fn LLVMRustThinLTOImport(M: ModuleRef,
+                                 bc: *const c_char,
+                                 len: size_t,
+                                 names: *const *const c_char,
+                                 num_names: size_t) -> bool;
 
     pub fn LLVMRustOpenArchive(path: *const c_char) -> ArchiveRef;
     pub fn LLVMRustArchiveReadSection(AR: ArchiveRef, name: *const c_char,
@@ -2208,6 +2225,13 @@ pub fn build_string<F>(f: F) -> Option<String> where F: FnOnce(RustStringRef){
     String::from_utf8(buf.into_inner()).ok()
 }
 
+/// Like `build_string`, but for output that isn't text.
+pub fn build_bytes<F>(f: F) -> Vec<u8> where F: FnOnce(RustStringRef) {
+    let mut buf = RefCell::new(Vec::new());
+    f(&mut buf as RustStringRepr as RustStringRef);
+    buf.into_inner()
+}
+
 pub unsafe fn twine_to_string(tr: TwineRef) -> String {
     build_string(|s| LLVMWriteTwineToString(tr, s))
         .expect("got a non-UTF8 Twine from LLVM")
@@ -57,16 +57,20 @@ impl<'a, 'b, 'tcx> UnusedImportCheckVisitor<'a, 'b, 'tcx> {
     // which are used. In particular, this means that if an import could name either a
     // public or private item, we will check the correct thing, dependent on how the import
     // is used.
-    fn finalize_import(&mut self, id: ast::NodeId, span: Span) {
+    //
+    // `item_span` is the span of the whole `use` item if removing it is the
+    // way to get rid of this import.
+    fn finalize_import(&mut self, id: ast::NodeId, span: Span, item_span: Option<Span>) {
         debug!("finalizing import uses for {:?}",
                 self.session.codemap().span_to_snippet(span));
 
         if !self.used_imports.contains(&(id, TypeNS)) &&
            !self.used_imports.contains(&(id, ValueNS)) {
-            self.session.add_lint(lint::builtin::UNUSED_IMPORTS,
-                                  id,
-                                  span,
-                                  "unused import".to_string());
+            self.session.add_lint_with_suggestion(lint::builtin::UNUSED_IMPORTS,
+                                                  id,
+                                                  span,
+                                                  "unused import".to_string(),
+                                                  item_span.map(remove_import));
         }
 
         let (v_priv, t_priv) = match self.last_private.get(&id) {
@@ -133,22 +137,23 @@ impl<'a, 'b, 'v, 'tcx> Visitor<'v> for UnusedImportCheckVisitor<'a, 'b, 'tcx> {
             ViewItemUse(ref p) => {
                 match p.node {
                     ViewPathSimple(_, _, id) => {
-                        self.finalize_import(id, p.span)
+                        self.finalize_import(id, p.span, Some(vi.span))
                     }
 
                     ViewPathList(_, ref list, _) => {
                         for i in list.iter() {
-                            self.finalize_import(i.node.id(), i.span);
+                            self.finalize_import(i.node.id(), i.span, None);
                         }
                     }
                     ViewPathGlob(_, id) => {
                         if !self.used_imports.contains(&(id, TypeNS)) &&
                            !self.used_imports.contains(&(id, ValueNS)) {
                             self.session
-                                .add_lint(lint::builtin::UNUSED_IMPORTS,
-                                          id,
-                                          p.span,
-                                          "unused import".to_string());
+                                .add_lint_with_suggestion(lint::builtin::UNUSED_IMPORTS,
+                                                          id,
+                                                          p.span,
+                                                          "unused import".to_string(),
+                                                          Some(remove_import(vi.span)));
                         }
                     }
                 }
@@ -159,6 +164,14 @@ impl<'a, 'b, 'v, 'tcx> Visitor<'v> for UnusedImportCheckVisitor<'a, 'b, 'tcx> {
     }
 }
 
+fn remove_import(item_span: Span) -> lint::LintSuggestion {
+    lint::LintSuggestion {
+        span: item_span,
+        msg: "remove the import".to_string(),
+        replacement: String::new(),
+    }
+}
+
 pub fn check_crate(resolver: &mut Resolver, krate: &ast::Crate) {
     let mut visitor = UnusedImportCheckVisitor { resolver: resolver };
     visit::walk_crate(&mut visitor, krate);
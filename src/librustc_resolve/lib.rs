@@ -84,6 +84,7 @@ use syntax::attr::AttrMetaMethods;
 use syntax::ext::mtwt;
 use syntax::parse::token::{self, special_names, special_idents};
 use syntax::codemap::{Span, Pos};
+use syntax::diagnostic::MaybeIncorrect;
 use syntax::owned_slice::OwnedSlice;
 use syntax::visit::{self, Visitor};
 
@@ -4470,16 +4471,20 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                              `self` argument is missing?");
                                 } else {
                                     let last_name = path.segments.last().unwrap().identifier.name;
+                                    let mut replacement = None;
                                     let mut msg = match self.find_fallback_in_self_type(last_name) {
                                         NoSuggestion => {
                                             // limit search to 5 to reduce the number
                                             // of stupid suggestions
-                                            self.find_best_match_for_name(path_name.as_slice(), 5)
-                                                                .map_or("".to_string(),
+                                            replacement = self.find_best_match_for_name(
+                                                path_name.as_slice(), 5);
+                                            replacement.as_ref().map_or("".to_string(),
                                                                         |x| format!("`{}`", x))
                                         }
-                                        Field =>
-                                            format!("`self.{}`", path_name),
+                                        Field => {
+                                            replacement = Some(format!("self.{}", path_name));
+                                            format!("`self.{}`", path_name)
+                                        }
                                         Method
                                         | TraitItem =>
                                             format!("to call `self.{}`", path_name),
@@ -4497,6 +4502,13 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                         format!("unresolved name `{}`{}",
                                                 path_name,
                                                 msg).as_slice());
+                                    if let (true, Some(replacement)) = (self.emit_errors,
+                                                                        replacement) {
+                                        self.session.span_suggestion(expr.span,
+                                                                     "try this",
+                                                                     replacement,
+                                                                     MaybeIncorrect);
+                                    }
                                 }
                             }
                         }
@@ -774,6 +774,20 @@ fn link_natively(sess: &Session, trans: &CrateTranslation, dylib: bool,
     link_args(&mut cmd, sess, dylib, tmpdir.path(),
               trans, obj_filename, out_filename);
     cmd.args(&sess.target.target.options.post_link_args[]);
+    // Instrumented code needs the profiling runtime to write its counters out.
+    if sess.opts.cg.profile_generate.is_some() || sess.opts.debugging_opts.coverage {
+        cmd.arg("-lprofiler-rt");
+    }
+    // The sanitizer runtimes intercept functions of the C library, which only
+    // works if they are linked into the executable as a whole.
+    if let Some(sanitizer) = sess.opts.debugging_opts.sanitizer {
+        if !dylib {
+            cmd.arg("-Wl,--whole-archive")
+               .arg(format!("-l{}", sanitizer.runtime_name()))
+               .arg("-Wl,--no-whole-archive")
+               .args(&["-lpthread", "-lrt", "-ldl", "-lm", "-rdynamic"]);
+        }
+    }
     if !sess.target.target.options.no_compiler_rt {
         cmd.arg("-lcompiler-rt");
     }
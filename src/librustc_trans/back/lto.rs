@@ -13,17 +13,24 @@ use super::write;
 use rustc::session::{self, config};
 use llvm;
 use llvm::archive_ro::ArchiveRO;
-use llvm::{ModuleRef, TargetMachineRef, True, False};
+use llvm::{ContextRef, ModuleRef, TargetMachineRef, True, False};
 use rustc::metadata::cstore;
 use rustc::util::common::time;
+use trans::ModuleTranslation;
+use syntax::diagnostic::Handler;
 
 use libc;
 use flate;
 
+use std::cmp;
+use std::collections::{HashMap, HashSet};
 use std::ffi::CString;
 use std::iter;
 use std::mem;
 use std::num::Int;
+use std::sync::Mutex;
+use std::sync::mpsc::channel;
+use std::thread;
 
 pub fn run(sess: &session::Session, llmod: ModuleRef,
            tm: TargetMachineRef, reachable: &[String]) {
@@ -47,11 +54,81 @@ pub fn run(sess: &session::Session, llmod: ModuleRef,
     // For each of our upstream dependencies, find the corresponding rlib and
     // load the bitcode from the archive. Then merge it into the current LLVM
     // module that we've got.
+    each_upstream_bytecode(sess, true, |name, i, bc_decoded| {
+        let ptr = bc_decoded.as_ptr();
+        debug!("linking {}, part {}", name, i);
+        time(sess.time_passes(),
+             &format!("ll link {}.{}", name, i)[],
+             (),
+             |()| unsafe {
+            if !llvm::LLVMRustLinkInExternalBitcode(llmod,
+                                                    ptr as *const libc::c_char,
+                                                    bc_decoded.len() as libc::size_t) {
+                write::llvm_err(sess.diagnostic().handler(),
+                                format!("failed to load bc of `{}`",
+                                        name));
+            }
+        });
+    });
+
+    // Internalize everything but the reachable symbols of the current module
+    let cstrs: Vec<CString> = reachable.iter().map(|s| {
+        CString::from_slice(s.as_bytes())
+    }).collect();
+    let arr: Vec<*const libc::c_char> = cstrs.iter().map(|c| c.as_ptr()).collect();
+    let ptr = arr.as_ptr();
+    unsafe {
+        llvm::LLVMRustRunRestrictionPass(llmod,
+                                         ptr as *const *const libc::c_char,
+                                         arr.len() as libc::size_t);
+    }
+
+    if sess.no_landing_pads() {
+        unsafe {
+            llvm::LLVMRustMarkAllFunctionsNounwind(llmod);
+        }
+    }
+
+    // Now we have one massive module inside of llmod. Time to run the
+    // LTO-specific optimization passes that LLVM provides.
+    //
+    // This code is based off the code found in llvm's LTO code generator:
+    //      tools/lto/LTOCodeGenerator.cpp
+    debug!("running the pass manager");
+    unsafe {
+        let pm = llvm::LLVMCreatePassManager();
+        llvm::LLVMRustAddAnalysisPasses(tm, pm, llmod);
+        llvm::LLVMRustAddPass(pm, "verify\0".as_ptr() as *const _);
+
+        let builder = llvm::LLVMPassManagerBuilderCreate();
+        llvm::LLVMPassManagerBuilderPopulateLTOPassManager(builder, pm,
+            /* Internalize = */ False,
+            /* RunInliner = */ True);
+        llvm::LLVMPassManagerBuilderDispose(builder);
+
+        llvm::LLVMRustAddPass(pm, "verify\0".as_ptr() as *const _);
+
+        time(sess.time_passes(), "LTO passes", (), |()|
+             llvm::LLVMRunPassManager(pm, llmod));
+
+        llvm::LLVMDisposePassManager(pm);
+    }
+    debug!("lto done");
+}
+
+/// Calls `f` with the name of each upstream crate linked statically, along
+/// with the inflated bytecode of each of its codegen units. Crates without
+/// an rlib or without bytecode are an error if `required` is set, and are
+/// skipped otherwise.
+fn each_upstream_bytecode<F>(sess: &session::Session, required: bool, mut f: F) where
+    F: FnMut(&str, uint, &[u8]),
+{
     let crates = sess.cstore.get_used_crates(cstore::RequireStatic);
     for (cnum, path) in crates.into_iter() {
         let name = sess.cstore.get_crate_data(cnum).name.clone();
         let path = match path {
             Some(p) => p,
+            None if !required => continue,
             None => {
                 sess.fatal(&format!("could not find rlib for: `{}`",
                                    name)[]);
@@ -73,7 +150,7 @@ pub fn run(sess: &session::Session, llmod: ModuleRef,
             let bc_encoded = match bc_encoded {
                 Some(data) => data,
                 None => {
-                    if i == 0 {
+                    if i == 0 && required {
                         // No bitcode was found at all.
                         sess.fatal(&format!("missing compressed bytecode in {}",
                                            path.display())[]);
@@ -121,66 +198,278 @@ pub fn run(sess: &session::Session, llmod: ModuleRef,
                 })
             };
 
-            let ptr = bc_decoded.as_slice().as_ptr();
-            debug!("linking {}, part {}", name, i);
-            time(sess.time_passes(),
-                 &format!("ll link {}.{}", name, i)[],
-                 (),
-                 |()| unsafe {
-                if !llvm::LLVMRustLinkInExternalBitcode(llmod,
-                                                        ptr as *const libc::c_char,
-                                                        bc_decoded.len() as libc::size_t) {
-                    write::llvm_err(sess.diagnostic().handler(),
-                                    format!("failed to load bc of `{}`",
-                                            &name[]));
-                }
-            });
+            f(&name[], i, bc_decoded.as_slice());
         }
     }
+}
 
-    // Internalize everything but the reachable symbols of the current module
-    let cstrs: Vec<CString> = reachable.iter().map(|s| {
-        CString::from_slice(s.as_bytes())
-    }).collect();
-    let arr: Vec<*const libc::c_char> = cstrs.iter().map(|c| c.as_ptr()).collect();
-    let ptr = arr.as_ptr();
-    unsafe {
-        llvm::LLVMRustRunRestrictionPass(llmod,
-                                         ptr as *const *const libc::c_char,
-                                         arr.len() as libc::size_t);
+/// The number of instructions up to which a function is imported by
+/// summary-based LTO. The functions called by an imported function are
+/// held to a lower limit the further away they are, while functions known
+/// to be hot (from profile data) are allowed to be larger.
+const IMPORT_INSTR_LIMIT: f64 = 100.0;
+const IMPORT_LIMIT_DECAY: f64 = 0.7;
+const IMPORT_HOT_MULTIPLIER: f64 = 3.0;
+
+/// An exported function, as summarized by LLVM.
+struct FunctionSummary {
+    instrs: uint,
+    /// Whether the function refers to nothing local to its module, which
+    /// couldn't be shared with the importing module.
+    importable: bool,
+    hot: bool,
+    callees: Vec<String>,
+}
+
+/// What summary-based LTO imports into each codegen unit.
+///
+/// Rather than merging everything into one module like `run` does, each
+/// codegen unit gets `available_externally` copies of the small functions
+/// it calls from the other codegen units and from upstream crates. Those
+/// can be inlined, but aren't emitted again, so that every codegen unit
+/// can still be optimized on its own worker thread.
+pub struct ThinLtoData {
+    /// A name for every module functions are imported from, along with the
+    /// bitcode of just the functions imported from it. The crate's codegen
+    /// units come first, in order.
+    modules: Vec<(String, Vec<u8>)>,
+    /// For each codegen unit, the modules it imports from, and the names of
+    /// the functions imported from each.
+    imports: Vec<Vec<(uint, Vec<CString>)>>,
+}
+
+impl ThinLtoData {
+    /// Summarizes the codegen units `units` and the upstream crates, and
+    /// decides what each codegen unit imports. This has to be done before
+    /// any of the units are optimized. Parsing, summarizing and extracting
+    /// the imported functions from each module is spread over the same
+    /// number of threads as the codegen units are later.
+    pub fn new(sess: &session::Session, units: &[ModuleTranslation]) -> ThinLtoData {
+        let num_workers = sess.opts.cg.codegen_units;
+
+        // Crates only available as dylibs don't come with bytecode; calls
+        // into them simply aren't inlined.
+        let mut upstream = Vec::new();
+        each_upstream_bytecode(sess, false, |name, i, bc| {
+            upstream.push((format!("{}.{}", name, i), bc.to_vec()));
+        });
+        let parsed = map_on_workers(upstream.iter().collect(), num_workers,
+                                    |&: &(ref name, ref bc): &(String, Vec<u8>)| {
+            SourceModule::parse(&name[], &bc[])
+        });
+
+        let mut module_names = range(0, units.len()).map(|i| format!("codegen unit {}", i))
+                                                    .collect::<Vec<_>>();
+        let mut sources = units.iter().map(|unit| SourceModule::Unit(unit.llmod))
+                                      .collect::<Vec<_>>();
+        for ((name, _), module) in upstream.into_iter().zip(parsed.into_iter()) {
+            match module {
+                Ok(module) => sources.push(module),
+                Err(msg) => sess.fatal(&msg[]),
+            }
+            module_names.push(name);
+        }
+
+        let summaries = map_on_workers(sources.iter().zip(module_names.iter()).collect(),
+                                       num_workers,
+                                       |&: (source, name): (&SourceModule, &String)| {
+            summarize(source.llmod(), &name[])
+        }).into_iter().map(|summary| match summary {
+            Ok(summary) => summary,
+            Err(msg) => sess.fatal(&msg[]),
+        }).collect::<Vec<_>>();
+
+        // Where each function is defined. Should several modules define the
+        // same function, any of the definitions will do.
+        let mut index = HashMap::new();
+        for (m, summary) in summaries.iter().enumerate() {
+            for (name, function) in summary.iter() {
+                if !index.contains_key(&name.as_slice()) {
+                    index.insert(name.as_slice(), (m, function));
+                }
+            }
+        }
+
+        // Everything imported from each module, by any of the codegen units.
+        let mut extracted = range(0, sources.len()).map(|_| HashSet::new()).collect::<Vec<_>>();
+        let imports = range(0, units.len()).map(|unit| {
+            let defined = &summaries[unit];
+            let mut worklist = defined.values()
+                                      .flat_map(|f| f.callees.iter())
+                                      .filter(|callee| !defined.contains_key(*callee))
+                                      .map(|callee| (&callee[], IMPORT_INSTR_LIMIT))
+                                      .collect::<Vec<_>>();
+            let mut imported = HashSet::new();
+            let mut names = range(0, sources.len()).map(|_| Vec::new()).collect::<Vec<_>>();
+            while let Some((name, limit)) = worklist.pop() {
+                if defined.contains_key(name) || imported.contains(&name) {
+                    continue;
+                }
+                let (m, function) = match index.get(&name) {
+                    Some(&entry) => entry,
+                    None => continue,
+                };
+                let limit = if function.hot { limit * IMPORT_HOT_MULTIPLIER } else { limit };
+                if !function.importable || function.instrs as f64 > limit {
+                    continue;
+                }
+                imported.insert(name);
+                extracted[m].insert(name);
+                names[m].push(CString::from_slice(name.as_bytes()));
+                for callee in function.callees.iter() {
+                    worklist.push((&callee[], limit * IMPORT_LIMIT_DECAY));
+                }
+            }
+            debug!("codegen unit {} imports {} functions", unit, imported.len());
+            names.into_iter().enumerate().filter(|&(_, ref names)| !names.is_empty()).collect()
+        }).collect();
+
+        // Every module is parsed only this once; the codegen units are each
+        // given the much smaller bitcode of just the functions imported.
+        let extracted = extracted.into_iter().map(|names| {
+            names.into_iter().map(|name| CString::from_slice(name.as_bytes())).collect()
+        }).collect::<Vec<Vec<_>>>();
+        let bitcode = map_on_workers(sources.iter().zip(extracted.iter()).collect(), num_workers,
+                                     |&: (source, names): (&SourceModule, &Vec<CString>)| {
+            if names.is_empty() {
+                return Vec::new();
+            }
+            let ptrs = names.iter().map(|n| n.as_ptr()).collect::<Vec<_>>();
+            llvm::build_bytes(|s| unsafe {
+                llvm::LLVMRustThinLTOExtract(source.llmod(),
+                                             ptrs.as_ptr(),
+                                             ptrs.len() as libc::size_t,
+                                             s)
+            })
+        });
+
+        ThinLtoData {
+            modules: module_names.into_iter().zip(bitcode.into_iter()).collect(),
+            imports: imports,
+        }
     }
+}
 
-    if sess.no_landing_pads() {
+/// A module functions may be imported from. Each is only ever used by one
+/// thread at a time.
+enum SourceModule {
+    /// One of the crate's codegen units, which stays with the unit.
+    Unit(ModuleRef),
+    /// The bytecode of an upstream crate, parsed into a context of its own.
+    Upstream(ContextRef, ModuleRef),
+}
+
+unsafe impl Send for SourceModule {}
+unsafe impl Sync for SourceModule {}
+
+impl SourceModule {
+    fn parse(name: &str, bc: &[u8]) -> Result<SourceModule, String> {
         unsafe {
-            llvm::LLVMRustMarkAllFunctionsNounwind(llmod);
+            let llcx = llvm::LLVMContextCreate();
+            let llmod = llvm::LLVMRustParseBitcode(llcx,
+                                                   bc.as_ptr() as *const libc::c_char,
+                                                   bc.len() as libc::size_t);
+            if llmod.is_null() {
+                llvm::LLVMContextDispose(llcx);
+                return Err(write::llvm_err_msg(format!("failed to parse bytecode of {}", name)));
+            }
+            Ok(SourceModule::Upstream(llcx, llmod))
         }
     }
 
-    // Now we have one massive module inside of llmod. Time to run the
-    // LTO-specific optimization passes that LLVM provides.
-    //
-    // This code is based off the code found in llvm's LTO code generator:
-    //      tools/lto/LTOCodeGenerator.cpp
-    debug!("running the pass manager");
-    unsafe {
-        let pm = llvm::LLVMCreatePassManager();
-        llvm::LLVMRustAddAnalysisPasses(tm, pm, llmod);
-        llvm::LLVMRustAddPass(pm, "verify\0".as_ptr() as *const _);
+    fn llmod(&self) -> ModuleRef {
+        match *self {
+            SourceModule::Unit(llmod) | SourceModule::Upstream(_, llmod) => llmod,
+        }
+    }
+}
 
-        let builder = llvm::LLVMPassManagerBuilderCreate();
-        llvm::LLVMPassManagerBuilderPopulateLTOPassManager(builder, pm,
-            /* Internalize = */ False,
-            /* RunInliner = */ True);
-        llvm::LLVMPassManagerBuilderDispose(builder);
+impl Drop for SourceModule {
+    fn drop(&mut self) {
+        if let SourceModule::Upstream(llcx, llmod) = *self {
+            unsafe {
+                llvm::LLVMDisposeModule(llmod);
+                llvm::LLVMContextDispose(llcx);
+            }
+        }
+    }
+}
 
-        llvm::LLVMRustAddPass(pm, "verify\0".as_ptr() as *const _);
+/// Applies `f` to each of `items` on up to `num_workers` threads, and
+/// returns the results in the same order.
+fn map_on_workers<T, R, F>(items: Vec<T>, num_workers: uint, f: F) -> Vec<R> where
+    T: Send, R: Send, F: Fn(T) -> R + Sync,
+{
+    let num_items = items.len();
+    let work = Mutex::new(items.into_iter().enumerate().collect::<Vec<_>>());
+    let (tx, rx) = channel();
+    {
+        let (work, f) = (&work, &f);
+        let _workers = range(0, cmp::min(num_workers, num_items)).map(|i| {
+            let tx = tx.clone();
+            thread::Builder::new().name(format!("thin-lto-{}", i)).scoped(move |:| {
+                loop {
+                    // Avoid holding the lock while working on the item.
+                    let item = work.lock().unwrap().pop();
+                    match item {
+                        Some((j, item)) => tx.send((j, (*f)(item))).unwrap(),
+                        None => break,
+                    }
+                }
+            })
+        }).collect::<Vec<_>>();
+    }
+    drop(tx);
 
-        time(sess.time_passes(), "LTO passes", (), |()|
-             llvm::LLVMRunPassManager(pm, llmod));
+    let mut results = range(0, num_items).map(|_| None).collect::<Vec<_>>();
+    for (j, result) in rx.iter() {
+        results[j] = Some(result);
+    }
+    results.into_iter().map(|r| r.expect("thin lto worker panicked")).collect()
+}
 
-        llvm::LLVMDisposePassManager(pm);
+fn summarize(llmod: ModuleRef, name: &str) -> Result<HashMap<String, FunctionSummary>, String> {
+    let mut ok = false;
+    let summary = llvm::build_string(|s| unsafe {
+        ok = llvm::LLVMRustThinLTOSummarize(llmod, s);
+    });
+    if !ok {
+        return Err(write::llvm_err_msg(format!("failed to summarize {}", name)));
+    }
+    let summary = summary.expect("got a non-UTF8 summary from LLVM");
+
+    // One line per function: its name, its number of instructions, whether
+    // it's importable and hot, and then the functions it calls.
+    Ok(summary.lines().map(|line| {
+        let mut words = line.split(' ');
+        let name = words.next().unwrap().to_string();
+        let instrs = words.next().and_then(|w| w.parse()).unwrap();
+        let importable = words.next() == Some("1");
+        let hot = words.next() == Some("1");
+        (name, FunctionSummary {
+            instrs: instrs,
+            importable: importable,
+            hot: hot,
+            callees: words.map(|w| w.to_string()).collect(),
+        })
+    }).collect())
+}
+
+/// Imports the functions chosen for the codegen unit `unit` into its module
+/// `llmod`, ahead of optimizing it.
+pub unsafe fn import(handler: &Handler, llmod: ModuleRef, data: &ThinLtoData, unit: uint) {
+    for &(m, ref names) in data.imports[unit].iter() {
+        let (ref name, ref bc) = data.modules[m];
+        debug!("importing {} functions from {}", names.len(), name);
+        let ptrs = names.iter().map(|n| n.as_ptr()).collect::<Vec<_>>();
+        if !llvm::LLVMRustThinLTOImport(llmod,
+                                        bc.as_ptr() as *const libc::c_char,
+                                        bc.len() as libc::size_t,
+                                        ptrs.as_ptr(),
+                                        ptrs.len() as libc::size_t) {
+            write::llvm_err(handler, format!("failed to import functions from {}", name));
+        }
     }
-    debug!("lto done");
 }
 
 fn is_versioned_bytecode_format(bc: &[u8]) -> bool {
@@ -18,21 +18,26 @@ use llvm::{ModuleRef, TargetMachineRef, PassManagerRef, DiagnosticInfoRef, Conte
 use llvm::SMDiagnosticRef;
 use trans::{CrateTranslation, ModuleTranslation};
 use util::common::time;
+use util::trace;
 use syntax::codemap;
 use syntax::diagnostic;
 use syntax::diagnostic::{Emitter, Handler, Level, mk_handler};
 
+use std::collections::BTreeMap;
 use std::ffi::{self, CString};
+use std::io;
 use std::io::Command;
 use std::io::fs;
-use std::iter::Unfold;
+use std::iter::{repeat, Unfold};
 use std::ptr;
 use std::str;
 use std::mem;
+use std::os;
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc::channel;
 use std::thread;
 use libc::{self, c_uint, c_int, c_void};
+use serialize::json::ToJson;
 
 #[derive(Clone, Copy, PartialEq, PartialOrd, Ord, Eq)]
 pub enum OutputType {
@@ -44,17 +49,21 @@ pub enum OutputType {
 }
 
 pub fn llvm_err(handler: &diagnostic::Handler, msg: String) -> ! {
+    handler.fatal(&llvm_err_msg(msg)[])
+}
+
+/// Appends the last error reported by LLVM, if any, to `msg`. For threads
+/// without a handler of their own to report it with.
+pub fn llvm_err_msg(msg: String) -> String {
     unsafe {
         let cstr = llvm::LLVMRustGetLastError();
         if cstr == ptr::null() {
-            handler.fatal(&msg[]);
+            msg
         } else {
             let err = ffi::c_str_to_bytes(&cstr);
             let err = String::from_utf8_lossy(err.as_slice()).to_string();
             libc::free(cstr as *mut _);
-            handler.fatal(&format!("{}: {}",
-                                  &msg[],
-                                  &err[])[]);
+            format!("{}: {}", &msg[], &err[])
         }
     }
 }
@@ -263,6 +272,16 @@ struct ModuleConfig {
     /// absolutely no optimizations (used for the metadata module).
     opt_level: Option<llvm::CodeGenOptLevel>,
 
+    /// Some(dir) to instrument the module for profile-guided optimization,
+    /// writing the profile to a .profraw file in `dir`.
+    pgo_gen: Option<Path>,
+    /// Some(file) to optimize the module using the merged profile `file`.
+    pgo_use: Option<Path>,
+    /// Instrument the module to write gcov coverage data.
+    coverage: bool,
+    /// Some(sanitizer) to instrument the module for `sanitizer`.
+    sanitizer: Option<config::Sanitizer>,
+
     // Flags indicating which outputs to produce.
     emit_no_opt_bc: bool,
     emit_bc: bool,
@@ -288,6 +307,11 @@ impl ModuleConfig {
             passes: passes,
             opt_level: None,
 
+            pgo_gen: None,
+            pgo_use: None,
+            coverage: false,
+            sanitizer: None,
+
             emit_no_opt_bc: false,
             emit_bc: false,
             emit_lto_bc: false,
@@ -394,6 +418,7 @@ unsafe extern "C" fn diagnostic_handler(info: DiagnosticInfoRef, user: *mut c_vo
 unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
                                mtrans: ModuleTranslation,
                                config: ModuleConfig,
+                               thin_lto: Option<(Arc<lto::ThinLtoData>, uint)>,
                                name_extra: String,
                                output_names: OutputFilenames) {
     let ModuleTranslation { llmod, llcx } = mtrans;
@@ -421,6 +446,29 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
 
     match config.opt_level {
         Some(opt_level) => {
+            // Profile instrumentation and profile data are applied to the
+            // unoptimized module, so that the optimizations see (and preserve)
+            // them.
+            if let Some(ref dir) = config.pgo_gen {
+                let file = CString::from_slice(dir.join("default.profraw").as_vec());
+                if !llvm::LLVMRustInsertProfileCounters(llmod, file.as_ptr()) {
+                    llvm_err(cgcx.handler, "failed to instrument the module".to_string());
+                }
+            }
+            if let Some(ref file) = config.pgo_use {
+                let path = CString::from_slice(file.as_vec());
+                if !llvm::LLVMRustApplyProfileData(llmod, path.as_ptr()) {
+                    llvm_err(cgcx.handler, format!("failed to load profile data from {}",
+                                                   file.display()));
+                }
+            }
+
+            // The imported functions aren't part of this module's profile.
+            if let Some((ref data, unit)) = thin_lto {
+                time(config.time_passes, "thin lto import", (), |()|
+                     lto::import(cgcx.handler, llmod, &**data, unit));
+            }
+
             // Create the two optimizing pass managers. These mirror what clang
             // does, and are by populated by LLVM's default PassManagerBuilder.
             // Each manager has a different set of passes, but they also share
@@ -439,10 +487,56 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
             if !config.no_prepopulate_passes {
                 llvm::LLVMRustAddAnalysisPasses(tm, fpm, llmod);
                 llvm::LLVMRustAddAnalysisPasses(tm, mpm, llmod);
+            }
+
+            // The coverage instrumentation is added ahead of the optimizations
+            // so that the counters still match the source lines.
+            if config.coverage {
+                let notes = output_names.with_extension(&format!("{}.gcno", name_extra)[]);
+                let data = output_names.with_extension(&format!("{}.gcda", name_extra)[]);
+                add_coverage_files(llcx, llmod, &notes, &data);
+                let pass = CString::from_slice(b"insert-gcov-profiling");
+                if !llvm::LLVMRustAddPass(mpm, pass.as_ptr()) {
+                    llvm_err(cgcx.handler, "-Z coverage needs LLVM's `insert-gcov-profiling` \
+                                            pass, which this LLVM lacks".to_string());
+                }
+            }
+
+            if !config.no_prepopulate_passes {
                 populate_llvm_passes(fpm, mpm, llmod, opt_level,
                                      config.no_builtins);
             }
 
+            // The counters inserted above are lowered to the form the
+            // profiling runtime expects once everything else is done.
+            if config.pgo_gen.is_some() {
+                let pass = CString::from_slice(b"instrprof");
+                if !llvm::LLVMRustAddPass(mpm, pass.as_ptr()) {
+                    llvm_err(cgcx.handler, "-C profile-generate needs LLVM's `instrprof` \
+                                            pass, which this LLVM lacks".to_string());
+                }
+            }
+
+            // The sanitizers instrument the optimized code, so that checks
+            // aren't inserted for accesses which are optimized away.
+            if let Some(sanitizer) = config.sanitizer {
+                let passes: &[&[u8]] = match sanitizer {
+                    config::AddressSanitizer => &[b"asan", b"asan-module"],
+                    config::ThreadSanitizer => &[b"tsan"],
+                    config::MemorySanitizer => &[b"msan"],
+                    config::LeakSanitizer => &[],
+                };
+                for pass in passes.iter() {
+                    let cpass = CString::from_slice(*pass);
+                    if !llvm::LLVMRustAddPass(mpm, cpass.as_ptr()) {
+                        llvm_err(cgcx.handler,
+                                 format!("-Z sanitizer needs LLVM's `{}` pass, which this \
+                                          LLVM lacks",
+                                         String::from_utf8_lossy(*pass)));
+                    }
+                }
+            }
+
             for pass in config.passes.iter() {
                 let pass = CString::from_slice(pass.as_bytes());
                 if !llvm::LLVMRustAddPass(mpm, pass.as_ptr()) {
@@ -553,6 +647,9 @@ pub fn run_passes(sess: &Session,
         // LLVM context, so they can't easily be combined.
         sess.fatal("can't perform LTO when using multiple codegen units");
     }
+    if sess.lto() && sess.opts.debugging_opts.thin_lto {
+        sess.fatal("can't perform LTO and summary-based LTO at the same time");
+    }
 
     // Sanity check
     assert!(trans.modules.len() == sess.opts.cg.codegen_units);
@@ -569,6 +666,10 @@ pub fn run_passes(sess: &Session,
     let mut metadata_config = ModuleConfig::new(tm, vec!());
 
     modules_config.opt_level = Some(get_llvm_opt_level(sess.opts.optimize));
+    modules_config.pgo_gen = sess.opts.cg.profile_generate.as_ref().map(|d| Path::new(d.clone()));
+    modules_config.pgo_use = sess.opts.cg.profile_use.as_ref().map(|f| Path::new(f.clone()));
+    modules_config.coverage = sess.opts.debugging_opts.coverage;
+    modules_config.sanitizer = sess.opts.debugging_opts.sanitizer;
 
     // Save all versions of the bytecode if we're saving our temporaries.
     if sess.opts.cg.save_temps {
@@ -613,6 +714,15 @@ pub fn run_passes(sess: &Session,
     modules_config.set_flags(sess, trans);
     metadata_config.set_flags(sess, trans);
 
+    // The imports are only of use to the optimizations, so don't bother
+    // without them.
+    let thin_lto = if sess.opts.debugging_opts.thin_lto && sess.opts.optimize != config::No {
+        Some(Arc::new(time(sess.time_passes(), "thin lto summaries", (), |()| {
+            lto::ThinLtoData::new(sess, &trans.modules[])
+        })))
+    } else {
+        None
+    };
 
     // Populate a buffer with a list of codegen tasks.  Items are processed in
     // LIFO order, just because it's a tiny bit simpler that way.  (The order
@@ -623,6 +733,7 @@ pub fn run_passes(sess: &Session,
         let work = build_work_item(sess,
                                    trans.metadata_module,
                                    metadata_config.clone(),
+                                   None,
                                    crate_output.clone(),
                                    "metadata".to_string());
         work_items.push(work);
@@ -632,6 +743,7 @@ pub fn run_passes(sess: &Session,
         let work = build_work_item(sess,
                                    *mtrans,
                                    modules_config.clone(),
+                                   thin_lto.clone().map(|data| (data, index)),
                                    crate_output.clone(),
                                    format!("{}", index));
         work_items.push(work);
@@ -649,6 +761,10 @@ pub fn run_passes(sess: &Session,
         llvm::LLVMRustDisposeTargetMachine(tm);
     }
 
+    if trace::is_recording() {
+        record_llvm_pass_timings(sess);
+    }
+
     // Produce final compile outputs.
 
     let copy_if_one_unit = |&: ext: &str, output_type: config::OutputType, keep_numbered: bool| {
@@ -846,6 +962,9 @@ pub fn run_passes(sess: &Session,
 struct WorkItem {
     mtrans: ModuleTranslation,
     config: ModuleConfig,
+    // What to import into the module ahead of optimizing it, along with the
+    // index of its codegen unit.
+    thin_lto: Option<(Arc<lto::ThinLtoData>, uint)>,
     output_names: OutputFilenames,
     name_extra: String
 }
@@ -853,22 +972,26 @@ struct WorkItem {
 fn build_work_item(sess: &Session,
                    mtrans: ModuleTranslation,
                    config: ModuleConfig,
+                   thin_lto: Option<(Arc<lto::ThinLtoData>, uint)>,
                    output_names: OutputFilenames,
                    name_extra: String)
                    -> WorkItem
 {
     let mut config = config;
     config.tm = create_target_machine(sess);
-    WorkItem { mtrans: mtrans, config: config, output_names: output_names,
-               name_extra: name_extra }
+    WorkItem { mtrans: mtrans, config: config, thin_lto: thin_lto,
+               output_names: output_names, name_extra: name_extra }
 }
 
 fn execute_work_item(cgcx: &CodegenContext,
                      work_item: WorkItem) {
-    unsafe {
+    // Only traced; -Z time-passes already times the passes of each unit.
+    let what = format!("codegen unit {}", work_item.name_extra);
+    trace::span(&what[], move || unsafe {
         optimize_and_codegen(cgcx, work_item.mtrans, work_item.config,
-                             work_item.name_extra, work_item.output_names);
-    }
+                             work_item.thin_lto, work_item.name_extra,
+                             work_item.output_names);
+    })
 }
 
 fn run_work_singlethreaded(sess: &Session,
@@ -891,11 +1014,13 @@ fn run_work_multithreaded(sess: &Session,
     let work_items_arc = Arc::new(Mutex::new(work_items));
     let mut diag_emitter = SharedEmitter::new();
     let mut futures = Vec::with_capacity(num_workers);
+    let parent_trace = trace::current();
 
     for i in range(0, num_workers) {
         let work_items_arc = work_items_arc.clone();
         let diag_emitter = diag_emitter.clone();
         let remark = sess.opts.cg.remark.clone();
+        let parent_trace = parent_trace.clone();
 
         let (tx, rx) = channel();
         let mut tx = Some(tx);
@@ -903,6 +1028,9 @@ fn run_work_multithreaded(sess: &Session,
 
         thread::Builder::new().name(format!("codegen-{}", i)).spawn(move |:| {
             let diag_handler = mk_handler(box diag_emitter);
+            let _attached = parent_trace.map(|t| {
+                trace::attach(t, &format!("codegen-{}", i)[])
+            });
 
             // Must construct cgcx inside the proc because it has non-Send
             // fields.
@@ -947,6 +1075,41 @@ fn run_work_multithreaded(sess: &Session,
     }
 }
 
+/// Adds the time LLVM spent in each of its passes to the trace. LLVM only
+/// keeps the total for each pass, so they're recorded as a single event.
+fn record_llvm_pass_timings(sess: &Session) {
+    let report = llvm::build_string(|s| unsafe {
+        llvm::LLVMRustPrintPassTimingsToString(s)
+    }).unwrap_or(String::new());
+    // Collecting the timings keeps LLVM from printing them itself.
+    if sess.time_llvm_passes() {
+        let _ = io::stderr().write_str(&report[]);
+    }
+
+    let mut timings = BTreeMap::new();
+    for line in report.lines() {
+        // Each pass is on a line like
+        //   0.0100 ( 50.0%)   0.0000 (  0.0%)   0.0100 ( 50.0%)   0.0110 ( 52.1%)  Name
+        // where the last of the times is the wall time.
+        let end = match line.rfind(')') {
+            Some(end) => end,
+            None => continue,
+        };
+        let name = line[end + 1..].trim();
+        let wall = line[..end].rfind('(')
+                              .and_then(|start| line[..start].words().last())
+                              .and_then(|wall| wall.parse::<f64>());
+        let wall = match wall {
+            Some(wall) if !name.is_empty() && name != "Total" => wall,
+            _ => continue,
+        };
+        // The same pass can show up in more than one group of timers.
+        let ms = wall * 1000.0 + timings.get(name).and_then(|t| t.as_f64()).unwrap_or(0.0);
+        timings.insert(name.to_string(), ms.to_json());
+    }
+    trace::instant("LLVM pass timings (ms)", timings);
+}
+
 pub fn run_assembler(sess: &Session, outputs: &OutputFilenames) {
     let pname = get_cc_prog(sess);
     let mut cmd = Command::new(&pname[]);
@@ -1000,7 +1163,11 @@ unsafe fn configure_llvm(sess: &Session) {
         add("rustc"); // fake program name
         if vectorize_loop { add("-vectorize-loops"); }
         if vectorize_slp  { add("-vectorize-slp");   }
-        if sess.time_llvm_passes() { add("-time-passes"); }
+        // The trace includes the timings of LLVM's passes, which it only
+        // collects when asked to print them.
+        if sess.time_llvm_passes() || sess.opts.debugging_opts.trace_passes.is_some() {
+            add("-time-passes");
+        }
         if sess.print_llvm_passes() { add("-debug-pass=Structure"); }
 
         for arg in sess.opts.cg.llvm_args.iter() {
@@ -1044,6 +1211,31 @@ unsafe fn configure_llvm(sess: &Session) {
     });
 }
 
+/// Tells the gcov instrumentation of `llmod` to write its notes to `notes`
+/// at compile time, and the program it ends up in to write the counters to
+/// `data` when it exits.
+unsafe fn add_coverage_files(llcx: ContextRef, llmod: ModuleRef, notes: &Path, data: &Path) {
+    // The data is written relative to wherever the program is run from
+    // unless the path is absolute.
+    let data = os::make_absolute(data).unwrap_or_else(|_| data.clone());
+    let mdstr = |&: path: &Path| {
+        let bytes = path.as_vec();
+        llvm::LLVMMDStringInContext(llcx, bytes.as_ptr() as *const _, bytes.len() as c_uint)
+    };
+
+    // There is one entry for each compile unit the module has debuginfo for.
+    let cu = "llvm.dbg.cu\0".as_ptr() as *const _;
+    let num_cus = llvm::LLVMGetNamedMetadataNumOperands(llmod, cu) as uint;
+    let mut cus = repeat(ptr::null_mut()).take(num_cus).collect::<Vec<_>>();
+    llvm::LLVMGetNamedMetadataOperands(llmod, cu, cus.as_mut_ptr());
+    for &cu in cus.iter() {
+        let elts = [mdstr(notes), mdstr(&data), cu];
+        llvm::LLVMAddNamedMetadataOperand(llmod, "llvm.gcov\0".as_ptr() as *const _,
+                                          llvm::LLVMMDNodeInContext(llcx, elts.as_ptr(),
+                                                                    elts.len() as c_uint));
+    }
+}
+
 unsafe fn populate_llvm_passes(fpm: llvm::PassManagerRef,
                                mpm: llvm::PassManagerRef,
                                llmod: ModuleRef,
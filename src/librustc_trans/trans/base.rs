@@ -203,6 +203,10 @@ pub fn decl_fn(ccx: &CrateContext, name: &str, cc: llvm::CallConv,
         llvm::SetFunctionAttribute(llfn, llvm::NoRedZoneAttribute)
     }
 
+    if let Some(sanitizer) = ccx.sess().opts.debugging_opts.sanitizer {
+        set_sanitize(llfn, sanitizer);
+    }
+
     llvm::SetFunctionCallConv(llfn, cc);
     // Function addresses in Rust are never significant, allowing functions to be merged.
     llvm::SetUnnamedAddr(llfn, true);
@@ -480,6 +484,21 @@ pub fn set_llvm_fn_attrs(ccx: &CrateContext, attrs: &[ast::Attribute], llfn: Val
     }
 }
 
+// Tell the sanitizer's instrumentation pass to instrument the function f.
+pub fn set_sanitize(f: ValueRef, sanitizer: config::Sanitizer) {
+    let attr = match sanitizer {
+        config::AddressSanitizer => llvm::SanitizeAddressAttribute,
+        config::ThreadSanitizer => llvm::SanitizeThreadAttribute,
+        config::MemorySanitizer => llvm::SanitizeMemoryAttribute,
+        // The leak sanitizer doesn't instrument anything; it only needs its
+        // runtime.
+        config::LeakSanitizer => return,
+    };
+    unsafe {
+        llvm::LLVMAddFunctionAttribute(f, llvm::FunctionIndex as c_uint, attr as uint64_t)
+    }
+}
+
 pub fn set_always_inline(f: ValueRef) {
     llvm::SetFunctionAttribute(f, llvm::AlwaysInlineAttribute)
 }
@@ -3008,12 +3027,20 @@ pub fn write_metadata(cx: &SharedCrateContext, krate: &ast::Crate) -> Vec<u8> {
 
     let encode_parms = crate_ctxt_to_encode_parms(cx, encode_inlined_item);
     let metadata = encoder::encode_metadata(encode_parms, krate);
-    let mut compressed = encoder::metadata_encoding_version.to_vec();
-    compressed.push_all(match flate::deflate_bytes(metadata.as_slice()) {
-        Some(compressed) => compressed,
-        None => cx.sess().fatal("failed to compress metadata"),
-    }.as_slice());
-    let llmeta = C_bytes_in_context(cx.metadata_llcx(), &compressed[]);
+    let mut section = encoder::metadata_encoding_version.to_vec();
+    if cx.sess().opts.debugging_opts.uncompressed_metadata {
+        // Left as is, the loader can use the metadata straight from the
+        // mapped library instead of inflating a copy of it.
+        section.push(encoder::METADATA_UNCOMPRESSED);
+        section.push_all(metadata.as_slice());
+    } else {
+        section.push(encoder::METADATA_DEFLATED);
+        section.push_all(match flate::deflate_bytes(metadata.as_slice()) {
+            Some(compressed) => compressed,
+            None => cx.sess().fatal("failed to compress metadata"),
+        }.as_slice());
+    }
+    let llmeta = C_bytes_in_context(cx.metadata_llcx(), &section[]);
     let llconst = C_struct_in_context(cx.metadata_llcx(), &[llmeta], false);
     let name = format!("rust_metadata_{}_{}",
                        cx.link_meta().crate_name,
@@ -660,7 +660,25 @@ fn const_expr_unadjusted(cx: &CrateContext, e: &ast::Expr) -> ValueRef {
                                        vinfo.disr_val,
                                        &arg_vals[])
                   }
-                  _ => cx.sess().span_bug(e.span, "expected a struct or variant def")
+                  Some(def::DefFn(def_id, _)) if const_eval::is_const_fn(cx.tcx(), def_id) => {
+                      // Constant functions only return integers and booleans,
+                      // though comparisons evaluate to integers as well.
+                      let ety = ty::expr_ty(cx.tcx(), e);
+                      match const_eval::eval_const_expr(cx.tcx(), e) {
+                          const_eval::const_int(i) if ty::type_is_bool(ety) => C_bool(cx, i != 0),
+                          const_eval::const_bool(b) => C_bool(cx, b),
+                          const_eval::const_int(i) => {
+                              C_integral(type_of::type_of(cx, ety), i as u64, true)
+                          }
+                          const_eval::const_uint(u) => {
+                              C_integral(type_of::type_of(cx, ety), u, false)
+                          }
+                          _ => cx.sess().span_bug(e.span, "constant function returned \
+                                                           a non-integral value")
+                      }
+                  }
+                  _ => cx.sess().span_bug(e.span, "expected a struct, variant or \
+                                                   constant function def")
               }
           }
           ast::ExprParen(ref e) => const_expr(cx, &**e).0,
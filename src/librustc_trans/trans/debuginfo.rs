@@ -1617,11 +1617,18 @@ fn create_DIArray(builder: DIBuilderRef, arr: &[DIDescriptor]) -> DIArray {
 }
 
 fn compile_unit_metadata(cx: &CrateContext) -> DIDescriptor {
+    let path_mapping = cx.sess().codemap().path_mapping();
     let work_dir = &cx.sess().working_dir;
     let compile_unit_name = match cx.sess().local_crate_source_file {
         None => fallback_path(cx),
         Some(ref abs_path) => {
-            if abs_path.is_relative() {
+            // FIXME (#9639): This needs to handle non-utf8 paths
+            let (mapped_path, was_remapped) = path_mapping.map_prefix(abs_path.as_str().unwrap());
+            if was_remapped {
+                // A remapped path no longer says anything about the working
+                // directory, so it is used as it is.
+                CString::from_vec(mapped_path.into_bytes())
+            } else if abs_path.is_relative() {
                 cx.sess().warn("debuginfo: Invalid path to crate's local root source file!");
                 fallback_path(cx)
             } else {
@@ -1651,7 +1658,8 @@ fn compile_unit_metadata(cx: &CrateContext) -> DIDescriptor {
                            (option_env!("CFG_VERSION")).expect("CFG_VERSION"));
 
     let compile_unit_name = compile_unit_name.as_ptr();
-    let work_dir = CString::from_slice(work_dir.as_vec());
+    let (work_dir, _) = path_mapping.map_prefix(work_dir.as_str().unwrap());
+    let work_dir = CString::from_slice(work_dir.as_bytes());
     let producer = CString::from_slice(producer.as_bytes());
     let flags = "\0";
     let split_name = "\0";
@@ -1765,8 +1773,12 @@ fn file_metadata(cx: &CrateContext, full_path: &str) -> DIFile {
 
     debug!("file_metadata: {}", full_path);
 
+    // The file names in the codemap have already been remapped, so the
+    // working directory has to be as well for the prefix to match.
     // FIXME (#9639): This needs to handle non-utf8 paths
-    let work_dir = cx.sess().working_dir.as_str().unwrap();
+    let (work_dir, _) = cx.sess().codemap().path_mapping()
+                          .map_prefix(cx.sess().working_dir.as_str().unwrap());
+    let work_dir = &work_dir[];
     let file_name =
         if full_path.starts_with(work_dir) {
             &full_path[(work_dir.len() + 1u)..full_path.len()]
@@ -68,6 +68,7 @@ use syntax::{ast, ast_util, codemap};
 use syntax::print::pprust::{expr_to_string};
 use syntax::ptr::P;
 use syntax::parse::token;
+use syntax::parse::token::InternedString;
 use std::rc::Rc;
 use std::iter::repeat;
 
@@ -1589,9 +1590,23 @@ fn trans_unary<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
         ast::UnNeg => {
             let datum = unpack_datum!(bcx, trans(bcx, sub_expr));
             let val = datum.to_llscalarish(bcx);
+            // Negated literals such as `-128i8` are in range even though the
+            // literal on its own isn't.
+            let is_lit = match sub_expr.node {
+                ast::ExprLit(..) => true,
+                _ => false
+            };
+            // Unsigned negation is left unchecked: `-x` is how wrapping
+            // negation is written for unsigned integers, and the
+            // `unsigned_negation` lint already points it out.
             let llneg = {
                 if ty::type_is_fp(un_ty) {
                     FNeg(bcx, val)
+                } else if ty::type_is_signed(un_ty) && !ty::type_is_simd(bcx.tcx(), un_ty) &&
+                          !is_lit && bcx.sess().overflow_checks() {
+                    let (new_bcx, llneg) = trans_overflow_checked_neg(bcx, expr.span, val);
+                    bcx = new_bcx;
+                    llneg
                 } else {
                     Neg(bcx, val)
                 }
@@ -1690,10 +1705,20 @@ fn trans_eager_binop<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     let is_float = ty::type_is_fp(intype);
     let is_signed = ty::type_is_signed(intype);
 
+    // The shift amount is checked before it's cast to the width of `lhs`,
+    // which would otherwise truncate too large an amount into range.
+    let uncast_rhs = rhs;
     let rhs = base::cast_shift_expr_rhs(bcx, op, lhs, rhs);
+    let check_overflow = bcx.sess().overflow_checks() && ty::type_is_integral(lhs_t);
 
     let mut bcx = bcx;
     let val = match op {
+      ast::BiAdd | ast::BiSub | ast::BiMul if check_overflow => {
+        let (new_bcx, val) = trans_overflow_checked_binop(bcx, binop_expr.span,
+                                                          op, lhs_t, lhs, rhs);
+        bcx = new_bcx;
+        val
+      }
       ast::BiAdd => {
         if is_float { FAdd(bcx, lhs, rhs) }
         else { Add(bcx, lhs, rhs) }
@@ -1737,6 +1762,14 @@ fn trans_eager_binop<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
       ast::BiBitOr => Or(bcx, lhs, rhs),
       ast::BiBitAnd => And(bcx, lhs, rhs),
       ast::BiBitXor => Xor(bcx, lhs, rhs),
+      ast::BiShl | ast::BiShr if check_overflow => {
+        bcx = fail_if_shift_overflows(bcx, binop_expr.span, op, lhs, uncast_rhs);
+        match op {
+            ast::BiShl => Shl(bcx, lhs, rhs),
+            _ if is_signed => AShr(bcx, lhs, rhs),
+            _ => LShr(bcx, lhs, rhs)
+        }
+      }
       ast::BiShl => Shl(bcx, lhs, rhs),
       ast::BiShr => {
         if is_signed {
@@ -1760,6 +1793,95 @@ fn trans_eager_binop<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     immediate_rvalue_bcx(bcx, val, binop_ty).to_expr_datumblock()
 }
 
+/// Translates the addition, subtraction or multiplication `op` of the
+/// integers `lhs` and `rhs` of type `t` using one of LLVM's overflow
+/// intrinsics, panicking at `span` if the operation overflows.
+fn trans_overflow_checked_binop<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
+                                            span: codemap::Span,
+                                            op: ast::BinOp,
+                                            t: Ty<'tcx>,
+                                            lhs: ValueRef,
+                                            rhs: ValueRef)
+                                            -> (Block<'blk, 'tcx>, ValueRef) {
+    let (names, msg) = match (op, ty::type_is_signed(t)) {
+        (ast::BiAdd, true) => (["llvm.sadd.with.overflow.i8", "llvm.sadd.with.overflow.i16",
+                                "llvm.sadd.with.overflow.i32", "llvm.sadd.with.overflow.i64"],
+                               "attempted to add with overflow"),
+        (ast::BiAdd, false) => (["llvm.uadd.with.overflow.i8", "llvm.uadd.with.overflow.i16",
+                                 "llvm.uadd.with.overflow.i32", "llvm.uadd.with.overflow.i64"],
+                                "attempted to add with overflow"),
+        (ast::BiSub, true) => (["llvm.ssub.with.overflow.i8", "llvm.ssub.with.overflow.i16",
+                                "llvm.ssub.with.overflow.i32", "llvm.ssub.with.overflow.i64"],
+                               "attempted to subtract with overflow"),
+        (ast::BiSub, false) => (["llvm.usub.with.overflow.i8", "llvm.usub.with.overflow.i16",
+                                 "llvm.usub.with.overflow.i32", "llvm.usub.with.overflow.i64"],
+                                "attempted to subtract with overflow"),
+        (ast::BiMul, true) => (["llvm.smul.with.overflow.i8", "llvm.smul.with.overflow.i16",
+                                "llvm.smul.with.overflow.i32", "llvm.smul.with.overflow.i64"],
+                               "attempted to multiply with overflow"),
+        (ast::BiMul, false) => (["llvm.umul.with.overflow.i8", "llvm.umul.with.overflow.i16",
+                                 "llvm.umul.with.overflow.i32", "llvm.umul.with.overflow.i64"],
+                                "attempted to multiply with overflow"),
+        _ => bcx.sess().span_bug(span, "unexpected overflow-checked binop")
+    };
+    let name = match machine::llbitsize_of_real(bcx.ccx(), val_ty(lhs)) {
+        8 => names[0],
+        16 => names[1],
+        32 => names[2],
+        _ => names[3]
+    };
+
+    let llfn = bcx.ccx().get_intrinsic(&name);
+    let res = Call(bcx, llfn, &[lhs, rhs], None);
+    let val = ExtractValue(bcx, res, 0);
+    let overflowed = ExtractValue(bcx, res, 1);
+    let bcx = with_cond(bcx, overflowed, |bcx| {
+        controlflow::trans_fail(bcx, span, InternedString::new(msg))
+    });
+    (bcx, val)
+}
+
+/// Translates the negation of the signed integer `val`, panicking at `span`
+/// if it is the minimum value of its type, whose negation overflows.
+fn trans_overflow_checked_neg<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
+                                          span: codemap::Span,
+                                          val: ValueRef)
+                                          -> (Block<'blk, 'tcx>, ValueRef) {
+    let llty = val_ty(val);
+    let bits = machine::llbitsize_of_real(bcx.ccx(), llty);
+    let min = C_integral(llty, 1u64 << (bits - 1), false);
+    let overflowed = ICmp(bcx, llvm::IntEQ, val, min);
+    let msg = "attempted to negate with overflow";
+    let bcx = with_cond(bcx, overflowed, |bcx| {
+        controlflow::trans_fail(bcx, span, InternedString::new(msg))
+    });
+    (bcx, Neg(bcx, val))
+}
+
+/// Panics at `span` if the amount `rhs` that `lhs` is shifted by is at
+/// least the width of `lhs`, in which case LLVM's result is undefined. `rhs`
+/// may be of any integer width.
+fn fail_if_shift_overflows<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
+                                       span: codemap::Span,
+                                       op: ast::BinOp,
+                                       lhs: ValueRef,
+                                       rhs: ValueRef)
+                                       -> Block<'blk, 'tcx> {
+    let msg = if op == ast::BiShl {
+        "attempted to shift left with overflow"
+    } else {
+        "attempted to shift right with overflow"
+    };
+    let bits = machine::llbitsize_of_real(bcx.ccx(), val_ty(lhs));
+    // Every integer type is wide enough to hold the width of any other.
+    let bits = C_integral(val_ty(rhs), bits, false);
+    // The amount is unsigned, so negative amounts are caught as well.
+    let overflowed = ICmp(bcx, llvm::IntUGE, rhs, bits);
+    with_cond(bcx, overflowed, |bcx| {
+        controlflow::trans_fail(bcx, span, InternedString::new(msg))
+    })
+}
+
 // refinement types would obviate the need for this
 enum lazy_binop_ty {
     lazy_and,
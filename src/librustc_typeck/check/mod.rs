@@ -4982,7 +4982,7 @@ pub fn check_enum_variants(ccx: &CrateCtxt,
                     // that the expression is in a form that eval_const_expr can
                     // handle, so we may still get an internal compiler error
 
-                    match const_eval::eval_const_expr_partial(ccx.tcx, &**e) {
+                    match const_eval::eval_const_expr_partial(ccx.tcx, &**e, Some(declty)) {
                         Ok(const_eval::const_int(val)) => current_disr_val = val as Disr,
                         Ok(const_eval::const_uint(val)) => current_disr_val = val as Disr,
                         Ok(_) => {
@@ -4990,7 +4990,7 @@ pub fn check_enum_variants(ccx: &CrateCtxt,
                                 "expected signed integer constant");
                         }
                         Err(ref err) => {
-                            span_err!(ccx.tcx.sess, e.span, E0080,
+                            span_err!(ccx.tcx.sess, err.span, E0080,
                                 "expected constant: {}", *err);
                         }
                     }
@@ -119,6 +119,7 @@ fn try_inline_def(cx: &DocContext, tcx: &ty::ctxt,
         inner: inner,
         visibility: Some(ast::Public),
         stability: stability::lookup(tcx, did).clean(cx),
+        deprecation: stability::lookup_deprecation(tcx, did).clean(cx),
         def_id: did,
     });
     Some(ret)
@@ -340,6 +341,7 @@ fn build_impl(cx: &DocContext, tcx: &ty::ctxt,
         attrs: attrs,
         visibility: Some(ast::Inherited),
         stability: stability::lookup(tcx, did).clean(cx),
+        deprecation: stability::lookup_deprecation(tcx, did).clean(cx),
         def_id: did,
     });
 
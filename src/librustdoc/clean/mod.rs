@@ -58,7 +58,7 @@ use visit_ast;
 
 /// A stable identifier to the particular version of JSON output.
 /// Increment this when the `Crate` and related structures change.
-pub static SCHEMA_VERSION: &'static str = "0.8.3";
+pub static SCHEMA_VERSION: &'static str = "0.8.4";
 
 mod inline;
 
@@ -67,6 +67,11 @@ fn get_stability(cx: &DocContext, def_id: ast::DefId) -> Option<Stability> {
     cx.tcx_opt().and_then(|tcx| stability::lookup(tcx, def_id)).clean(cx)
 }
 
+// extract the deprecation of a node from tcx, if possible
+fn get_deprecation(cx: &DocContext, def_id: ast::DefId) -> Option<Deprecation> {
+    cx.tcx_opt().and_then(|tcx| stability::lookup_deprecation(tcx, def_id)).clean(cx)
+}
+
 pub trait Clean<T> {
     fn clean(&self, cx: &DocContext) -> T;
 }
@@ -175,6 +180,7 @@ impl<'a, 'tcx> Clean<Crate> for visit_ast::RustdocVisitor<'a, 'tcx> {
                     attrs: child.attrs.clone(),
                     visibility: Some(ast::Public),
                     stability: None,
+                    deprecation: None,
                     def_id: ast_util::local_def(prim.to_node_id()),
                     inner: PrimitiveItem(prim),
                 });
@@ -236,6 +242,7 @@ pub struct Item {
     pub visibility: Option<Visibility>,
     pub def_id: ast::DefId,
     pub stability: Option<Stability>,
+    pub deprecation: Option<Deprecation>,
 }
 
 impl Item {
@@ -386,6 +393,7 @@ impl Clean<Item> for doctree::Module {
             source: whence.clean(cx),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             def_id: ast_util::local_def(self.id),
             inner: ModuleItem(Module {
                is_crate: self.is_crate,
@@ -816,6 +824,7 @@ impl Clean<Item> for ast::Method {
             def_id: ast_util::local_def(self.id),
             visibility: self.pe_vis().clean(cx),
             stability: get_stability(cx, ast_util::local_def(self.id)),
+            deprecation: get_deprecation(cx, ast_util::local_def(self.id)),
             inner: MethodItem(Method {
                 generics: self.pe_generics().clean(cx),
                 self_: self.pe_explicit_self().node.clean(cx),
@@ -854,6 +863,7 @@ impl Clean<Item> for ast::TypeMethod {
             def_id: ast_util::local_def(self.id),
             visibility: None,
             stability: get_stability(cx, ast_util::local_def(self.id)),
+            deprecation: get_deprecation(cx, ast_util::local_def(self.id)),
             inner: TyMethodItem(TyMethod {
                 unsafety: self.unsafety.clone(),
                 decl: decl,
@@ -900,6 +910,7 @@ impl Clean<Item> for doctree::Function {
             source: self.whence.clean(cx),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             def_id: ast_util::local_def(self.id),
             inner: FunctionItem(Function {
                 decl: self.decl.clean(cx),
@@ -1040,6 +1051,7 @@ impl Clean<Item> for doctree::Trait {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: TraitItem(Trait {
                 unsafety: self.unsafety,
                 items: self.items.clean(cx),
@@ -1160,6 +1172,7 @@ impl<'tcx> Clean<Item> for ty::Method<'tcx> {
             name: Some(self.name.clean(cx)),
             visibility: Some(ast::Inherited),
             stability: get_stability(cx, self.def_id),
+            deprecation: get_deprecation(cx, self.def_id),
             def_id: self.def_id,
             attrs: inline::load_attrs(cx, cx.tcx(), self.def_id),
             source: Span::empty(),
@@ -1510,6 +1523,7 @@ impl Clean<Item> for ast::StructField {
             source: self.span.clean(cx),
             visibility: Some(vis),
             stability: get_stability(cx, ast_util::local_def(self.node.id)),
+            deprecation: get_deprecation(cx, ast_util::local_def(self.node.id)),
             def_id: ast_util::local_def(self.node.id),
             inner: StructFieldItem(TypedStructField(self.node.ty.clean(cx))),
         }
@@ -1537,6 +1551,7 @@ impl Clean<Item> for ty::field_ty {
             source: Span::empty(),
             visibility: Some(self.vis),
             stability: get_stability(cx, self.id),
+            deprecation: get_deprecation(cx, self.id),
             def_id: self.id,
             inner: StructFieldItem(TypedStructField(ty.ty.clean(cx))),
         }
@@ -1568,6 +1583,7 @@ impl Clean<Item> for doctree::Struct {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: StructItem(Struct {
                 struct_type: self.struct_type,
                 generics: self.generics.clean(cx),
@@ -1614,6 +1630,7 @@ impl Clean<Item> for doctree::Enum {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: EnumItem(Enum {
                 variants: self.variants.clean(cx),
                 generics: self.generics.clean(cx),
@@ -1636,6 +1653,7 @@ impl Clean<Item> for doctree::Variant {
             source: self.whence.clean(cx),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             def_id: ast_util::local_def(self.id),
             inner: VariantItem(Variant {
                 kind: self.kind.clean(cx),
@@ -1671,6 +1689,7 @@ impl<'tcx> Clean<Item> for ty::VariantInfo<'tcx> {
                             //        at the needed information here.
                             def_id: self.id,
                             stability: get_stability(cx, self.id),
+                            deprecation: get_deprecation(cx, self.id),
                             inner: StructFieldItem(
                                 TypedStructField(ty.clean(cx))
                             )
@@ -1687,6 +1706,7 @@ impl<'tcx> Clean<Item> for ty::VariantInfo<'tcx> {
             def_id: self.id,
             inner: VariantItem(Variant { kind: kind }),
             stability: get_stability(cx, self.id),
+            deprecation: get_deprecation(cx, self.id),
         }
     }
 }
@@ -1853,6 +1873,7 @@ impl Clean<Item> for doctree::Typedef {
             def_id: ast_util::local_def(self.id.clone()),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: TypedefItem(Typedef {
                 type_: self.ty.clean(cx),
                 generics: self.gen.clean(cx),
@@ -1904,6 +1925,7 @@ impl Clean<Item> for doctree::Static {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: StaticItem(Static {
                 type_: self.type_.clean(cx),
                 mutability: self.mutability.clean(cx),
@@ -1928,6 +1950,7 @@ impl Clean<Item> for doctree::Constant {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: ConstantItem(Constant {
                 type_: self.type_.clean(cx),
                 expr: self.expr.span.to_src(cx),
@@ -1973,6 +1996,7 @@ impl Clean<Item> for doctree::Impl {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: ImplItem(Impl {
                 generics: self.generics.clean(cx),
                 trait_: self.trait_.clean(cx),
@@ -2013,6 +2037,7 @@ impl Clean<Vec<Item>> for ast::ViewItem {
                 def_id: ast_util::local_def(0),
                 visibility: self.vis.clean(cx),
                 stability: None,
+                deprecation: None,
                 inner: ViewItemItem(ViewItem { inner: node.clean(cx) }),
             }
         };
@@ -2161,6 +2186,7 @@ impl Clean<Item> for ast::ForeignItem {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: get_stability(cx, ast_util::local_def(self.id)),
+            deprecation: get_deprecation(cx, ast_util::local_def(self.id)),
             inner: inner,
         }
     }
@@ -2341,6 +2367,7 @@ impl Clean<Item> for doctree::Macro {
             source: self.whence.clean(cx),
             visibility: ast::Public.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             def_id: ast_util::local_def(self.id),
             inner: MacroItem(Macro {
                 source: self.whence.to_src(cx),
@@ -2365,6 +2392,26 @@ impl Clean<Stability> for attr::Stability {
     }
 }
 
+#[derive(Clone, RustcEncodable, RustcDecodable)]
+pub struct Deprecation {
+    pub since: String,
+    pub note: String,
+    pub replacement: String,
+}
+
+impl Clean<Deprecation> for attr::Deprecation {
+    fn clean(&self, _: &DocContext) -> Deprecation {
+        let to_string = |&: s: &Option<InternedString>| {
+            s.as_ref().map_or("".to_string(), |interned| interned.get().to_string())
+        };
+        Deprecation {
+            since: to_string(&self.since),
+            note: to_string(&self.note),
+            replacement: to_string(&self.replacement),
+        }
+    }
+}
+
 impl Clean<Item> for ast::AssociatedType {
     fn clean(&self, cx: &DocContext) -> Item {
         Item {
@@ -2375,6 +2422,7 @@ impl Clean<Item> for ast::AssociatedType {
             visibility: None,
             def_id: ast_util::local_def(self.ty_param.id),
             stability: None,
+            deprecation: None,
         }
     }
 }
@@ -2399,6 +2447,7 @@ impl Clean<Item> for ty::AssociatedType {
             visibility: None,
             def_id: self.def_id,
             stability: None,
+            deprecation: None,
         }
     }
 }
@@ -2420,6 +2469,7 @@ impl Clean<Item> for ast::Typedef {
             visibility: None,
             def_id: ast_util::local_def(self.id),
             stability: None,
+            deprecation: None,
         }
     }
 }
@@ -23,6 +23,7 @@ use syntax::ast_util;
 
 use clean;
 use stability_summary::ModuleSummary;
+use html::escape::Escape;
 use html::item_type::ItemType;
 use html::render;
 use html::render::{cache, CURRENT_LOCATION_KEY};
@@ -47,6 +48,8 @@ pub struct RawMutableSpace(pub clean::Mutability);
 pub struct Stability<'a>(pub &'a Option<clean::Stability>);
 /// Wrapper struct for emitting the stability level concisely.
 pub struct ConciseStability<'a>(pub &'a Option<clean::Stability>);
+/// Wrapper struct for emitting the deprecation banner of an item.
+pub struct Deprecation<'a>(pub &'a Option<clean::Deprecation>);
 /// Wrapper struct for emitting a where clause from Generics.
 pub struct WhereClause<'a>(pub &'a clean::Generics);
 /// Wrapper struct for emitting type parameter bounds.
@@ -792,6 +795,28 @@ impl<'a> fmt::String for ConciseStability<'a> {
     }
 }
 
+impl<'a> fmt::String for Deprecation<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Deprecation(depr) = *self;
+        let depr = match *depr {
+            Some(ref depr) => depr,
+            None => return Ok(())
+        };
+        try!(write!(f, "<div class='deprecated'>Deprecated"));
+        if depr.since.len() > 0 {
+            try!(write!(f, " since {}", Escape(depr.since.as_slice())));
+        }
+        if depr.note.len() > 0 {
+            try!(write!(f, ": {}", Escape(depr.note.as_slice())));
+        }
+        if depr.replacement.len() > 0 {
+            try!(write!(f, "; use <code>{}</code> instead",
+                        Escape(depr.replacement.as_slice())));
+        }
+        write!(f, "</div>")
+    }
+}
+
 impl fmt::String for ModuleSummary {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fn fmt_inner<'a>(f: &mut fmt::Formatter,
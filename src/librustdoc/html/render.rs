@@ -58,7 +58,7 @@ use clean;
 use doctree;
 use fold::DocFolder;
 use html::format::{VisSpace, Method, UnsafetySpace, MutableSpace, Stability};
-use html::format::{ConciseStability, TyParamBounds, WhereClause};
+use html::format::{ConciseStability, TyParamBounds, WhereClause, Deprecation};
 use html::highlight;
 use html::item_type::ItemType;
 use html::layout;
@@ -1477,6 +1477,7 @@ fn shorter<'a>(s: Option<&'a str>) -> &'a str {
 }
 
 fn document(w: &mut fmt::Formatter, item: &clean::Item) -> fmt::Result {
+    try!(write!(w, "{}", Deprecation(&item.deprecation)));
     match item.doc_value() {
         Some(s) => {
             try!(write!(w, "<div class='docblock'>{}</div>", Markdown(s)));
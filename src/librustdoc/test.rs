@@ -290,6 +290,7 @@ impl Collector {
                 name: testing::DynTestName(name),
                 ignore: should_ignore,
                 should_fail: testing::ShouldFail::No, // compiler failures are test failures
+                timeout: None,
             },
             testfn: testing::DynTestFn(Thunk::new(move|| {
                 runtest(test.as_slice(),
@@ -371,6 +371,9 @@ pub fn find_stability_generic<'a,
                              -> Option<(Stability, &'a AM)> {
     for attr in attrs {
         let level = match attr.name().get() {
+            // `#[deprecated(...)]` is not a stability level; see
+            // `find_deprecation`.
+            "deprecated" if attr.meta_item_list().is_some() => continue,
             "deprecated" => Deprecated,
             "experimental" => Experimental,
             "unstable" => Unstable,
@@ -396,6 +399,67 @@ pub fn find_stability(attrs: &[Attribute]) -> Option<Stability> {
     })
 }
 
+/// Represents the `#[deprecated(since = "...", note = "...")]` attribute,
+/// which, unlike the stability levels, may be used by any crate. The
+/// `#[deprecated]` and `#[deprecated = "note"]` forms are understood as
+/// well.
+#[derive(RustcEncodable,RustcDecodable,Clone,Show)]
+pub struct Deprecation {
+    /// The version the item was deprecated in.
+    pub since: Option<InternedString>,
+    /// Why the item was deprecated.
+    pub note: Option<InternedString>,
+    /// The path of an item to use instead.
+    pub replacement: Option<InternedString>,
+}
+
+/// Find the first `deprecated` attribute, reporting unknown or malformed
+/// keys in it. `None` if none exists.
+pub fn find_deprecation(diagnostic: &SpanHandler, attrs: &[Attribute]) -> Option<Deprecation> {
+    for attr in attrs.iter() {
+        if !attr.check_name("deprecated") {
+            continue;
+        }
+
+        let mut depr = Deprecation { since: None, note: None, replacement: None };
+        let items = match attr.meta_item_list() {
+            Some(items) => items,
+            None => {
+                depr.note = attr.value_str();
+                return Some(depr);
+            }
+        };
+        for item in items.iter() {
+            let name = item.name();
+            let slot = match name.get() {
+                "since" => &mut depr.since,
+                "note" => &mut depr.note,
+                "replacement" => &mut depr.replacement,
+                _ => {
+                    diagnostic.span_err(item.span,
+                                        &format!("unknown key `{}` in `deprecated` attribute; \
+                                                  expected `since`, `note` or `replacement`",
+                                                 name)[]);
+                    continue
+                }
+            };
+            match item.value_str() {
+                Some(_) if slot.is_some() => {
+                    diagnostic.span_err(item.span,
+                                        &format!("`{}` given more than once", name)[]);
+                }
+                Some(value) => *slot = Some(value),
+                None => {
+                    diagnostic.span_err(item.span,
+                                        &format!("expected `{} = \"...\"`", name)[]);
+                }
+            }
+        }
+        return Some(depr);
+    }
+    None
+}
+
 pub fn require_unique_names(diagnostic: &SpanHandler, metas: &[P<MetaItem>]) {
     let mut set = HashSet::new();
     for meta in metas.iter() {
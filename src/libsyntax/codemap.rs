@@ -276,6 +276,9 @@ pub struct FileMap {
     /// originate from files has names between angle brackets by convention,
     /// e.g. `<anon>`
     pub name: FileName,
+    /// The name of the file before `--remap-path-prefix` was applied to it,
+    /// if that changed it
+    pub unmapped_name: Option<FileName>,
     /// The complete source code
     pub src: String,
     /// The start position of this source in the CodeMap
@@ -328,25 +331,74 @@ impl FileMap {
         self.multibyte_chars.borrow_mut().push(mbc);
     }
 
+    /// The name the file can be found under, regardless of any remapping.
+    pub fn original_name(&self) -> &str {
+        match self.unmapped_name {
+            Some(ref name) => &name[],
+            None => &self.name[],
+        }
+    }
+
     pub fn is_real_file(&self) -> bool {
         !(self.name.starts_with("<") &&
           self.name.ends_with(">"))
     }
 }
 
+/// Replacements of path prefixes, as given by `--remap-path-prefix FROM=TO`.
+/// They are applied to the names of all files added to a `CodeMap`, so that
+/// the paths which end up in the compiler's output don't depend on where the
+/// sources happen to be on the building machine.
+#[derive(Clone, Show)]
+pub struct FilePathMapping {
+    mapping: Vec<(String, String)>,
+}
+
+impl FilePathMapping {
+    pub fn empty() -> FilePathMapping {
+        FilePathMapping { mapping: vec![] }
+    }
+
+    pub fn new(mapping: Vec<(String, String)>) -> FilePathMapping {
+        FilePathMapping { mapping: mapping }
+    }
+
+    /// Applies the mapping to `path`, returning the new path and whether it
+    /// was changed. The mapping given last on the command line wins if more
+    /// than one applies.
+    pub fn map_prefix(&self, path: &str) -> (String, bool) {
+        for &(ref from, ref to) in self.mapping.iter().rev() {
+            if path.starts_with(&from[]) {
+                return (format!("{}{}", to, &path[from.len()..]), true);
+            }
+        }
+        (path.to_string(), false)
+    }
+}
+
 pub struct CodeMap {
     pub files: RefCell<Vec<Rc<FileMap>>>,
-    expansions: RefCell<Vec<ExpnInfo>>
+    expansions: RefCell<Vec<ExpnInfo>>,
+    path_mapping: FilePathMapping,
 }
 
 impl CodeMap {
     pub fn new() -> CodeMap {
+        CodeMap::with_file_path_mapping(FilePathMapping::empty())
+    }
+
+    pub fn with_file_path_mapping(path_mapping: FilePathMapping) -> CodeMap {
         CodeMap {
             files: RefCell::new(Vec::new()),
             expansions: RefCell::new(Vec::new()),
+            path_mapping: path_mapping,
         }
     }
 
+    pub fn path_mapping(&self) -> &FilePathMapping {
+        &self.path_mapping
+    }
+
     pub fn new_filemap(&self, filename: FileName, src: String) -> Rc<FileMap> {
         let mut files = self.files.borrow_mut();
         let start_pos = match files.last() {
@@ -371,8 +423,10 @@ impl CodeMap {
             src.push('\n');
         }
 
+        let (name, was_remapped) = self.path_mapping.map_prefix(&filename[]);
         let filemap = Rc::new(FileMap {
-            name: filename,
+            name: name,
+            unmapped_name: if was_remapped { Some(filename) } else { None },
             src: src.to_string(),
             start_pos: Pos::from_uint(start_pos),
             lines: RefCell::new(Vec::new()),
@@ -426,6 +480,12 @@ impl CodeMap {
         self.lookup_char_pos(sp.lo).file.name.to_string()
     }
 
+    /// The name `span_to_filename` would return before any remapping, for
+    /// finding other files relative to the one `sp` is in.
+    pub fn span_to_original_filename(&self, sp: Span) -> FileName {
+        self.lookup_char_pos(sp.lo).file.original_name().to_string()
+    }
+
     pub fn span_to_lines(&self, sp: Span) -> FileLines {
         let lo = self.lookup_char_pos(sp.lo);
         let hi = self.lookup_char_pos(sp.hi);
@@ -784,4 +844,23 @@ mod test {
 
         assert_eq!(sstr, "blork.rs:2:1: 2:12");
     }
+
+    #[test]
+    fn t10() {
+        // Test that file names are remapped as they are added
+        let mapping = FilePathMapping::new(vec![
+            ("/home/user".to_string(), "/src".to_string()),
+            ("/home/user/proj".to_string(), "/proj".to_string()),
+        ]);
+        let cm = CodeMap::with_file_path_mapping(mapping);
+        let fm1 = cm.new_filemap("/home/user/proj/lib.rs".to_string(), "".to_string());
+        let fm2 = cm.new_filemap("/home/user/foo.rs".to_string(), "".to_string());
+        let fm3 = cm.new_filemap("<anon>".to_string(), "".to_string());
+
+        assert_eq!(fm1.name, "/proj/lib.rs");
+        assert_eq!(fm2.name, "/src/foo.rs");
+        assert_eq!(fm3.name, "<anon>");
+        assert_eq!(fm1.original_name(), "/home/user/proj/lib.rs");
+        assert_eq!(fm3.original_name(), "<anon>");
+    }
 }
@@ -11,6 +11,7 @@
 pub use self::Level::*;
 pub use self::RenderSpan::*;
 pub use self::ColorConfig::*;
+pub use self::Applicability::*;
 use self::Destination::*;
 
 use codemap::{COMMAND_LINE_SP, COMMAND_LINE_EXPN, Pos, Span};
@@ -18,6 +19,7 @@ use codemap;
 use diagnostics;
 
 use std::cell::{RefCell, Cell};
+use std::cmp;
 use std::fmt;
 use std::io;
 use std::iter::range;
@@ -28,7 +30,7 @@ use term;
 /// maximum number of lines we will print for each error; arbitrary.
 static MAX_LINES: uint = 6u;
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum RenderSpan {
     /// A FullSpan renders with both with an initial line for the
     /// message, prefixed by file:linenum, followed by a summary of
@@ -38,18 +40,36 @@ pub enum RenderSpan {
     /// A FileLine renders with just a line for the message prefixed
     /// by file:linenum.
     FileLine(Span),
+
+    /// A suggestion renders with both with an initial line for the
+    /// message, prefixed by file:linenum, followed by the source code
+    /// covered by the span with the given string substituted for the
+    /// span. Machine-applicable substitutions are also what gets applied
+    /// to the source files by `SuggestionApplier`.
+    Suggestion(Span, String, Applicability),
+}
+
+/// How confident we are that a suggestion is what the user wants.
+#[derive(Clone, Copy, PartialEq, Show)]
+pub enum Applicability {
+    /// The suggestion is definitely correct, and may be applied to the
+    /// source without a human looking at it first.
+    MachineApplicable,
+    /// The suggestion is a guess, like the correction of a typo, and only
+    /// shown to the user.
+    MaybeIncorrect,
 }
 
 impl RenderSpan {
-    fn span(self) -> Span {
-        match self {
-            FullSpan(s) | FileLine(s) => s
+    pub fn span(&self) -> Span {
+        match *self {
+            FullSpan(s) | FileLine(s) | Suggestion(s, _, _) => s
         }
     }
     fn is_full_span(&self) -> bool {
         match self {
             &FullSpan(..) => true,
-            &FileLine(..) => false,
+            &FileLine(..) | &Suggestion(..) => false,
         }
     }
 }
@@ -118,6 +138,11 @@ impl SpanHandler {
     pub fn fileline_note(&self, sp: Span, msg: &str) {
         self.handler.custom_emit(&self.cm, FileLine(sp), msg, Note);
     }
+    /// Suggest replacing the code covered by `sp` with `suggestion`.
+    pub fn span_suggestion(&self, sp: Span, msg: &str, suggestion: String,
+                           applicability: Applicability) {
+        self.handler.custom_emit(&self.cm, Suggestion(sp, suggestion, applicability), msg, Help);
+    }
     pub fn span_bug(&self, sp: Span, msg: &str) -> ! {
         self.handler.emit(Some((&self.cm, sp)), msg, Bug);
         panic!(ExplicitBug);
@@ -401,7 +426,10 @@ fn emit(dst: &mut EmitterWriter, cm: &codemap::CodeMap, rsp: RenderSpan,
     } else {
         cm.span_to_string(sp)
     };
-    if custom {
+    if let Suggestion(_, ref suggestion, _) = rsp {
+        try!(print_diagnostic(dst, &ss[], lvl, msg, code));
+        try!(show_suggestion(dst, cm, sp, &suggestion[], cm.span_to_lines(sp)));
+    } else if custom {
         // we want to tell compiletest/runtest to look at the last line of the
         // span (since `custom_highlight_lines` displays an arrow to the end of
         // the span)
@@ -511,6 +539,51 @@ fn highlight_lines(err: &mut EmitterWriter,
     Ok(())
 }
 
+/// Prints the lines covered by `sp` as they would read with `suggestion`
+/// substituted for the span. Only the first `MAX_LINES` lines of the result
+/// are shown.
+fn show_suggestion(err: &mut EmitterWriter,
+                   cm: &codemap::CodeMap,
+                   sp: Span,
+                   suggestion: &str,
+                   lines: codemap::FileLines) -> io::IoResult<()> {
+    let fm = &*lines.file;
+    let (first, last) = match (lines.lines.first(), lines.lines.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => return Ok(()),
+    };
+    let (first_line, last_line) = match (fm.get_line(first), fm.get_line(last)) {
+        (Some(first_line), Some(last_line)) => (first_line, last_line),
+        _ => return Ok(()),
+    };
+
+    // `get_line` returns the line without its terminator, so the offsets
+    // of the span within the first and last lines can be used directly.
+    let lo = (sp.lo - (*fm.lines.borrow())[first]).to_uint();
+    let hi = (sp.hi - (*fm.lines.borrow())[last]).to_uint();
+    let lo = cmp::min(lo, first_line.len());
+    let hi = cmp::min(hi, last_line.len());
+
+    let mut complete = String::new();
+    complete.push_str(&first_line[..lo]);
+    complete.push_str(suggestion);
+    complete.push_str(&last_line[hi..]);
+
+    let mut elided = false;
+    for (i, line) in complete.lines().enumerate() {
+        if i == MAX_LINES {
+            elided = true;
+            break;
+        }
+        try!(write!(&mut err.dst, "{}:{} {}\n", fm.name, first + i + 1, line));
+    }
+    if elided {
+        let s = format!("{}:{} ", fm.name, first + MAX_LINES);
+        try!(write!(&mut err.dst, "{0:1$}...\n", "", s.len()));
+    }
+    Ok(())
+}
+
 /// Here are the differences between this and the normal `highlight_lines`:
 /// `custom_highlight_lines` will always put arrow on the last byte of the
 /// span (instead of the first byte). Also, when the span is too long (more
@@ -73,6 +73,9 @@ static KNOWN_FEATURES: &'static [(&'static str, Status)] = &[
     ("box_syntax", Active),
     ("on_unimplemented", Active),
 
+    // Functions whose calls can be evaluated in constant expressions
+    ("const_fn", Active),
+
     ("if_let", Accepted),
     ("while_let", Accepted),
 
@@ -276,6 +279,10 @@ impl<'a, 'v> Visitor<'v> for PostExpansionVisitor<'a> {
                     self.gate_feature("plugin_registrar", i.span,
                                       "compiler plugins are experimental and possibly buggy");
                 }
+                if attr::contains_name(&i.attrs[], "const_fn") {
+                    self.gate_feature("const_fn", i.span,
+                                      "constant functions are experimental");
+                }
             }
 
             ast::ItemStruct(..) => {
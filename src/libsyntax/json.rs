@@ -0,0 +1,300 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A JSON emitter for errors.
+//!
+//! This works by converting the diagnostics reported through a `Handler`
+//! into the structs defined below and then serializing each top-level
+//! diagnostic as a single JSON object on its own line. Notes and helps which
+//! directly follow an error or warning are attached to it as children rather
+//! than being emitted on their own.
+//!
+//! The format is not yet stable and may change between releases.
+
+use codemap::{self, Span, COMMAND_LINE_EXPN, Pos};
+use diagnostic::{Emitter, Level, RenderSpan, FullSpan, FileLine, Suggestion};
+use diagnostic::{Bug, Fatal, Error, Warning, Note, Help};
+use diagnostics::registry::Registry;
+
+use std::io;
+use serialize::json;
+
+pub struct JsonEmitter {
+    dst: Box<Writer + Send>,
+    registry: Option<Registry>,
+    /// The most recent top-level diagnostic, kept around until we know that
+    /// no more notes or helps are going to be attached to it.
+    pending: Option<Diagnostic>,
+}
+
+impl JsonEmitter {
+    pub fn stderr(registry: Option<Registry>) -> JsonEmitter {
+        JsonEmitter::new(box io::stderr(), registry)
+    }
+
+    pub fn new(dst: Box<Writer + Send>, registry: Option<Registry>) -> JsonEmitter {
+        JsonEmitter {
+            dst: dst,
+            registry: registry,
+            pending: None,
+        }
+    }
+
+    fn add(&mut self, diag: Diagnostic, lvl: Level) {
+        match lvl {
+            Note | Help if self.pending.is_some() => {
+                self.pending.as_mut().unwrap().children.push(diag);
+                return
+            }
+            _ => {}
+        }
+        self.flush_pending();
+        match lvl {
+            // Fatal errors and bugs are immediately followed by a panic, so
+            // there is no chance for anything to be attached to them.
+            Bug | Fatal => self.write(&diag),
+            Error | Warning => self.pending = Some(diag),
+            Note | Help => self.write(&diag),
+        }
+    }
+
+    fn flush_pending(&mut self) {
+        if let Some(diag) = self.pending.take() {
+            self.write(&diag);
+        }
+    }
+
+    fn write(&mut self, diag: &Diagnostic) {
+        if let Err(e) = writeln!(&mut self.dst, "{}", json::encode(diag)) {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+}
+
+impl Emitter for JsonEmitter {
+    fn emit(&mut self, cmsp: Option<(&codemap::CodeMap, Span)>,
+            msg: &str, code: Option<&str>, lvl: Level) {
+        let data = Diagnostic::new(cmsp.map(|(cm, sp)| (cm, FullSpan(sp))),
+                                   msg, code, lvl, self.registry.as_ref());
+        self.add(data, lvl);
+    }
+
+    fn custom_emit(&mut self, cm: &codemap::CodeMap,
+                   sp: RenderSpan, msg: &str, lvl: Level) {
+        let data = Diagnostic::new(Some((cm, sp)), msg, None, lvl,
+                                   self.registry.as_ref());
+        self.add(data, lvl);
+    }
+}
+
+#[unsafe_destructor]
+impl Drop for JsonEmitter {
+    fn drop(&mut self) {
+        self.flush_pending();
+    }
+}
+
+// The following data types are provided just for serialisation.
+
+#[derive(RustcEncodable)]
+struct Diagnostic {
+    /// The primary error message.
+    message: String,
+    code: Option<DiagnosticCode>,
+    /// "error: internal compiler error", "error", "warning", "note", "help".
+    level: String,
+    span: Option<DiagnosticSpan>,
+    /// Associated diagnostic messages.
+    children: Vec<Diagnostic>,
+}
+
+#[derive(RustcEncodable)]
+struct DiagnosticSpan {
+    file_name: String,
+    byte_start: u32,
+    byte_end: u32,
+    /// 1-based.
+    line_start: uint,
+    line_end: uint,
+    /// 1-based, character offset.
+    column_start: uint,
+    column_end: uint,
+    /// How the span is rendered by the text emitter: "full_span" (the
+    /// message plus the covered source lines), "file_line" (the message
+    /// only) or "suggestion" (the covered source lines with the suggested
+    /// replacement applied).
+    kind: String,
+    /// The code that should replace the span, for suggestions.
+    suggested_replacement: Option<String>,
+    /// If the span is the result of a macro expansion, the place where the
+    /// macro was invoked.
+    expansion: Option<Box<DiagnosticSpanMacroExpansion>>,
+}
+
+#[derive(RustcEncodable)]
+struct DiagnosticSpanMacroExpansion {
+    /// Name of the macro, e.g. `foo!` or `#[derive(Foo)]`.
+    macro_decl_name: String,
+    /// Span where the macro was invoked.
+    span: DiagnosticSpan,
+    /// Span of the macro definition, if it is known.
+    def_site_span: Option<DiagnosticSpan>,
+}
+
+#[derive(RustcEncodable)]
+struct DiagnosticCode {
+    /// The code itself.
+    code: String,
+    /// An explanation for the code, if one is registered.
+    explanation: Option<&'static str>,
+}
+
+impl Diagnostic {
+    fn new(rsp: Option<(&codemap::CodeMap, RenderSpan)>,
+           msg: &str,
+           code: Option<&str>,
+           level: Level,
+           registry: Option<&Registry>)
+           -> Diagnostic {
+        Diagnostic {
+            message: msg.to_string(),
+            code: code.map(|c| DiagnosticCode::new(c, registry)),
+            level: level.to_string(),
+            span: rsp.and_then(|(cm, rsp)| DiagnosticSpan::from_render_span(cm, rsp)),
+            children: vec![],
+        }
+    }
+}
+
+impl DiagnosticSpan {
+    fn from_render_span(cm: &codemap::CodeMap, rsp: RenderSpan) -> Option<DiagnosticSpan> {
+        let (kind, replacement) = match rsp {
+            FullSpan(..) => ("full_span", None),
+            FileLine(..) => ("file_line", None),
+            Suggestion(_, ref s, _) => ("suggestion", Some(s.clone())),
+        };
+        DiagnosticSpan::from_span(cm, rsp.span(), kind).map(|mut span| {
+            span.suggested_replacement = replacement;
+            span
+        })
+    }
+
+    fn from_span(cm: &codemap::CodeMap, sp: Span, kind: &str) -> Option<DiagnosticSpan> {
+        // Spans originating from the command line have no source to point
+        // into, so they are reported without location information.
+        if sp.expn_id == COMMAND_LINE_EXPN {
+            return None;
+        }
+
+        let start = cm.lookup_char_pos(sp.lo);
+        let end = cm.lookup_char_pos(sp.hi);
+        let expansion = cm.with_expn_info(sp.expn_id, |ei| ei.and_then(|ei| {
+            let (pre, post) = match ei.callee.format {
+                codemap::MacroAttribute => ("#[", "]"),
+                codemap::MacroBang => ("", "!"),
+            };
+            DiagnosticSpan::from_span(cm, ei.call_site, "full_span").map(|call_site| {
+                box DiagnosticSpanMacroExpansion {
+                    macro_decl_name: format!("{}{}{}", pre, ei.callee.name, post),
+                    span: call_site,
+                    def_site_span: ei.callee.span.and_then(|sp| {
+                        DiagnosticSpan::from_span(cm, sp, "full_span")
+                    }),
+                }
+            })
+        }));
+
+        Some(DiagnosticSpan {
+            file_name: start.file.name.clone(),
+            byte_start: (sp.lo - start.file.start_pos).to_uint() as u32,
+            byte_end: (sp.hi - start.file.start_pos).to_uint() as u32,
+            line_start: start.line,
+            line_end: end.line,
+            column_start: start.col.to_uint() + 1,
+            column_end: end.col.to_uint() + 1,
+            kind: kind.to_string(),
+            suggested_replacement: None,
+            expansion: expansion,
+        })
+    }
+}
+
+impl DiagnosticCode {
+    fn new(code: &str, registry: Option<&Registry>) -> DiagnosticCode {
+        DiagnosticCode {
+            code: code.to_string(),
+            explanation: registry.and_then(|r| r.find_description(code)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::JsonEmitter;
+
+    use codemap::{CodeMap, BytePos, mk_sp};
+    use diagnostic::{Emitter, Error, Note, Warning};
+    use serialize::json;
+
+    use std::io::{MemWriter, Writer, IoResult};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone)]
+    struct SharedWriter(Arc<Mutex<MemWriter>>);
+
+    impl Writer for SharedWriter {
+        fn write(&mut self, bytes: &[u8]) -> IoResult<()> {
+            self.0.lock().unwrap().write(bytes)
+        }
+    }
+
+    fn emitted_lines<F>(f: F) -> Vec<json::Json> where
+        F: FnOnce(&mut JsonEmitter, &CodeMap),
+    {
+        let buf = SharedWriter(Arc::new(Mutex::new(MemWriter::new())));
+        let cm = CodeMap::new();
+        cm.new_filemap("foo.rs".to_string(), "fn main() {\n    bar();\n}\n".to_string());
+        {
+            let mut emitter = JsonEmitter::new(box buf.clone(), None);
+            f(&mut emitter, &cm);
+        }
+        let output = buf.0.lock().unwrap().get_ref().to_vec();
+        String::from_utf8(output).unwrap().lines().map(|line| {
+            json::from_str(line).unwrap()
+        }).collect()
+    }
+
+    #[test]
+    fn test_notes_are_attached_as_children() {
+        let lines = emitted_lines(|e, cm| {
+            let sp = mk_sp(BytePos(16), BytePos(19));
+            e.emit(Some((cm, sp)), "unresolved name `bar`", Some("E0425"), Error);
+            e.emit(Some((cm, sp)), "did you mean `baz`?", None, Note);
+            e.emit(None, "unused variable", None, Warning);
+        });
+        assert_eq!(lines.len(), 2);
+
+        let err = &lines[0];
+        assert_eq!(err["level"].as_string(), Some("error"));
+        assert_eq!(err["code"]["code"].as_string(), Some("E0425"));
+        let span = &err["span"];
+        assert_eq!(span["file_name"].as_string(), Some("foo.rs"));
+        assert_eq!(span["line_start"].as_u64(), Some(2));
+        assert_eq!(span["column_start"].as_u64(), Some(5));
+        assert_eq!(span["column_end"].as_u64(), Some(8));
+        let children = err["children"].as_array().unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0]["level"].as_string(), Some("note"));
+
+        let warn = &lines[1];
+        assert_eq!(warn["level"].as_string(), Some("warning"));
+        assert!(warn["span"].is_null());
+    }
+}
@@ -67,11 +67,13 @@ pub mod config;
 pub mod diagnostic;
 pub mod feature_gate;
 pub mod fold;
+pub mod json;
 pub mod owned_slice;
 pub mod parse;
 pub mod ptr;
 pub mod show_span;
 pub mod std_inject;
+pub mod suggestions;
 pub mod test;
 pub mod visit;
 
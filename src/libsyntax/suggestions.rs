@@ -0,0 +1,220 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Applying the suggestions attached to diagnostics to the source files.
+//!
+//! `SuggestionApplier` wraps another emitter, forwarding every diagnostic to
+//! it, and remembers the replacement carried by each machine-applicable
+//! `Suggestion` render span. When the applier is dropped (at the end of the
+//! compilation) the recorded replacements are written back into the files
+//! they came from, unless any errors were reported: the suggestions are only
+//! known to be correct for code that compiles.
+
+use codemap::{self, Span, Pos, NO_EXPANSION};
+use diagnostic::{Emitter, Level, RenderSpan, Suggestion, MachineApplicable};
+use diagnostic::{Bug, Fatal, Error, Warning, Note, Help};
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::io::File;
+use std::mem;
+
+/// A replacement of the bytes `lo..hi` of a source file.
+struct Replacement {
+    lo: uint,
+    hi: uint,
+    text: String,
+}
+
+pub struct SuggestionApplier {
+    inner: Box<Emitter + Send>,
+    /// The replacements to make, keyed by the name of the file they apply
+    /// to, along with the source of the file as the codemap saw it.
+    files: HashMap<String, (String, Vec<Replacement>)>,
+    /// Whether any errors have been reported.
+    failed: bool,
+}
+
+impl SuggestionApplier {
+    pub fn new(inner: Box<Emitter + Send>) -> SuggestionApplier {
+        SuggestionApplier {
+            inner: inner,
+            files: HashMap::new(),
+            failed: false,
+        }
+    }
+
+    fn record(&mut self, cm: &codemap::CodeMap, sp: Span, text: &str) {
+        // Code produced by macro expansion has no single place in the source
+        // that could be rewritten.
+        if sp.expn_id != NO_EXPANSION {
+            return;
+        }
+        let lo = cm.lookup_byte_offset(sp.lo);
+        let hi = cm.lookup_byte_offset(sp.hi);
+        if lo.fm.name != hi.fm.name || !lo.fm.is_real_file() {
+            return;
+        }
+
+        let replacement = Replacement {
+            lo: lo.pos.to_uint(),
+            hi: hi.pos.to_uint(),
+            text: text.to_string(),
+        };
+        match self.files.entry(lo.fm.original_name().to_string()) {
+            Occupied(mut entry) => entry.get_mut().1.push(replacement),
+            Vacant(entry) => { entry.insert((lo.fm.src.clone(), vec![replacement])); }
+        }
+    }
+
+    fn note_level(&mut self, lvl: Level) {
+        match lvl {
+            Bug | Fatal | Error => self.failed = true,
+            Warning | Note | Help => {}
+        }
+    }
+
+    /// Writes all recorded replacements to disk, returning the number of
+    /// replacements made.
+    fn apply(&mut self) -> uint {
+        let mut applied = 0u;
+        let files = mem::replace(&mut self.files, HashMap::new());
+        let mut files = files.into_iter().collect::<Vec<_>>();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, (src, replacements)) in files.into_iter() {
+            match apply_to_file(&name[], &src[], replacements) {
+                Ok(n) => applied += n,
+                Err(msg) => self.inner.emit(None, &msg[], None, Warning),
+            }
+        }
+        applied
+    }
+}
+
+impl Emitter for SuggestionApplier {
+    fn emit(&mut self, cmsp: Option<(&codemap::CodeMap, Span)>,
+            msg: &str, code: Option<&str>, lvl: Level) {
+        self.note_level(lvl);
+        self.inner.emit(cmsp, msg, code, lvl);
+    }
+
+    fn custom_emit(&mut self, cm: &codemap::CodeMap,
+                   sp: RenderSpan, msg: &str, lvl: Level) {
+        self.note_level(lvl);
+        if let Suggestion(span, ref text, MachineApplicable) = sp {
+            self.record(cm, span, &text[]);
+        }
+        self.inner.custom_emit(cm, sp, msg, lvl);
+    }
+}
+
+#[unsafe_destructor]
+impl Drop for SuggestionApplier {
+    fn drop(&mut self) {
+        if self.failed {
+            if !self.files.is_empty() {
+                self.inner.emit(None, "not applying suggestions because of the previous errors",
+                                None, Note);
+            }
+            return;
+        }
+        let applied = self.apply();
+        if applied > 0 {
+            let msg = format!("applied {} suggestion{}", applied,
+                              if applied == 1 { "" } else { "s" });
+            self.inner.emit(None, &msg[], None, Note);
+        }
+    }
+}
+
+/// Rewrites the file `name`, whose contents were `src` when the suggestions
+/// were made. Overlapping replacements are dropped in favour of the one
+/// starting first.
+fn apply_to_file(name: &str, src: &str, mut replacements: Vec<Replacement>)
+                 -> Result<uint, String> {
+    let path = Path::new(name);
+    let on_disk = try!(File::open(&path).read_to_string().map_err(|e| {
+        format!("couldn't read {} to apply suggestions: {}", name, e)
+    }));
+
+    // The codemap strips the byte order mark and adds a trailing newline if
+    // there is none; the file on disk must otherwise be unchanged.
+    let (bom, contents) = if on_disk.starts_with("\u{feff}") {
+        ("\u{feff}", &on_disk[3..])
+    } else {
+        ("", &on_disk[])
+    };
+    if !src.starts_with(contents) || src.len() - contents.len() > 1 {
+        return Err(format!("{} changed during compilation, not applying \
+                            suggestions to it", name));
+    }
+
+    replacements.sort_by(|a, b| a.lo.cmp(&b.lo));
+    let mut out = String::from_str(bom);
+    let mut last = 0u;
+    let mut applied = 0u;
+    for r in replacements.iter() {
+        if r.lo < last || r.hi > contents.len() {
+            continue;
+        }
+        out.push_str(&contents[last..r.lo]);
+        out.push_str(&r.text[]);
+        last = r.hi;
+        applied += 1;
+    }
+    out.push_str(&contents[last..]);
+
+    try!(File::create(&path).write_str(&out[]).map_err(|e| {
+        format!("couldn't write suggestions to {}: {}", name, e)
+    }));
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{apply_to_file, Replacement};
+
+    use std::io::{File, TempDir};
+
+    fn replacement(lo: uint, hi: uint, text: &str) -> Replacement {
+        Replacement { lo: lo, hi: hi, text: text.to_string() }
+    }
+
+    #[test]
+    fn test_apply_to_file() {
+        let dir = TempDir::new("suggestions").unwrap();
+        let path = dir.path().join("foo.rs");
+        let src = "fn main() {\n    let x = (1);\n    x = 2;\n}\n";
+        File::create(&path).write_str(src).unwrap();
+
+        let name = path.as_str().unwrap();
+        let applied = apply_to_file(name, src, vec![
+            // Listed out of order, and with an overlapping replacement.
+            replacement(24, 27, "1"),
+            replacement(20, 21, "mut x"),
+            replacement(20, 25, "y = (1"),
+        ]).unwrap();
+        assert_eq!(applied, 2);
+
+        let result = File::open(&path).read_to_string().unwrap();
+        assert_eq!(result, "fn main() {\n    let mut x = 1;\n    x = 2;\n}\n");
+    }
+
+    #[test]
+    fn test_changed_file_is_left_alone() {
+        let dir = TempDir::new("suggestions").unwrap();
+        let path = dir.path().join("foo.rs");
+        File::create(&path).write_str("fn main() {}\n").unwrap();
+
+        let name = path.as_str().unwrap();
+        assert!(apply_to_file(name, "fn foo() {}\n", vec![replacement(3, 6, "bar")]).is_err());
+        assert_eq!(File::open(&path).read_to_string().unwrap(), "fn main() {}\n");
+    }
+}
@@ -47,7 +47,8 @@ struct Test {
     path: Vec<ast::Ident> ,
     bench: bool,
     ignore: bool,
-    should_fail: ShouldFail
+    should_fail: ShouldFail,
+    timeout: Option<u64>,
 }
 
 struct TestCtxt<'a> {
@@ -136,7 +137,8 @@ impl<'a> fold::Folder for TestHarnessGenerator<'a> {
                         path: self.cx.path.clone(),
                         bench: is_bench_fn(&self.cx, &*i),
                         ignore: is_ignored(&*i),
-                        should_fail: should_fail(&*i)
+                        should_fail: should_fail(&*i),
+                        timeout: timeout(&self.cx, &*i),
                     };
                     self.cx.testfns.push(test);
                     self.tests.push(i.ident);
@@ -377,6 +379,20 @@ fn should_fail(i: &ast::Item) -> ShouldFail {
     }
 }
 
+fn timeout(cx: &TestCtxt, i: &ast::Item) -> Option<u64> {
+    i.attrs.iter().find(|attr| attr.check_name("timeout")).and_then(|attr| {
+        match attr.value_str().and_then(|s| s.get().parse::<u64>()) {
+            Some(secs) if secs > 0 => Some(secs),
+            _ => {
+                cx.span_diagnostic.span_err(attr.span, "the timeout of a test must be a \
+                                                        positive number of seconds: \
+                                                        `#[timeout = \"N\"]`");
+                None
+            }
+        }
+    })
+}
+
 /*
 
 We're going to be building a module that looks more or less like:
@@ -392,6 +408,12 @@ mod __test {
   ];
 }
 
+When built with `-Z coverage`, the coverage data is written by the profiling
+runtime once this `main` returns. That includes runs with failing tests: the
+failure is reported by panicking on the main thread, which the runtime turns
+into an exit status rather than aborting. A test binary that is killed by a
+signal writes no data.
+
 */
 
 fn mk_std(cx: &TestCtxt) -> ast::ViewItem {
@@ -584,13 +606,22 @@ fn mk_test_desc_and_fn_rec(cx: &TestCtxt, test: &Test) -> P<ast::Expr> {
         }
     };
 
+    let timeout_expr = match test.timeout {
+        Some(secs) => {
+            let lit = ast::LitInt(secs, ast::UnsignedIntLit(ast::TyU64));
+            ecx.expr_some(span, ecx.expr_lit(span, lit))
+        }
+        None => ecx.expr_none(span),
+    };
+
     // self::test::TestDesc { ... }
     let desc_expr = ecx.expr_struct(
         span,
         test_path("TestDesc"),
         vec![field("name", name_expr),
              field("ignore", ignore_expr),
-             field("should_fail", fail_expr)]);
+             field("should_fail", fail_expr),
+             field("timeout", timeout_expr)]);
 
 
     let mut visible_path = match cx.toplevel_reexport {
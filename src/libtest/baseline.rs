@@ -0,0 +1,241 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Named benchmark baselines.
+//!
+//! A baseline keeps every ns/iter sample taken of each benchmark in a run,
+//! rather than just a single value like the metrics ratchet does. Comparing
+//! a run against a baseline then tests whether the difference of the mean
+//! of each benchmark is significant, so that noisy benchmarks don't need a
+//! large fixed noise threshold.
+
+pub use self::BaselineChange::*;
+
+use stats::Stats;
+
+use serialize::json;
+use std::collections::BTreeMap;
+use std::f64;
+use std::io::fs::{self, PathExtensions};
+use std::io::{self, File};
+use std::num::Float;
+
+/// The z-score of a two-sided 95% confidence interval. Benchmarks take 50
+/// samples, which is plenty for the normal approximation.
+const Z_95: f64 = 1.96;
+
+/// Changes of the mean smaller than this many percent are never considered
+/// significant, however confident we are that they happened.
+const MIN_CHANGE_PCT: f64 = 1.0;
+
+/// The samples of all benchmarks of a run, keyed by benchmark name.
+#[derive(Clone, PartialEq, Show, RustcEncodable, RustcDecodable)]
+pub struct Baseline {
+    benches: BTreeMap<String, Vec<f64>>,
+}
+
+#[derive(Copy, Clone, PartialEq, Show, RustcEncodable)]
+pub enum BaselineChange {
+    /// The difference isn't significant.
+    NoChange,
+    Improved,
+    Regressed,
+    /// The benchmark isn't part of the baseline.
+    Added,
+    /// The benchmark is only part of the baseline.
+    Removed,
+}
+
+/// How a single benchmark compares to its baseline.
+#[derive(Clone, PartialEq, Show, RustcEncodable)]
+pub struct BaselineComparison {
+    pub name: String,
+    pub change: BaselineChange,
+    /// Mean ns/iter in the baseline and in this run.
+    pub old_mean: Option<f64>,
+    pub new_mean: Option<f64>,
+    /// Change of the mean in percent of the baseline's mean, along with the
+    /// bounds of its 95% confidence interval.
+    pub diff_pct: Option<f64>,
+    pub ci_low_pct: Option<f64>,
+    pub ci_high_pct: Option<f64>,
+}
+
+/// The machine readable report of a comparison.
+#[derive(RustcEncodable)]
+struct Report<'a> {
+    baseline: &'a str,
+    benches: &'a [BaselineComparison],
+}
+
+impl Baseline {
+    pub fn new() -> Baseline {
+        Baseline { benches: BTreeMap::new() }
+    }
+
+    pub fn insert(&mut self, name: &str, samples: &[f64]) {
+        self.benches.insert(name.to_string(), samples.to_vec());
+    }
+
+    /// Loads the baseline `name` from `dir`.
+    pub fn load(dir: &Path, name: &str) -> Result<Baseline, String> {
+        let path = baseline_path(dir, name);
+        if !path.exists() {
+            return Err(format!("no baseline `{}` in {}", name, dir.display()));
+        }
+        let contents = try!(File::open(&path).read_to_string().map_err(|e| {
+            format!("couldn't read {}: {}", path.display(), e)
+        }));
+        json::decode(contents.as_slice()).map_err(|e| {
+            format!("couldn't decode {}: {:?}", path.display(), e)
+        })
+    }
+
+    /// Saves the baseline as `name` in `dir`, replacing any baseline of the
+    /// same name.
+    pub fn save(&self, dir: &Path, name: &str) -> io::IoResult<()> {
+        if !dir.exists() {
+            try!(fs::mkdir_recursive(dir, io::USER_DIR));
+        }
+        let mut file = try!(File::create(&baseline_path(dir, name)));
+        write!(&mut file, "{}", json::as_json(self))
+    }
+
+    /// Compares the benchmarks of `self` to those of the older baseline
+    /// `old`, in order of their names.
+    pub fn compare_to(&self, old: &Baseline) -> Vec<BaselineComparison> {
+        let mut comparisons = Vec::new();
+        for (name, new) in self.benches.iter() {
+            comparisons.push(match old.benches.get(name) {
+                Some(old) => compare_samples(name.as_slice(), &old[], &new[]),
+                None => BaselineComparison {
+                    name: name.clone(),
+                    change: Added,
+                    old_mean: None,
+                    new_mean: Some(new.mean()),
+                    diff_pct: None,
+                    ci_low_pct: None,
+                    ci_high_pct: None,
+                },
+            });
+        }
+        for (name, old) in old.benches.iter() {
+            if !self.benches.contains_key(name) {
+                comparisons.push(BaselineComparison {
+                    name: name.clone(),
+                    change: Removed,
+                    old_mean: Some(old.mean()),
+                    new_mean: None,
+                    diff_pct: None,
+                    ci_low_pct: None,
+                    ci_high_pct: None,
+                });
+            }
+        }
+        comparisons.sort_by(|a, b| a.name.cmp(&b.name));
+        comparisons
+    }
+}
+
+/// Writes `comparisons` to `path` as a JSON object.
+pub fn write_report(path: &Path, baseline: &str,
+                    comparisons: &[BaselineComparison]) -> io::IoResult<()> {
+    let report = Report { baseline: baseline, benches: comparisons };
+    let mut file = try!(File::create(path));
+    write!(&mut file, "{}", json::as_pretty_json(&report))
+}
+
+fn baseline_path(dir: &Path, name: &str) -> Path {
+    dir.join(format!("{}.json", name))
+}
+
+/// Compares two sets of samples of the same benchmark using the confidence
+/// interval of the difference of their means (Welch's method).
+fn compare_samples(name: &str, old: &[f64], new: &[f64]) -> BaselineComparison {
+    let (old_mean, new_mean) = (old.mean(), new.mean());
+    let std_err = (old.var() / old.len() as f64 + new.var() / new.len() as f64).sqrt();
+    let diff = new_mean - old_mean;
+    let (low, high) = (diff - Z_95 * std_err, diff + Z_95 * std_err);
+
+    let pct = |&: x: f64| x / old_mean.max(f64::EPSILON) * 100.0;
+    let diff_pct = pct(diff);
+    // Lower is better: the time per iteration went up if even the lower
+    // bound of the interval is above zero.
+    let change = if diff_pct.abs() < MIN_CHANGE_PCT {
+        NoChange
+    } else if low > 0.0 {
+        Regressed
+    } else if high < 0.0 {
+        Improved
+    } else {
+        NoChange
+    };
+
+    BaselineComparison {
+        name: name.to_string(),
+        change: change,
+        old_mean: Some(old_mean),
+        new_mean: Some(new_mean),
+        diff_pct: Some(diff_pct),
+        ci_low_pct: Some(pct(low)),
+        ci_high_pct: Some(pct(high)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Baseline, NoChange, Improved, Regressed, Added, Removed};
+
+    use std::io::TempDir;
+
+    fn samples(center: f64, spread: f64) -> Vec<f64> {
+        range(0u, 50).map(|i| center + spread * ((i % 10) as f64 - 4.5)).collect()
+    }
+
+    #[test]
+    fn test_compare_to() {
+        let mut old = Baseline::new();
+        old.insert("same", &samples(1000.0, 10.0)[]);
+        old.insert("faster", &samples(1000.0, 10.0)[]);
+        old.insert("slower", &samples(1000.0, 10.0)[]);
+        old.insert("noisy", &samples(1000.0, 200.0)[]);
+        old.insert("gone", &samples(1000.0, 10.0)[]);
+
+        let mut new = Baseline::new();
+        new.insert("same", &samples(1002.0, 10.0)[]);
+        new.insert("faster", &samples(900.0, 10.0)[]);
+        new.insert("slower", &samples(1100.0, 10.0)[]);
+        new.insert("noisy", &samples(1050.0, 200.0)[]);
+        new.insert("new", &samples(1000.0, 10.0)[]);
+
+        let changes = new.compare_to(&old).into_iter()
+                         .map(|c| (c.name, c.change))
+                         .collect::<Vec<_>>();
+        assert_eq!(changes, vec![("faster".to_string(), Improved),
+                                 ("gone".to_string(), Removed),
+                                 ("new".to_string(), Added),
+                                 ("noisy".to_string(), NoChange),
+                                 ("same".to_string(), NoChange),
+                                 ("slower".to_string(), Regressed)]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = TempDir::new("test-baseline").unwrap();
+        let dir = dir.path().join("baselines");
+
+        let mut baseline = Baseline::new();
+        baseline.insert("foo", &samples(1000.0, 10.0)[]);
+        baseline.save(&dir, "master").unwrap();
+
+        assert_eq!(Baseline::load(&dir, "master"), Ok(baseline));
+        assert!(Baseline::load(&dir, "other").is_err());
+    }
+}
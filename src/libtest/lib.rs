@@ -45,12 +45,14 @@ extern crate term;
 pub use self::TestFn::*;
 pub use self::MetricChange::*;
 pub use self::ColorConfig::*;
+pub use self::OutputFormat::*;
 pub use self::TestResult::*;
 pub use self::TestName::*;
 use self::TestEvent::*;
 use self::NamePadding::*;
 use self::OutputLocation::*;
 
+use baseline::{Baseline, BaselineComparison, Regressed, Improved, NoChange, Added, Removed};
 use stats::Stats;
 use getopts::{OptGroup, optflag, optopt};
 use regex::Regex;
@@ -62,15 +64,18 @@ use std::any::Any;
 use std::cmp;
 use std::collections::BTreeMap;
 use std::f64;
+use std::i64;
 use std::fmt::Show;
 use std::fmt;
 use std::io::fs::PathExtensions;
 use std::io::stdio::StdWriter;
 use std::io::{File, ChanReader, ChanWriter};
+use std::io::timer::Timer;
 use std::io;
 use std::iter::repeat;
 use std::num::{Float, Int};
 use std::os;
+use std::rand::{self, IsaacRng, Rng, SeedableRng};
 use std::str::FromStr;
 use std::sync::mpsc::{channel, Sender};
 use std::thread::{self, Thread};
@@ -80,15 +85,16 @@ use std::time::Duration;
 // to be used by rustc to compile tests in libtest
 pub mod test {
     pub use {Bencher, TestName, TestResult, TestDesc,
-             TestDescAndFn, TestOpts, TrFailed, TrIgnored, TrOk,
+             TestDescAndFn, TestOpts, TrFailed, TrIgnored, TrOk, TrTimedOut,
              Metric, MetricMap, MetricAdded, MetricRemoved,
              MetricChange, Improvement, Regression, LikelyNoise,
              StaticTestFn, StaticTestName, DynTestName, DynTestFn,
-             run_test, test_main, test_main_static, filter_tests,
+             run_test, test_main, test_main_static, filter_tests, list_tests_console,
              parse_opts, StaticBenchFn, ShouldFail};
 }
 
 pub mod stats;
+pub mod baseline;
 
 // The name of a test. By convention this follows the rules for rust
 // paths; i.e. it should be a series of identifiers separated by double
@@ -209,6 +215,9 @@ pub struct TestDesc {
     pub name: TestName,
     pub ignore: bool,
     pub should_fail: ShouldFail,
+    // Number of seconds after which the test is considered to hang. This
+    // overrides the `--test-timeout` of the run.
+    pub timeout: Option<u64>,
 }
 
 #[derive(Show)]
@@ -260,6 +269,12 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn> ) {
             Some(Err(msg)) => panic!("{:?}", msg),
             None => return
         };
+    if opts.list {
+        if let Err(e) = list_tests_console(&opts, tests) {
+            panic!("io error when listing tests: {:?}", e);
+        }
+        return;
+    }
     match run_tests_console(&opts, tests) {
         Ok(true) => {}
         Ok(false) => panic!("Some tests failed"),
@@ -292,8 +307,33 @@ pub enum ColorConfig {
     NeverColor,
 }
 
+/// How the console test runner reports its progress.
+#[derive(Copy, Clone, PartialEq, Show)]
+pub enum OutputFormat {
+    /// Human readable output, coloured according to the `ColorConfig`.
+    PrettyFormat,
+    /// One JSON object per line for each event of the run.
+    JsonFormat,
+    /// A JUnit XML report, written once all tests have run.
+    JunitFormat,
+}
+
+impl FromStr for OutputFormat {
+    fn from_str(s: &str) -> Option<OutputFormat> {
+        match s {
+            "pretty" => Some(PrettyFormat),
+            "json" => Some(JsonFormat),
+            "junit" => Some(JunitFormat),
+            _ => None,
+        }
+    }
+}
+
 pub struct TestOpts {
-    pub filter: Option<Regex>,
+    pub list: bool,
+    pub filters: Vec<String>,
+    pub filter_exact: bool,
+    pub skip: Vec<String>,
     pub run_ignored: bool,
     pub run_tests: bool,
     pub run_benchmarks: bool,
@@ -304,16 +344,28 @@ pub struct TestOpts {
     pub logfile: Option<Path>,
     pub nocapture: bool,
     pub color: ColorConfig,
+    pub format: OutputFormat,
     pub show_boxplot: bool,
     pub boxplot_width: uint,
     pub show_all_stats: bool,
+    pub test_timeout: Option<u64>,
+    pub report_slowest: Option<uint>,
+    pub shuffle: bool,
+    pub shuffle_seed: Option<u64>,
+    pub save_baseline: Option<String>,
+    pub baseline: Option<String>,
+    pub baseline_dir: Option<Path>,
+    pub baseline_report: Option<Path>,
 }
 
 impl TestOpts {
     #[cfg(test)]
     fn new() -> TestOpts {
         TestOpts {
-            filter: None,
+            list: false,
+            filters: Vec::new(),
+            filter_exact: false,
+            skip: Vec::new(),
             run_ignored: false,
             run_tests: false,
             run_benchmarks: false,
@@ -324,9 +376,18 @@ impl TestOpts {
             logfile: None,
             nocapture: false,
             color: AutoColor,
+            format: PrettyFormat,
             show_boxplot: false,
             boxplot_width: 50,
             show_all_stats: false,
+            test_timeout: None,
+            report_slowest: None,
+            shuffle: false,
+            shuffle_seed: None,
+            save_baseline: None,
+            baseline: None,
+            baseline_dir: None,
+            baseline_report: None,
         }
     }
 }
@@ -336,6 +397,12 @@ pub type OptRes = Result<TestOpts, String>;
 
 fn optgroups() -> Vec<getopts::OptGroup> {
     vec!(getopts::optflag("", "ignored", "Run ignored tests"),
+      getopts::optflag("", "list", "List all tests and benchmarks instead of running them"),
+      getopts::optflag("", "exact", "Run only tests whose names are exactly one of the \
+                                     filters, and skip only tests named exactly like a \
+                                     --skip pattern"),
+      getopts::optmulti("", "skip", "Skip tests whose names match the regex PATTERN \
+                                     (may be given multiple times)", "PATTERN"),
       getopts::optflag("", "test", "Run tests and not benchmarks"),
       getopts::optflag("", "bench", "Run benchmarks instead of tests"),
       getopts::optflag("h", "help", "Display this message (longer with --help)"),
@@ -358,21 +425,50 @@ fn optgroups() -> Vec<getopts::OptGroup> {
             auto   = colorize if stdout is a tty and tests are run on serially (default);
             always = always colorize output;
             never  = never colorize output;", "auto|always|never"),
+      getopts::optopt("", "format", "Configure formatting of output:
+            pretty = human readable output (default);
+            json   = one JSON object per line for each event;
+            junit  = a JUnit XML report once all tests have run;", "pretty|json|junit"),
       getopts::optflag("", "boxplot", "Display a boxplot of the benchmark statistics"),
       getopts::optopt("", "boxplot-width", "Set the boxplot width (default 50)", "WIDTH"),
-      getopts::optflag("", "stats", "Display the benchmark min, max, and quartiles"))
+      getopts::optflag("", "stats", "Display the benchmark min, max, and quartiles"),
+      getopts::optopt("", "test-timeout", "Fail tests which run for longer than SECS \
+                                           seconds (benchmarks are not affected)", "SECS"),
+      getopts::optopt("", "report-slowest", "List the N slowest tests after running \
+                                             them", "N"),
+      getopts::optflag("", "shuffle", "Run tests in a random order"),
+      getopts::optopt("", "shuffle-seed", "Run tests in the random order given by SEED \
+                                           (implies --shuffle)", "SEED"),
+      getopts::optopt("", "save-baseline", "Save the samples of all benchmarks as the \
+                                            baseline NAME", "NAME"),
+      getopts::optopt("", "baseline", "Compare the benchmarks to the baseline NAME; \
+                                       significant regressions fail the run", "NAME"),
+      getopts::optopt("", "baseline-dir", "Directory baselines are kept in \
+                                           (default: bench-baselines)", "PATH"),
+      getopts::optopt("", "baseline-report", "Write the comparison with the baseline \
+                                              to PATH as JSON", "PATH"))
 }
 
 fn usage(binary: &str) {
-    let message = format!("Usage: {} [OPTIONS] [FILTER]", binary);
+    let message = format!("Usage: {} [OPTIONS] [FILTER...]", binary);
     println!(r#"{usage}
 
-The FILTER regex is tested against the name of all tests to run, and
-only those tests that match are run.
+Each FILTER regex is tested against the name of all tests to run, and
+only those tests that match at least one of them are run. With --exact,
+the filters are test names rather than regexes.
+
+Tests can be excluded from the run with --skip, whose PATTERN is matched
+in the same way as the filters. When combined with --list,
+all of the options selecting tests only list the tests which would run.
 
 By default, all tests are run in parallel. This can be altered with the
 RUST_TEST_TASKS environment variable when running tests (set it to 1).
 
+Tests are started in alphabetical order unless --shuffle is given. The seed
+of a shuffled run is printed at the end so that the order can be reproduced
+with --shuffle-seed. Shuffling happens after sharding, so every shard of a
+--test-shard run contains the same tests regardless of the seed.
+
 All tests have their standard output and standard error captured by default.
 This can be overridden with the --nocapture flag or the RUST_TEST_NOCAPTURE=1
 environment variable. Logging is not captured by default.
@@ -390,9 +486,14 @@ Test Attributes:
     #[ignore]      - When applied to a function which is already attributed as a
                      test, then the test runner will ignore these tests during
                      normal test runs. Running with --ignored will run these
-                     tests."#,
+                     tests.
+    #[timeout = "SECS"] - The test (also labeled with #[test]) fails if it runs
+                     for longer than SECS seconds, regardless of --test-timeout.
+                     Tests running for over {warn} seconds are reported either
+                     way."#,
              usage = getopts::usage(message.as_slice(),
-                                    optgroups().as_slice()));
+                                    optgroups().as_slice()),
+             warn = TEST_WARN_TIMEOUT_S);
 }
 
 // Parses command line arguments into test options
@@ -406,15 +507,17 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
 
     if matches.opt_present("h") { usage(args[0].as_slice()); return None; }
 
-    let filter = if matches.free.len() > 0 {
-        let s = matches.free[0].as_slice();
-        match Regex::new(s) {
-            Ok(re) => Some(re),
-            Err(e) => return Some(Err(format!("could not parse /{}/: {:?}", s, e)))
+    let list = matches.opt_present("list");
+    let filter_exact = matches.opt_present("exact");
+    let filters = matches.free.clone();
+    let skip = matches.opt_strs("skip");
+    if !filter_exact {
+        for s in filters.iter().chain(skip.iter()) {
+            if let Err(e) = Regex::new(s.as_slice()) {
+                return Some(Err(format!("could not parse /{}/: {:?}", s, e)));
+            }
         }
-    } else {
-        None
-    };
+    }
 
     let run_ignored = matches.opt_present("ignored");
 
@@ -453,6 +556,16 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
                                             v))),
     };
 
+    let format = match matches.opt_str("format") {
+        Some(v) => match v.parse::<OutputFormat>() {
+            Some(format) => format,
+            None => return Some(Err(format!("argument for --format must be \
+                                             pretty, json, or junit (was {})",
+                                             v))),
+        },
+        None => PrettyFormat,
+    };
+
     let show_boxplot = matches.opt_present("boxplot");
     let boxplot_width = match matches.opt_str("boxplot-width") {
         Some(width) => {
@@ -468,8 +581,48 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
 
     let show_all_stats = matches.opt_present("stats");
 
+    let test_timeout = match matches.opt_str("test-timeout") {
+        Some(secs) => match secs.parse::<u64>() {
+            Some(secs) if secs > 0 => Some(secs),
+            _ => return Some(Err(format!("argument for --test-timeout must be a \
+                                          positive number of seconds (was {})",
+                                          secs))),
+        },
+        None => None,
+    };
+
+    let shuffle_seed = match matches.opt_str("shuffle-seed") {
+        Some(seed) => match seed.parse::<u64>() {
+            Some(seed) => Some(seed),
+            None => return Some(Err(format!("argument for --shuffle-seed must be a \
+                                             u64 (was {})", seed))),
+        },
+        None => None,
+    };
+    let shuffle = matches.opt_present("shuffle") || shuffle_seed.is_some();
+
+    let save_baseline = matches.opt_str("save-baseline");
+    let baseline = matches.opt_str("baseline");
+    let baseline_dir = matches.opt_str("baseline-dir").map(|s| Path::new(s));
+    let baseline_report = matches.opt_str("baseline-report").map(|s| Path::new(s));
+    if baseline_report.is_some() && baseline.is_none() {
+        return Some(Err(format!("--baseline-report requires --baseline")));
+    }
+
+    let report_slowest = match matches.opt_str("report-slowest") {
+        Some(n) => match n.parse::<uint>() {
+            Some(n) => Some(n),
+            None => return Some(Err(format!("argument for --report-slowest must be \
+                                             a uint"))),
+        },
+        None => None,
+    };
+
     let test_opts = TestOpts {
-        filter: filter,
+        list: list,
+        filters: filters,
+        filter_exact: filter_exact,
+        skip: skip,
         run_ignored: run_ignored,
         run_tests: run_tests,
         run_benchmarks: run_benchmarks,
@@ -480,9 +633,18 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         logfile: logfile,
         nocapture: nocapture,
         color: color,
+        format: format,
         show_boxplot: show_boxplot,
         boxplot_width: boxplot_width,
         show_all_stats: show_all_stats,
+        test_timeout: test_timeout,
+        report_slowest: report_slowest,
+        shuffle: shuffle,
+        shuffle_seed: shuffle_seed,
+        save_baseline: save_baseline,
+        baseline: baseline,
+        baseline_dir: baseline_dir,
+        baseline_report: baseline_report,
     };
 
     Some(Ok(test_opts))
@@ -513,6 +675,7 @@ pub fn opt_shard(maybestr: Option<String>) -> Option<(uint,uint)> {
 #[derive(Clone, PartialEq)]
 pub struct BenchSamples {
     ns_iter_summ: stats::Summary<f64>,
+    ns_iter_samples: Vec<f64>,
     mb_s: uint,
 }
 
@@ -521,6 +684,7 @@ pub enum TestResult {
     TrOk,
     TrFailed,
     TrIgnored,
+    TrTimedOut,
     TrMetrics(MetricMap),
     TrBench(BenchSamples),
 }
@@ -534,6 +698,7 @@ struct ConsoleTestState<T> {
     log_out: Option<File>,
     out: OutputLocation<T>,
     use_color: bool,
+    format: OutputFormat,
     show_boxplot: bool,
     boxplot_width: uint,
     show_all_stats: bool,
@@ -544,6 +709,12 @@ struct ConsoleTestState<T> {
     measured: uint,
     metrics: MetricMap,
     failures: Vec<(TestDesc, Vec<u8> )> ,
+    // every result of the run, only kept when writing a JUnit report
+    results: Vec<(TestDesc, TestResult, Vec<u8>, Duration)>,
+    report_slowest: Option<uint>,
+    exec_times: Vec<(TestName, Duration)>,
+    shuffle_seed: Option<u64>,
+    baseline: Baseline,
     max_name_len: uint, // number of columns to fill when aligning names
 }
 
@@ -563,6 +734,7 @@ impl<T: Writer> ConsoleTestState<T> {
             out: out,
             log_out: log_out,
             use_color: use_color(opts),
+            format: opts.format,
             show_boxplot: opts.show_boxplot,
             boxplot_width: opts.boxplot_width,
             show_all_stats: opts.show_all_stats,
@@ -573,6 +745,11 @@ impl<T: Writer> ConsoleTestState<T> {
             measured: 0u,
             metrics: MetricMap::new(),
             failures: Vec::new(),
+            results: Vec::new(),
+            report_slowest: opts.report_slowest,
+            exec_times: Vec::new(),
+            shuffle_seed: None,
+            baseline: Baseline::new(),
             max_name_len: 0u,
         })
     }
@@ -589,6 +766,10 @@ impl<T: Writer> ConsoleTestState<T> {
         self.write_pretty("ignored", term::color::YELLOW)
     }
 
+    pub fn write_timed_out(&mut self) -> io::IoResult<()> {
+        self.write_pretty("TIMEOUT", term::color::RED)
+    }
+
     pub fn write_metric(&mut self) -> io::IoResult<()> {
         self.write_pretty("metric", term::color::CYAN)
     }
@@ -640,21 +821,69 @@ impl<T: Writer> ConsoleTestState<T> {
 
     pub fn write_run_start(&mut self, len: uint) -> io::IoResult<()> {
         self.total = len;
-        let noun = if len != 1 { "tests" } else { "test" };
-        self.write_plain(format!("\nrunning {} {}\n", len, noun).as_slice())
+        match self.format {
+            PrettyFormat => {
+                let noun = if len != 1 { "tests" } else { "test" };
+                self.write_plain(format!("\nrunning {} {}\n", len, noun).as_slice())
+            }
+            JsonFormat => {
+                self.write_plain(format!("{{ \"type\": \"suite\", \"event\": \"started\", \
+                                          \"test_count\": {} }}\n", len).as_slice())
+            }
+            JunitFormat => Ok(()),
+        }
     }
 
     pub fn write_test_start(&mut self, test: &TestDesc,
                             align: NamePadding) -> io::IoResult<()> {
-        let name = test.padded_name(self.max_name_len, align);
-        self.write_plain(format!("test {} ... ", name).as_slice())
+        match self.format {
+            PrettyFormat => {
+                let name = test.padded_name(self.max_name_len, align);
+                self.write_plain(format!("test {} ... ", name).as_slice())
+            }
+            JsonFormat => {
+                self.write_plain(format!("{{ \"type\": \"test\", \"event\": \"started\", \
+                                          \"name\": {} }}\n",
+                                         json_str(test.name.as_slice())).as_slice())
+            }
+            JunitFormat => Ok(()),
+        }
+    }
+
+    pub fn write_long_running(&mut self, test: &TestDesc) -> io::IoResult<()> {
+        match self.format {
+            PrettyFormat => {
+                self.write_plain(format!("test {} has been running for over {} seconds\n",
+                                         test.name, TEST_WARN_TIMEOUT_S).as_slice())
+            }
+            JsonFormat => {
+                self.write_plain(format!("{{ \"type\": \"test\", \"event\": \"long_running\", \
+                                          \"name\": {}, \"elapsed\": {} }}\n",
+                                         json_str(test.name.as_slice()),
+                                         TEST_WARN_TIMEOUT_S).as_slice())
+            }
+            JunitFormat => Ok(()),
+        }
     }
 
-    pub fn write_result(&mut self, result: &TestResult) -> io::IoResult<()> {
+    pub fn write_result(&mut self, test: &TestDesc, result: &TestResult,
+                        stdout: &[u8], duration: Duration) -> io::IoResult<()> {
+        match self.format {
+            PrettyFormat => self.write_pretty_result(result),
+            JsonFormat => self.write_json_result(test, result, stdout, duration),
+            JunitFormat => {
+                self.results.push((test.clone(), result.clone(), stdout.to_vec(), duration));
+                Ok(())
+            }
+        }
+    }
+
+    fn write_pretty_result(&mut self, result: &TestResult) -> io::IoResult<()> {
         try!(match *result {
             TrOk => self.write_ok(),
             TrFailed => self.write_failed(),
             TrIgnored => self.write_ignored(),
+            TrTimedOut => self.write_timed_out(),
             TrMetrics(ref mm) => {
                 try!(self.write_metric());
                 self.write_plain(format!(": {}", fmt_metrics(mm)).as_slice())
@@ -691,6 +920,43 @@ impl<T: Writer> ConsoleTestState<T> {
         self.write_plain("\n")
     }
 
+    fn write_json_result(&mut self, test: &TestDesc, result: &TestResult,
+                         stdout: &[u8], duration: Duration) -> io::IoResult<()> {
+        let name = json_str(test.name.as_slice());
+        let exec_time = secs(duration);
+        let line = match *result {
+            TrOk | TrIgnored => {
+                let event = if *result == TrOk { "ok" } else { "ignored" };
+                format!("{{ \"type\": \"test\", \"event\": \"{}\", \"name\": {}, \
+                         \"exec_time\": {:.6} }}", event, name, exec_time)
+            }
+            TrFailed | TrTimedOut => {
+                let event = if *result == TrFailed { "failed" } else { "timeout" };
+                let stdout = if stdout.len() > 0 {
+                    format!(", \"stdout\": {}",
+                            json_str(String::from_utf8_lossy(stdout).as_slice()))
+                } else {
+                    String::new()
+                };
+                format!("{{ \"type\": \"test\", \"event\": \"{}\", \"name\": {}, \
+                         \"exec_time\": {:.6}{} }}", event, name, exec_time, stdout)
+            }
+            TrMetrics(MetricMap(ref mm)) => {
+                format!("{{ \"type\": \"metric\", \"name\": {}, \"metrics\": {}, \
+                         \"exec_time\": {:.6} }}", name, json::as_json(mm), exec_time)
+            }
+            TrBench(ref bs) => {
+                let summ = &bs.ns_iter_summ;
+                format!("{{ \"type\": \"bench\", \"name\": {}, \"median\": {}, \
+                         \"deviation\": {}, \"min\": {}, \"max\": {}, \"mb_s\": {}, \
+                         \"exec_time\": {:.6} }}",
+                        name, summ.median as u64, (summ.max - summ.min) as u64,
+                        summ.min as u64, summ.max as u64, bs.mb_s, exec_time)
+            }
+        };
+        self.write_plain(format!("{}\n", line).as_slice())
+    }
+
     pub fn write_log(&mut self, test: &TestDesc,
                      result: &TestResult) -> io::IoResult<()> {
         match self.log_out {
@@ -700,6 +966,7 @@ impl<T: Writer> ConsoleTestState<T> {
                         TrOk => "ok".to_string(),
                         TrFailed => "failed".to_string(),
                         TrIgnored => "ignored".to_string(),
+                        TrTimedOut => "timeout".to_string(),
                         TrMetrics(ref mm) => fmt_metrics(mm),
                         TrBench(ref bs) => fmt_bench_samples(bs)
                     }, test.name.as_slice());
@@ -736,6 +1003,55 @@ impl<T: Writer> ConsoleTestState<T> {
         Ok(())
     }
 
+    pub fn write_slowest(&mut self, n: uint) -> io::IoResult<()> {
+        let mut exec_times = self.exec_times.clone();
+        exec_times.sort_by(|a, b| b.1.cmp(&a.1));
+        try!(self.write_plain("\nslowest tests:\n"));
+        for &(ref name, duration) in exec_times.iter().take(n) {
+            try!(self.write_plain(format!("    {:>10.3}s {}\n",
+                                          secs(duration), name).as_slice()));
+        }
+        Ok(())
+    }
+
+    pub fn write_baseline_comparison(&mut self, name: &str,
+                                     comparisons: &[BaselineComparison]) -> io::IoResult<()> {
+        if self.format == JsonFormat {
+            for c in comparisons.iter() {
+                try!(self.write_plain(format!("{{ \"type\": \"bench_comparison\", \
+                                               \"baseline\": {}, \"comparison\": {} }}\n",
+                                              json_str(name), json::as_json(c)).as_slice()));
+            }
+            return Ok(());
+        } else if self.format != PrettyFormat {
+            return Ok(());
+        }
+
+        try!(self.write_plain(format!("\ncomparison with baseline `{}`:\n", name).as_slice()));
+        let (mut regressed, mut improved, mut unchanged) = (0u, 0u, 0u);
+        for c in comparisons.iter() {
+            try!(self.write_plain(format!("{}: ", c.name).as_slice()));
+            match c.change {
+                Added => { try!(self.write_added()); }
+                Removed => { try!(self.write_removed()); }
+                Regressed | Improved | NoChange => {
+                    try!(self.write_plain(format!("{:+.2}% [{:+.2}%, {:+.2}%] ",
+                                                  c.diff_pct.unwrap(),
+                                                  c.ci_low_pct.unwrap(),
+                                                  c.ci_high_pct.unwrap()).as_slice()));
+                    match c.change {
+                        Regressed => { regressed += 1; try!(self.write_regressed()); }
+                        Improved => { improved += 1; try!(self.write_improved()); }
+                        _ => { unchanged += 1; try!(self.write_plain("no change")); }
+                    }
+                }
+            }
+            try!(self.write_plain("\n"));
+        }
+        self.write_plain(format!("result of comparison: {} regressed, {} improved, \
+                                  {} unchanged\n", regressed, improved, unchanged).as_slice())
+    }
+
     pub fn write_metric_diff(&mut self, diff: &MetricDiff) -> io::IoResult<()> {
         let mut noise = 0u;
         let mut improved = 0u;
@@ -786,34 +1102,95 @@ impl<T: Writer> ConsoleTestState<T> {
     }
 
     pub fn write_run_finish(&mut self,
-                            ratchet_metrics: &Option<Path>,
-                            ratchet_pct: Option<f64>) -> io::IoResult<bool> {
+                            opts: &TestOpts,
+                            exec_time: Duration) -> io::IoResult<bool> {
         assert!(self.passed + self.failed + self.ignored + self.measured == self.total);
 
+        let ratchet_metrics = &opts.ratchet_metrics;
+        let ratchet_pct = opts.ratchet_noise_percent;
+
+        // Only the human readable output has room for the details of the
+        // ratchet; the other formats just report whether the run succeeded.
+        let pretty = self.format == PrettyFormat;
         let ratchet_success = match *ratchet_metrics {
             None => true,
             Some(ref pth) => {
-                try!(self.write_plain(format!("\nusing metrics ratchet: {:?}\n",
-                                              pth.display()).as_slice()));
-                match ratchet_pct {
-                    None => (),
-                    Some(pct) =>
-                        try!(self.write_plain(format!("with noise-tolerance \
-                                                         forced to: {}%\n",
-                                                        pct).as_slice()))
+                if pretty {
+                    try!(self.write_plain(format!("\nusing metrics ratchet: {:?}\n",
+                                                  pth.display()).as_slice()));
+                    match ratchet_pct {
+                        None => (),
+                        Some(pct) =>
+                            try!(self.write_plain(format!("with noise-tolerance \
+                                                             forced to: {}%\n",
+                                                            pct).as_slice()))
+                    }
                 }
                 let (diff, ok) = self.metrics.ratchet(pth, ratchet_pct);
-                try!(self.write_metric_diff(&diff));
+                if pretty {
+                    try!(self.write_metric_diff(&diff));
+                }
                 ok
             }
         };
 
-        let test_success = self.failed == 0u;
-        if !test_success {
-            try!(self.write_failures());
+        let baseline_success = match opts.baseline {
+            None => true,
+            Some(ref name) => {
+                let dir = baseline_dir(opts);
+                let old = match Baseline::load(&dir, name.as_slice()) {
+                    Ok(old) => old,
+                    Err(e) => return Err(io::IoError {
+                        kind: io::OtherIoError,
+                        desc: "failed to load the benchmark baseline",
+                        detail: Some(e),
+                    }),
+                };
+                let comparisons = self.baseline.compare_to(&old);
+                match opts.baseline_report {
+                    Some(ref path) => {
+                        try!(baseline::write_report(path, name.as_slice(), &comparisons[]));
+                    }
+                    None => {}
+                }
+                try!(self.write_baseline_comparison(name.as_slice(), &comparisons[]));
+                comparisons.iter().all(|c| c.change != Regressed)
+            }
+        };
+
+        match opts.save_baseline {
+            Some(ref name) => {
+                let dir = baseline_dir(opts);
+                try!(self.baseline.save(&dir, name.as_slice()));
+                if pretty {
+                    try!(self.write_plain(format!("\nbaseline `{}` saved to {:?}\n", name,
+                                                  dir.display()).as_slice()));
+                }
+            }
+            None => {}
         }
 
-        let success = ratchet_success && test_success;
+        let success = ratchet_success && baseline_success && self.failed == 0u;
+        try!(match self.format {
+            PrettyFormat => self.write_pretty_summary(success),
+            JsonFormat => self.write_json_summary(success, exec_time),
+            JunitFormat => self.write_junit_report(exec_time),
+        });
+        Ok(success)
+    }
+
+    fn write_pretty_summary(&mut self, success: bool) -> io::IoResult<()> {
+        if self.failed != 0u {
+            try!(self.write_failures());
+        }
+        if let Some(n) = self.report_slowest {
+            try!(self.write_slowest(n));
+        }
+        if let Some(seed) = self.shuffle_seed {
+            try!(self.write_plain(format!("\ntests were shuffled with seed {0}, use \
+                                           --shuffle-seed={0} to run them in the same \
+                                           order again\n", seed).as_slice()));
+        }
 
         try!(self.write_plain("\ntest result: "));
         if success {
@@ -824,11 +1201,104 @@ impl<T: Writer> ConsoleTestState<T> {
         }
         let s = format!(". {} passed; {} failed; {} ignored; {} measured\n\n",
                         self.passed, self.failed, self.ignored, self.measured);
-        try!(self.write_plain(s.as_slice()));
-        return Ok(success);
+        self.write_plain(s.as_slice())
+    }
+
+    fn write_json_summary(&mut self, success: bool, exec_time: Duration) -> io::IoResult<()> {
+        let shuffle_seed = match self.shuffle_seed {
+            Some(seed) => format!(", \"shuffle_seed\": {}", seed),
+            None => String::new(),
+        };
+        let s = format!("{{ \"type\": \"suite\", \"event\": \"{}\", \"passed\": {}, \
+                         \"failed\": {}, \"ignored\": {}, \"measured\": {}, \
+                         \"exec_time\": {:.6}{} }}\n",
+                        if success { "ok" } else { "failed" },
+                        self.passed, self.failed, self.ignored, self.measured,
+                        secs(exec_time), shuffle_seed);
+        self.write_plain(s.as_slice())
+    }
+
+    fn write_junit_report(&mut self, exec_time: Duration) -> io::IoResult<()> {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+        out.push_str(format!("<testsuite name=\"test\" errors=\"0\" failures=\"{}\" \
+                              tests=\"{}\" skipped=\"{}\" time=\"{:.6}\">\n",
+                             self.failed, self.total, self.ignored,
+                             secs(exec_time)).as_slice());
+        if let Some(seed) = self.shuffle_seed {
+            out.push_str(format!("<properties>\n<property name=\"shuffle_seed\" \
+                                  value=\"{}\"/>\n</properties>\n", seed).as_slice());
+        }
+        for &(ref test, ref result, ref stdout, duration) in self.results.iter() {
+            // JUnit wants the tests grouped into classes, so use the module
+            // path of the test for that.
+            let name = test.name.as_slice();
+            let (class, name) = match name.match_indices("::").last() {
+                Some((i, j)) => (&name[..i], &name[j..]),
+                None => ("crate", name),
+            };
+            out.push_str(format!("<testcase classname=\"{}\" name=\"{}\" time=\"{:.6}\"",
+                                 xml_escape(class), xml_escape(name),
+                                 secs(duration)).as_slice());
+            match *result {
+                TrFailed => {
+                    out.push_str(">\n<failure type=\"assert\"/>\n");
+                    if stdout.len() > 0 {
+                        let stdout = String::from_utf8_lossy(stdout.as_slice());
+                        out.push_str(format!("<system-out>{}</system-out>\n",
+                                             xml_escape(stdout.as_slice())).as_slice());
+                    }
+                    out.push_str("</testcase>\n");
+                }
+                TrTimedOut => {
+                    out.push_str(">\n<failure type=\"timeout\"/>\n</testcase>\n");
+                }
+                TrIgnored => out.push_str(">\n<skipped/>\n</testcase>\n"),
+                TrBench(ref bs) => {
+                    out.push_str(format!(">\n<system-out>{}</system-out>\n</testcase>\n",
+                                         xml_escape(fmt_bench_samples(bs).trim())).as_slice());
+                }
+                TrMetrics(ref mm) => {
+                    out.push_str(format!(">\n<system-out>{}</system-out>\n</testcase>\n",
+                                         xml_escape(fmt_metrics(mm).as_slice())).as_slice());
+                }
+                TrOk => out.push_str("/>\n"),
+            }
+        }
+        out.push_str("</testsuite>\n</testsuites>\n");
+        self.write_plain(out.as_slice())
     }
 }
 
+fn baseline_dir(opts: &TestOpts) -> Path {
+    opts.baseline_dir.clone().unwrap_or_else(|| Path::new("bench-baselines"))
+}
+
+/// Quotes and escapes `s` for use as a JSON string.
+fn json_str(s: &str) -> String {
+    json::as_json(&s.to_string()).to_string()
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The length of `d` in (fractional) seconds.
+fn secs(d: Duration) -> f64 {
+    d.num_nanoseconds().unwrap_or(i64::MAX) as f64 / 1_000_000_000.0
+}
+
 pub fn fmt_metrics(mm: &MetricMap) -> String {
     let MetricMap(ref mm) = *mm;
     let v : Vec<String> = mm.iter()
@@ -851,16 +1321,62 @@ pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
     }
 }
 
+/// Prints the tests which would be run with `opts`, one per line, without
+/// running them.
+pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::IoResult<()> {
+    let mut out = io::stdout();
+    let tests = filter_tests(opts, tests);
+    let (mut ntests, mut nbenches, mut nmetrics) = (0u, 0u, 0u);
+    for test in tests.iter() {
+        let kind = match test.testfn {
+            StaticTestFn(..) | DynTestFn(..) => { ntests += 1; "test" }
+            StaticBenchFn(..) | DynBenchFn(..) => { nbenches += 1; "bench" }
+            StaticMetricFn(..) | DynMetricFn(..) => { nmetrics += 1; "metric" }
+        };
+        let name = test.desc.name.as_slice();
+        try!(match opts.format {
+            JsonFormat => {
+                writeln!(&mut out, "{{ \"type\": \"{}\", \"event\": \"discovered\", \
+                                    \"name\": {}, \"ignored\": {} }}",
+                         kind, json_str(name), test.desc.ignore)
+            }
+            PrettyFormat | JunitFormat => {
+                writeln!(&mut out, "{}: {}{}", name, kind,
+                         if test.desc.ignore { " (ignored)" } else { "" })
+            }
+        });
+    }
+
+    if opts.format != JsonFormat {
+        fn plural(count: uint, s: &str) -> String {
+            format!("{} {}{}", count, s, if count == 1 { "" } else { "s" })
+        }
+        try!(writeln!(&mut out, "\n{}, {}, {}", plural(ntests, "test"),
+                      plural(nbenches, "benchmark"), plural(nmetrics, "metric")));
+    }
+    Ok(())
+}
+
 // A simple console test runner
 pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::IoResult<bool> {
 
     fn callback<T: Writer>(event: &TestEvent, st: &mut ConsoleTestState<T>) -> io::IoResult<()> {
         match (*event).clone() {
-            TeFiltered(ref filtered_tests) => st.write_run_start(filtered_tests.len()),
+            TeFiltered(ref filtered_tests, shuffle_seed) => {
+                st.shuffle_seed = shuffle_seed;
+                st.write_run_start(filtered_tests.len())
+            }
             TeWait(ref test, padding) => st.write_test_start(test, padding),
-            TeResult(test, result, stdout) => {
+            TeLongRunning(ref test) => st.write_long_running(test),
+            TeResult(test, result, stdout, duration) => {
                 try!(st.write_log(&test, &result));
-                try!(st.write_result(&result));
+                try!(st.write_result(&test, &result, &stdout[], duration));
+                match result {
+                    TrOk | TrFailed | TrTimedOut => {
+                        st.exec_times.push((test.name.clone(), duration));
+                    }
+                    _ => {}
+                }
                 match result {
                     TrOk => st.passed += 1,
                     TrIgnored => st.ignored += 1,
@@ -881,9 +1397,10 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::IoR
                         st.metrics.insert_metric(test.name.as_slice(),
                                                  bs.ns_iter_summ.median,
                                                  bs.ns_iter_summ.max - bs.ns_iter_summ.min);
+                        st.baseline.insert(test.name.as_slice(), &bs.ns_iter_samples[]);
                         st.measured += 1
                     }
-                    TrFailed => {
+                    TrFailed | TrTimedOut => {
                         st.failed += 1;
                         st.failures.push((test, stdout));
                     }
@@ -907,16 +1424,22 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::IoR
         },
         None => {}
     }
-    try!(run_tests(opts, tests, |x| callback(&x, &mut st)));
+    let mut result = Ok(());
+    let exec_time = Duration::span(|| {
+        result = run_tests(opts, tests, |x| callback(&x, &mut st));
+    });
+    try!(result);
     match opts.save_metrics {
         None => (),
         Some(ref pth) => {
             try!(st.metrics.save(pth));
-            try!(st.write_plain(format!("\nmetrics saved to: {:?}",
-                                          pth.display()).as_slice()));
+            if st.format == PrettyFormat {
+                try!(st.write_plain(format!("\nmetrics saved to: {:?}",
+                                              pth.display()).as_slice()));
+            }
         }
     }
-    return st.write_run_finish(&opts.ratchet_metrics, opts.ratchet_noise_percent);
+    return st.write_run_finish(opts, exec_time);
 }
 
 #[test]
@@ -924,19 +1447,22 @@ fn should_sort_failures_before_printing_them() {
     let test_a = TestDesc {
         name: StaticTestName("a"),
         ignore: false,
-        should_fail: ShouldFail::No
+        should_fail: ShouldFail::No,
+        timeout: None
     };
 
     let test_b = TestDesc {
         name: StaticTestName("b"),
         ignore: false,
-        should_fail: ShouldFail::No
+        should_fail: ShouldFail::No,
+        timeout: None
     };
 
     let mut st = ConsoleTestState {
         log_out: None,
         out: Raw(Vec::new()),
         use_color: false,
+        format: PrettyFormat,
         show_boxplot: false,
         boxplot_width: 0,
         show_all_stats: false,
@@ -947,7 +1473,12 @@ fn should_sort_failures_before_printing_them() {
         measured: 0u,
         max_name_len: 10u,
         metrics: MetricMap::new(),
-        failures: vec!((test_b, Vec::new()), (test_a, Vec::new()))
+        failures: vec!((test_b, Vec::new()), (test_a, Vec::new())),
+        results: Vec::new(),
+        report_slowest: None,
+        exec_times: Vec::new(),
+        shuffle_seed: None,
+        baseline: Baseline::new(),
     };
 
     st.write_failures().unwrap();
@@ -971,12 +1502,30 @@ fn use_color(opts: &TestOpts) -> bool {
 
 #[derive(Clone)]
 enum TestEvent {
-    TeFiltered(Vec<TestDesc> ),
+    TeFiltered(Vec<TestDesc>, Option<u64>),
     TeWait(TestDesc, NamePadding),
-    TeResult(TestDesc, TestResult, Vec<u8> ),
+    TeLongRunning(TestDesc),
+    TeResult(TestDesc, TestResult, Vec<u8>, Duration),
 }
 
-pub type MonitorMsg = (TestDesc, TestResult, Vec<u8> );
+/// Tests which have been running for this many seconds are reported, whether
+/// or not they have a timeout.
+pub const TEST_WARN_TIMEOUT_S: u64 = 60;
+
+/// A test which has been started but whose result hasn't been reported yet.
+struct RunningTest {
+    desc: TestDesc,
+    /// The number of seconds after which the test times out, if any.
+    timeout: Option<u64>,
+    /// When the test was started, as given by the clock in `run_tests`.
+    started: Duration,
+    /// Whether the test has been reported as running for a long time.
+    reported_long_running: bool,
+}
+
+/// The outcome of a single test: its result, its captured output and how
+/// long it took to run.
+pub type MonitorMsg = (TestDesc, TestResult, Vec<u8>, Duration);
 
 unsafe impl Send for MonitorMsg {}
 
@@ -985,12 +1534,22 @@ fn run_tests<F>(opts: &TestOpts,
                 mut callback: F) -> io::IoResult<()> where
     F: FnMut(TestEvent) -> io::IoResult<()>,
 {
-    let filtered_tests = filter_tests(opts, tests);
+    let mut filtered_tests = filter_tests(opts, tests);
+
+    let shuffle_seed = if opts.shuffle || opts.shuffle_seed.is_some() {
+        Some(opts.shuffle_seed.unwrap_or_else(|| rand::random()))
+    } else {
+        None
+    };
+    if let Some(seed) = shuffle_seed {
+        shuffle_tests(seed, filtered_tests.as_mut_slice());
+    }
+
     let filtered_descs = filtered_tests.iter()
                                        .map(|t| t.desc.clone())
                                        .collect();
 
-    try!(callback(TeFiltered(filtered_descs)));
+    try!(callback(TeFiltered(filtered_descs, shuffle_seed)));
 
     let (filtered_tests, filtered_benchs_and_metrics): (Vec<_>, _) =
         filtered_tests.into_iter().partition(|e| {
@@ -1010,6 +1569,18 @@ fn run_tests<F>(opts: &TestOpts,
 
     let (tx, rx) = channel::<MonitorMsg>();
 
+    // Running tests are checked once a second to find the ones which have
+    // been running for too long.
+    let mut timer = try!(Timer::new());
+    let ticks = timer.periodic(Duration::seconds(1));
+    let mut running: Vec<RunningTest> = Vec::new();
+
+    // Ticks can queue up while tests are being started, so the time tests
+    // have been running for is measured rather than counted in ticks: the
+    // clock is advanced by the time spent waiting for them, which is nearly
+    // all of it.
+    let mut clock = Duration::zero();
+
     while pending > 0 || !remaining.is_empty() {
         while pending < concurrency && !remaining.is_empty() {
             let test = remaining.pop().unwrap();
@@ -1019,16 +1590,63 @@ fn run_tests<F>(opts: &TestOpts,
                 // that hang forever.
                 try!(callback(TeWait(test.desc.clone(), test.testfn.padding())));
             }
+            running.push(RunningTest {
+                timeout: test.desc.timeout.or(opts.test_timeout),
+                desc: test.desc.clone(),
+                started: clock,
+                reported_long_running: false,
+            });
             run_test(opts, !opts.run_tests, test, tx.clone());
             pending += 1;
         }
 
-        let (desc, result, stdout) = rx.recv().unwrap();
-        if concurrency != 1 {
-            try!(callback(TeWait(desc.clone(), PadNone)));
+        let mut msg = None;
+        clock = clock + Duration::span(|| {
+            msg = select! {
+                msg = rx.recv() => Some(msg.unwrap()),
+                _tick = ticks.recv() => None
+            };
+        });
+
+        let finished = match msg {
+            Some((desc, result, stdout, duration)) => {
+                // A test which timed out has already been reported, even
+                // though its thread could not be stopped.
+                match running.iter().position(|t| t.desc == desc) {
+                    Some(i) => { running.remove(i); }
+                    None => continue,
+                }
+                vec![(desc, result, stdout, duration)]
+            }
+            None => {
+                let mut timed_out = Vec::new();
+                let mut still_running = Vec::new();
+                for mut t in running.into_iter() {
+                    let duration = clock - t.started;
+                    let elapsed = duration.num_seconds() as u64;
+                    if elapsed >= TEST_WARN_TIMEOUT_S && !t.reported_long_running {
+                        t.reported_long_running = true;
+                        try!(callback(TeLongRunning(t.desc.clone())));
+                    }
+                    match t.timeout {
+                        Some(limit) if elapsed >= limit => {
+                            timed_out.push((t.desc, TrTimedOut, Vec::new(), duration));
+                        }
+                        _ => still_running.push(t),
+                    }
+                }
+                running = still_running;
+                timed_out
+            }
+        };
+
+        for (desc, result, stdout, duration) in finished.into_iter() {
+            if concurrency != 1 {
+                try!(callback(TeWait(desc.clone(), PadNone)));
+            }
+            try!(callback(TeResult(desc, result, stdout, duration)));
+            pending -= 1;
         }
-        try!(callback(TeResult(desc, result, stdout)));
-        pending -= 1;
     }
 
     // All benchmarks run at the end, in serial.
@@ -1036,8 +1654,8 @@ fn run_tests<F>(opts: &TestOpts,
     for b in filtered_benchs_and_metrics.into_iter() {
         try!(callback(TeWait(b.desc.clone(), b.testfn.padding())));
         run_test(opts, !opts.run_benchmarks, b, tx.clone());
-        let (test, result, stdout) = rx.recv().unwrap();
-        try!(callback(TeResult(test, result, stdout)));
+        let (test, result, stdout, duration) = rx.recv().unwrap();
+        try!(callback(TeResult(test, result, stdout, duration)));
     }
     Ok(())
 }
@@ -1061,14 +1679,24 @@ fn get_concurrency() -> uint {
 pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
     let mut filtered = tests;
 
-    // Remove tests that don't match the test filter
-    filtered = match opts.filter {
-        None => filtered,
-        Some(ref re) => {
-            filtered.into_iter()
-                .filter(|test| re.is_match(test.desc.name.as_slice())).collect()
-        }
-    };
+    let filters = opts.filters.iter().map(|s| {
+        NamePattern::new(s.as_slice(), opts.filter_exact)
+    }).collect::<Vec<_>>();
+    let skip = opts.skip.iter().map(|s| {
+        NamePattern::new(s.as_slice(), opts.filter_exact)
+    }).collect::<Vec<_>>();
+
+    // Remove tests that don't match any of the test filters
+    if !filters.is_empty() {
+        filtered = filtered.into_iter().filter(|test| {
+            filters.iter().any(|p| p.matches(test.desc.name.as_slice()))
+        }).collect();
+    }
+
+    // Remove tests that match any of the skip patterns
+    filtered = filtered.into_iter().filter(|test| {
+        !skip.iter().any(|p| p.matches(test.desc.name.as_slice()))
+    }).collect();
 
     // Maybe pull out the ignored test and unignore them
     filtered = if !opts.run_ignored {
@@ -1105,6 +1733,42 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
     }
 }
 
+/// A test filter or `--skip` pattern. Both are regexes matched anywhere in
+/// the names of tests, or with `--exact` the whole names of tests.
+enum NamePattern {
+    Exact(String),
+    Regex(Regex),
+}
+
+impl NamePattern {
+    fn new(pattern: &str, exact: bool) -> NamePattern {
+        if exact {
+            return NamePattern::Exact(pattern.to_string());
+        }
+        // `parse_opts` rejects patterns which aren't valid regexes, but a
+        // harness building its own `TestOpts` may still pass one.
+        match Regex::new(pattern) {
+            Ok(re) => NamePattern::Regex(re),
+            Err(e) => panic!("could not parse /{}/: {:?}", pattern, e),
+        }
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match *self {
+            NamePattern::Exact(ref s) => s.as_slice() == name,
+            NamePattern::Regex(ref re) => re.is_match(name),
+        }
+    }
+}
+
+/// Puts `tests` into an order determined by `seed`. The same seed always
+/// results in the same order of the same tests.
+pub fn shuffle_tests(seed: u64, tests: &mut [TestDescAndFn]) {
+    let seed = [seed as u32, (seed >> 32) as u32];
+    let mut rng: IsaacRng = SeedableRng::from_seed(&seed[]);
+    rng.shuffle(tests);
+}
+
 pub fn run_test(opts: &TestOpts,
                 force_ignore: bool,
                 test: TestDescAndFn,
@@ -1113,7 +1777,7 @@ pub fn run_test(opts: &TestOpts,
     let TestDescAndFn {desc, testfn} = test;
 
     if force_ignore || desc.ignore {
-        monitor_ch.send((desc, TrIgnored, Vec::new())).unwrap();
+        monitor_ch.send((desc, TrIgnored, Vec::new(), Duration::zero())).unwrap();
         return;
     }
 
@@ -1137,34 +1801,43 @@ pub fn run_test(opts: &TestOpts,
                 cfg = cfg.stderr(box stderr as Box<Writer + Send>);
             }
 
-            let result_guard = cfg.scoped(move || { testfn.invoke(()) });
+            let mut task_result = None;
+            let duration = Duration::span(|| {
+                task_result = Some(cfg.scoped(move || { testfn.invoke(()) }).join());
+            });
             let stdout = reader.read_to_end().unwrap().into_iter().collect();
-            let test_result = calc_result(&desc, result_guard.join());
-            monitor_ch.send((desc.clone(), test_result, stdout)).unwrap();
+            let test_result = calc_result(&desc, task_result.unwrap());
+            monitor_ch.send((desc.clone(), test_result, stdout, duration)).unwrap();
         });
     }
 
     match testfn {
         DynBenchFn(bencher) => {
-            let bs = ::bench::benchmark(|harness| bencher.run(harness));
-            monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
+            let mut bs = None;
+            let duration = Duration::span(|| {
+                bs = Some(::bench::benchmark(|harness| bencher.run(harness)));
+            });
+            monitor_ch.send((desc, TrBench(bs.unwrap()), Vec::new(), duration)).unwrap();
             return;
         }
         StaticBenchFn(benchfn) => {
-            let bs = ::bench::benchmark(|harness| (benchfn.clone())(harness));
-            monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
+            let mut bs = None;
+            let duration = Duration::span(|| {
+                bs = Some(::bench::benchmark(|harness| (benchfn.clone())(harness)));
+            });
+            monitor_ch.send((desc, TrBench(bs.unwrap()), Vec::new(), duration)).unwrap();
             return;
         }
         DynMetricFn(f) => {
             let mut mm = MetricMap::new();
-            f.invoke(&mut mm);
-            monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
+            let duration = Duration::span(|| f.invoke(&mut mm));
+            monitor_ch.send((desc, TrMetrics(mm), Vec::new(), duration)).unwrap();
             return;
         }
         StaticMetricFn(f) => {
             let mut mm = MetricMap::new();
-            f(&mut mm);
-            monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
+            let duration = Duration::span(|| f(&mut mm));
+            monitor_ch.send((desc, TrMetrics(mm), Vec::new(), duration)).unwrap();
             return;
         }
         DynTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture, f),
@@ -1368,7 +2041,15 @@ impl Bencher {
     }
 
     // This is a more statistics-driven benchmark algorithm
-    pub fn auto_bench<F>(&mut self, mut f: F) -> stats::Summary<f64> where F: FnMut(&mut Bencher) {
+    pub fn auto_bench<F>(&mut self, f: F) -> stats::Summary<f64> where F: FnMut(&mut Bencher) {
+        self.auto_bench_samples(f).0
+    }
+
+    // Like `auto_bench`, but also returns the samples the summary was made
+    // from.
+    fn auto_bench_samples<F>(&mut self, mut f: F) -> (stats::Summary<f64>, Vec<f64>) where
+        F: FnMut(&mut Bencher),
+    {
         // Initial bench run to get ballpark figure.
         let mut n = 1_u64;
         self.bench_n(n, |x| f(x));
@@ -1419,13 +2100,13 @@ impl Bencher {
             if loop_run.num_milliseconds() > 100 &&
                 summ.median_abs_dev_pct < 1.0 &&
                 summ.median - summ5.median < summ5.median_abs_dev {
-                return summ5;
+                return (summ5, samples.to_vec());
             }
 
             total_run = total_run + loop_run;
             // Longest we ever run for is 3s.
             if total_run.num_seconds() > 3 {
-                return summ5;
+                return (summ5, samples.to_vec());
             }
 
             n *= 2;
@@ -1445,7 +2126,7 @@ pub mod bench {
             bytes: 0
         };
 
-        let ns_iter_summ = bs.auto_bench(f);
+        let (ns_iter_summ, ns_iter_samples) = bs.auto_bench_samples(f);
 
         let ns_iter = cmp::max(ns_iter_summ.median as u64, 1);
         let iter_s = 1_000_000_000 / ns_iter;
@@ -1453,6 +2134,7 @@ pub mod bench {
 
         BenchSamples {
             ns_iter_summ: ns_iter_summ,
+            ns_iter_samples: ns_iter_samples,
             mb_s: mb_s as uint
         }
     }
@@ -1464,11 +2146,73 @@ mod tests {
                TestDesc, TestDescAndFn, TestOpts, run_test,
                Metric, MetricMap, MetricAdded, MetricRemoved,
                Improvement, Regression, LikelyNoise,
-               StaticTestName, DynTestName, DynTestFn, ShouldFail};
+               StaticTestName, DynTestName, DynTestFn, ShouldFail, TrTimedOut};
+    use {JsonFormat, OutputFormat, run_tests, shuffle_tests};
+    use ConsoleTestState;
+    use OutputLocation::{Raw, Pretty};
+    use NamePadding::PadNone;
+    use TestEvent::TeResult;
+    use baseline::Baseline;
+    use serialize::json;
+    use std::io::timer;
+    use std::time::Duration;
     use std::io::TempDir;
     use std::thunk::Thunk;
     use std::sync::mpsc::channel;
 
+    /// A console state writing to memory, with nothing recorded yet.
+    fn console_state(format: OutputFormat) -> ConsoleTestState<Vec<u8>> {
+        ConsoleTestState {
+            log_out: None,
+            out: Raw(Vec::new()),
+            use_color: false,
+            format: format,
+            show_boxplot: false,
+            boxplot_width: 0,
+            show_all_stats: false,
+            total: 0u,
+            passed: 0u,
+            failed: 0u,
+            ignored: 0u,
+            measured: 0u,
+            max_name_len: 10u,
+            metrics: MetricMap::new(),
+            failures: Vec::new(),
+            results: Vec::new(),
+            report_slowest: None,
+            exec_times: Vec::new(),
+            shuffle_seed: None,
+            baseline: Baseline::new(),
+        }
+    }
+
+    #[test]
+    fn should_write_one_json_object_per_result() {
+        let test = TestDesc {
+            name: StaticTestName("a::b"),
+            ignore: false,
+            should_fail: ShouldFail::No,
+            timeout: None
+        };
+
+        let mut st = console_state(JsonFormat);
+
+        st.write_test_start(&test, PadNone).unwrap();
+        st.write_result(&test, &TrFailed, b"oh \"no\"\n", Duration::milliseconds(1500)).unwrap();
+        let s = match st.out {
+            Raw(ref m) => String::from_utf8_lossy(&m[]).into_owned(),
+            Pretty(_) => unreachable!()
+        };
+
+        let events: Vec<json::Json> = s.lines().map(|l| json::from_str(l).unwrap()).collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["event"].as_string(), Some("started"));
+        assert_eq!(events[1]["event"].as_string(), Some("failed"));
+        assert_eq!(events[1]["name"].as_string(), Some("a::b"));
+        assert_eq!(events[1]["stdout"].as_string(), Some("oh \"no\"\n"));
+        assert_eq!(events[1]["exec_time"].as_f64(), Some(1.5));
+    }
+
     #[test]
     pub fn do_not_run_ignored_tests() {
         fn f() { panic!(); }
@@ -1477,12 +2221,13 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: true,
                 should_fail: ShouldFail::No,
+                timeout: None,
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res != TrOk);
     }
 
@@ -1494,12 +2239,13 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: true,
                 should_fail: ShouldFail::No,
+                timeout: None,
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrIgnored);
     }
 
@@ -1510,13 +2256,14 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_fail: ShouldFail::Yes(None)
+                should_fail: ShouldFail::Yes(None),
+                timeout: None
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
@@ -1527,13 +2274,14 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_fail: ShouldFail::Yes(Some("error message"))
+                should_fail: ShouldFail::Yes(Some("error message")),
+                timeout: None,
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
@@ -1544,13 +2292,14 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_fail: ShouldFail::Yes(Some("foobar"))
+                should_fail: ShouldFail::Yes(Some("foobar")),
+                timeout: None,
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailed);
     }
 
@@ -1561,13 +2310,14 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_fail: ShouldFail::Yes(None)
+                should_fail: ShouldFail::Yes(None),
+                timeout: None
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailed);
     }
 
@@ -1578,7 +2328,38 @@ mod tests {
             Some(Ok(o)) => o,
             _ => panic!("Malformed arg in first_free_arg_should_be_a_filter")
         };
-        assert!(opts.filter.expect("should've found filter").is_match("some_regex_filter"))
+        assert_eq!(opts.filters, vec!("some_regex_filter".to_string()));
+    }
+
+    #[test]
+    fn parse_filters_and_skips() {
+        let args = vec!("progname".to_string(),
+                        "foo".to_string(),
+                        "bar".to_string(),
+                        "--exact".to_string(),
+                        "--skip".to_string(),
+                        "baz".to_string(),
+                        "--skip=qux".to_string());
+        let opts = match parse_opts(args.as_slice()) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_filters_and_skips")
+        };
+        assert_eq!(opts.filters, vec!("foo".to_string(), "bar".to_string()));
+        assert!(opts.filter_exact);
+        assert_eq!(opts.skip, vec!("baz".to_string(), "qux".to_string()));
+    }
+
+    #[test]
+    fn parse_malformed_patterns() {
+        let args = vec!("progname".to_string(), "foo(".to_string());
+        assert!(parse_opts(args.as_slice()).unwrap().is_err());
+
+        let args = vec!("progname".to_string(), "--skip=foo(".to_string());
+        assert!(parse_opts(args.as_slice()).unwrap().is_err());
+
+        // Exact names needn't be regexes.
+        let args = vec!("progname".to_string(), "--exact".to_string(), "foo(".to_string());
+        assert!(parse_opts(args.as_slice()).unwrap().is_ok());
     }
 
     #[test]
@@ -1593,6 +2374,62 @@ mod tests {
         assert!((opts.run_ignored));
     }
 
+    #[test]
+    fn test_hanging_test_times_out() {
+        fn f() { loop { timer::sleep(Duration::seconds(60)); } }
+        let mut opts = TestOpts::new();
+        opts.run_tests = true;
+        opts.test_timeout = Some(1);
+        let tests = vec!(TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("whatever"),
+                ignore: false,
+                should_fail: ShouldFail::No,
+                timeout: None,
+            },
+            testfn: DynTestFn(Thunk::new(move|| f())),
+        });
+        let mut results = Vec::new();
+        run_tests(&opts, tests, |event| {
+            if let TeResult(_, res, _, _) = event {
+                results.push(res);
+            }
+            Ok(())
+        }).unwrap();
+        assert!(results == vec!(TrTimedOut));
+    }
+
+    #[test]
+    fn parse_test_timeout_flag() {
+        let args = vec!("progname".to_string(),
+                        "--test-timeout".to_string(),
+                        "30".to_string());
+        let opts = match parse_opts(args.as_slice()) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_test_timeout_flag")
+        };
+        assert_eq!(opts.test_timeout, Some(30));
+
+        let args = vec!("progname".to_string(),
+                        "--test-timeout=0".to_string());
+        assert!(parse_opts(args.as_slice()).unwrap().is_err());
+    }
+
+    #[test]
+    fn parse_format_flag() {
+        let args = vec!("progname".to_string(),
+                        "--format=json".to_string());
+        let opts = match parse_opts(args.as_slice()) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_format_flag")
+        };
+        assert_eq!(opts.format, JsonFormat);
+
+        let args = vec!("progname".to_string(),
+                        "--format=xml".to_string());
+        assert!(parse_opts(args.as_slice()).unwrap().is_err());
+    }
+
     #[test]
     pub fn filter_for_ignored_option() {
         // When we run ignored tests the test filter should filter out all the
@@ -1608,6 +2445,7 @@ mod tests {
                     name: StaticTestName("1"),
                     ignore: true,
                     should_fail: ShouldFail::No,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Thunk::new(move|| {})),
             },
@@ -1616,6 +2454,7 @@ mod tests {
                     name: StaticTestName("2"),
                     ignore: false,
                     should_fail: ShouldFail::No,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Thunk::new(move|| {})),
             });
@@ -1652,6 +2491,7 @@ mod tests {
                         name: DynTestName((*name).clone()),
                         ignore: false,
                         should_fail: ShouldFail::No,
+                        timeout: None,
                     },
                     testfn: DynTestFn(Thunk::new(testfn)),
                 };
@@ -1680,7 +2520,7 @@ mod tests {
     #[test]
     pub fn filter_tests_regex() {
         let mut opts = TestOpts::new();
-        opts.filter = Some(::regex::Regex::new("a.*b.+c").unwrap());
+        opts.filters = vec!("a.*b.+c".to_string());
 
         let mut names = ["yes::abXc", "yes::aXXXbXXXXc",
                          "no::XYZ", "no::abc"];
@@ -1693,6 +2533,7 @@ mod tests {
                     name: DynTestName(name.to_string()),
                     ignore: false,
                     should_fail: ShouldFail::No,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Thunk::new(test_fn))
             }
@@ -1708,6 +2549,109 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn shuffle_tests_with_seed() {
+        fn test_fn() {}
+        let tests = |&:| {
+            range(0u, 20).map(|i| {
+                TestDescAndFn {
+                    desc: TestDesc {
+                        name: DynTestName(format!("test{}", i)),
+                        ignore: false,
+                        should_fail: ShouldFail::No,
+                        timeout: None,
+                    },
+                    testfn: DynTestFn(Thunk::new(test_fn))
+                }
+            }).collect::<Vec<_>>()
+        };
+        let names = |&: tests: Vec<TestDescAndFn>| {
+            tests.into_iter().map(|t| t.desc.name.to_string()).collect::<Vec<_>>()
+        };
+
+        let mut a = tests();
+        let mut b = tests();
+        let mut c = tests();
+        shuffle_tests(42, a.as_mut_slice());
+        shuffle_tests(42, b.as_mut_slice());
+        shuffle_tests(43, c.as_mut_slice());
+        let (a, b, c) = (names(a), names(b), names(c));
+        assert_eq!(a, b);
+        assert!(a != c);
+
+        let mut sorted = a.clone();
+        sorted.sort();
+        let mut expected = names(tests());
+        expected.sort();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn parse_shuffle_seed_flag() {
+        let args = vec!("progname".to_string(),
+                        "--shuffle-seed=1234".to_string());
+        let opts = match parse_opts(args.as_slice()) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_shuffle_seed_flag")
+        };
+        assert!(opts.shuffle);
+        assert_eq!(opts.shuffle_seed, Some(1234));
+    }
+
+    #[test]
+    pub fn filter_tests_exact_and_skip() {
+        let names = ["a::b", "a::b_long", "a::c", "x::b", "x::b::y"];
+        let tests = |&:| {
+            fn test_fn() {}
+            names.iter().map(|name| {
+                TestDescAndFn {
+                    desc: TestDesc {
+                        name: DynTestName(name.to_string()),
+                        ignore: false,
+                        should_fail: ShouldFail::No,
+                        timeout: None,
+                    },
+                    testfn: DynTestFn(Thunk::new(test_fn))
+                }
+            }).collect::<Vec<_>>()
+        };
+        let filtered_names = |&: opts: &TestOpts| {
+            filter_tests(opts, tests()).into_iter()
+                                       .map(|t| t.desc.name.to_string())
+                                       .collect::<Vec<_>>()
+        };
+
+        let mut opts = TestOpts::new();
+        opts.filters = vec!("a::b".to_string(), "x::b".to_string());
+        assert_eq!(filtered_names(&opts).len(), 4);
+
+        opts.filter_exact = true;
+        assert_eq!(filtered_names(&opts),
+                   vec!("a::b".to_string(), "x::b".to_string()));
+
+        opts.filter_exact = false;
+        opts.filters = Vec::new();
+        opts.skip = vec!("::b".to_string());
+        assert_eq!(filtered_names(&opts), vec!("a::c".to_string()));
+
+        opts.skip = vec!("^a::b$".to_string(), "y$".to_string());
+        assert_eq!(filtered_names(&opts),
+                   vec!("a::b_long".to_string(), "a::c".to_string(), "x::b".to_string()));
+
+        opts.filter_exact = true;
+        opts.skip = vec!("a::b".to_string(), "x::b::y".to_string());
+        assert_eq!(filtered_names(&opts),
+                   vec!("a::b_long".to_string(), "a::c".to_string(), "x::b".to_string()));
+    }
+
+    #[test]
+    #[should_fail(expected = "could not parse /a(/")]
+    pub fn filter_tests_rejects_malformed_patterns() {
+        let mut opts = TestOpts::new();
+        opts.filters = vec!("a(".to_string());
+        filter_tests(&opts, Vec::new());
+    }
+
     #[test]
     pub fn test_metricmap_compare() {
         let mut m1 = MetricMap::new();
@@ -10,6 +10,7 @@
 
 //! Implementation of the `build` subcommand, used to compile a book.
 
+use std::collections::HashSet;
 use std::os;
 use std::io;
 use std::io::{fs, File, BufferedWriter, TempDir, IoResult};
@@ -72,10 +73,23 @@ fn write_toc(book: &Book, path_to_root: &Path, out: &mut Writer) -> IoResult<()>
     Ok(())
 }
 
-fn render(book: &Book, tgt: &Path) -> CliResult<()> {
+/// Renders `book`, whose sources live in `src`, into `tgt`.
+///
+/// If `only` is given, just the items whose source paths it contains are
+/// rendered; the rest of `tgt` is left untouched. `extra_html` is appended
+/// to the content of every rendered page.
+pub fn render(book: &Book,
+              src: &Path,
+              tgt: &Path,
+              only: Option<&HashSet<Path>>,
+              extra_html: Option<&str>) -> CliResult<()> {
     let tmp = try!(TempDir::new("rust-book"));
 
     for (section, item) in book.iter() {
+        if let Some(only) = only {
+            if !only.contains(&item.path) { continue }
+        }
+
         println!("{} {}", section, item.title);
 
         let out_path = tgt.join(item.path.dirname());
@@ -83,12 +97,6 @@ fn render(book: &Book, tgt: &Path) -> CliResult<()> {
         let regex = r"\[(?P<title>[^]]*)\]\((?P<url_stem>[^)]*)\.(?P<ext>md|markdown)\)";
         let md_urls = Regex::new(regex).unwrap();
 
-        let src;
-        if os::args().len() < 3 {
-            src = os::getcwd().unwrap().clone();
-        } else {
-            src = Path::new(os::args()[2].clone());
-        }
         // preprocess the markdown, rerouting markdown references to html references
         let markdown_data = try!(File::open(&src.join(&item.path)).read_to_string());
         let preprocessed_path = tmp.path().join(item.path.filename().unwrap());
@@ -112,6 +120,9 @@ fn render(book: &Book, tgt: &Path) -> CliResult<()> {
         {
             let mut toc = BufferedWriter::new(try!(File::create(&postlude)));
             try!(writeln!(&mut toc, "</div></div>"));
+            if let Some(extra_html) = extra_html {
+                try!(writeln!(&mut toc, "{}", extra_html));
+            }
         }
 
         try!(fs::mkdir_recursive(&out_path, io::USER_DIR));
@@ -138,6 +149,22 @@ fn render(book: &Book, tgt: &Path) -> CliResult<()> {
     Ok(())
 }
 
+/// Parses the `SUMMARY.md` in `src`, reporting any errors to `term`.
+pub fn parse_book(src: &Path, term: &mut Term) -> CommandResult<Book> {
+    let summary = try!(File::open(&src.join("SUMMARY.md")));
+    match book::parse_summary(summary, src) {
+        Ok(book) => Ok(book),
+        Err(errors) => {
+            let n = errors.len();
+            for err in errors.into_iter() {
+                term.err(&format!("error: {}", err)[]);
+            }
+
+            Err(box format!("{} errors occurred", n) as Box<Error>)
+        }
+    }
+}
+
 impl Subcommand for Build {
     fn parse_args(&mut self, _: &[String]) -> CliResult<()> {
         Ok(())
@@ -164,20 +191,8 @@ impl Subcommand for Build {
 
         try!(File::create(&tgt.join("rust-book.css")).write_str(css::STYLE));
 
-        let summary = try!(File::open(&src.join("SUMMARY.md")));
-        match book::parse_summary(summary, &src) {
-            Ok(book) => {
-                // execute rustdoc on the whole book
-                render(&book, &tgt)
-            }
-            Err(errors) => {
-                let n = errors.len();
-                for err in errors.into_iter() {
-                    term.err(&format!("error: {}", err)[]);
-                }
-
-                Err(box format!("{} errors occurred", n) as Box<Error>)
-            }
-        }
+        // execute rustdoc on the whole book
+        let book = try!(parse_book(&src, term));
+        render(&book, &src, &tgt, None, None)
     }
 }
@@ -41,6 +41,6 @@ pub fn usage() {
     println!("The <command> must be one of:");
     println!("  help    Print this message.");
     println!("  build   Build the book in subdirectory _book");
-    println!("  serve   --NOT YET IMPLEMENTED--");
+    println!("  serve   Serve the book on a local port, rebuilding it on changes");
     println!("  test    --NOT YET IMPLEMENTED--");
 }
@@ -8,29 +8,256 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! Implementation of the `serve` subcommand. Just a stub for now.
+//! Implementation of the `serve` subcommand. Builds the book into a temporary
+//! directory, serves it over HTTP on a local port, and rebuilds the pages
+//! whose sources change. Served pages poll the server and reload themselves
+//! after a rebuild.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{self, fs, File, TempDir, IoResult};
+use std::io::{TcpListener, TcpStream, Listener, Acceptor, BufferedStream};
+use std::io::timer;
+use std::os;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::Thread;
+use std::time::Duration;
 
 use subcommand::Subcommand;
-use error::CliResult;
-use error::CommandResult;
+use error::{Error, CliResult, CommandResult};
 use term::Term;
+use book::Book;
+use build;
+use css;
+
+const DEFAULT_PORT: u16 = 8000;
+
+/// The URL served pages poll to find out whether the book has been rebuilt.
+const GENERATION_URL: &'static str = "/__rustbook/generation";
+
+/// How often the sources are checked for changes, in milliseconds.
+const POLL_INTERVAL: i64 = 500;
+
+/// Appended to every page; reloads the page once the book has been rebuilt.
+fn reload_script() -> String {
+    format!(r#"<script type="text/javascript">
+(function() {{
+    var generation = null;
+    function poll() {{
+        var xhr = new XMLHttpRequest();
+        xhr.onreadystatechange = function() {{
+            if (xhr.readyState != 4) {{ return; }}
+            if (xhr.status == 200) {{
+                if (generation !== null && generation != xhr.responseText) {{
+                    window.location.reload();
+                    return;
+                }}
+                generation = xhr.responseText;
+            }}
+            setTimeout(poll, 1000);
+        }};
+        xhr.open("GET", "{url}", true);
+        xhr.send();
+    }}
+    poll();
+}})();
+</script>"#, url = GENERATION_URL)
+}
 
-struct Serve;
+struct Serve {
+    src: Option<Path>,
+    port: u16,
+}
 
 pub fn parse_cmd(name: &str) -> Option<Box<Subcommand>> {
     if name == "serve" {
-        Some(box Serve as Box<Subcommand>)
+        Some(box Serve { src: None, port: DEFAULT_PORT } as Box<Subcommand>)
     } else {
         None
     }
 }
 
 impl Subcommand for Serve {
-    fn parse_args(&mut self, _: &[String]) -> CliResult<()> {
+    fn parse_args(&mut self, args: &[String]) -> CliResult<()> {
+        // The first argument is the name of the subcommand itself.
+        let mut args = args.iter().skip(1);
+        while let Some(arg) = args.next() {
+            if &arg[] == "--port" || &arg[] == "-p" {
+                let port = args.next().and_then(|p| p.parse::<u16>());
+                match port {
+                    Some(port) => self.port = port,
+                    None => return Err(box "--port requires a port number" as Box<Error>),
+                }
+            } else if self.src.is_none() {
+                self.src = Some(Path::new(arg.clone()));
+            } else {
+                return Err(box format!("unexpected argument `{}`", arg) as Box<Error>);
+            }
+        }
         Ok(())
     }
-    fn usage(&self) {}
-    fn execute(&mut self, _: &mut Term) -> CommandResult<()> {
-        Ok(())
+    fn usage(&self) {
+        println!("Usage: rust-book serve [<src>] [--port <port>]");
+        println!("");
+        println!("Builds the book in <src> (the current directory by default) and");
+        println!("serves it on http://localhost:<port>/ (port {} by default),", DEFAULT_PORT);
+        println!("rebuilding it whenever its sources change.");
+    }
+    fn execute(&mut self, term: &mut Term) -> CommandResult<()> {
+        let src = match self.src {
+            Some(ref src) => src.clone(),
+            None => os::getcwd().unwrap(),
+        };
+        let tmp = try!(TempDir::new("rust-book-serve"));
+        let tgt = tmp.path().clone();
+
+        try!(File::create(&tgt.join("rust-book.css")).write_str(css::STYLE));
+        let mut book = try!(build::parse_book(&src, term));
+        let script = reload_script();
+        try!(build::render(&book, &src, &tgt, None, Some(&script[])));
+
+        let generation = Arc::new(AtomicUsize::new(0));
+        let listener = try!(TcpListener::bind(("127.0.0.1", self.port)));
+        let acceptor = try!(listener.listen());
+        {
+            let root = tgt.clone();
+            let generation = generation.clone();
+            Thread::spawn(move || serve(acceptor, root, generation));
+        }
+        println!("Serving the book on http://localhost:{}/", self.port);
+
+        let mut mtimes = modification_times(&book, &src);
+        loop {
+            timer::sleep(Duration::milliseconds(POLL_INTERVAL));
+
+            let new_mtimes = modification_times(&book, &src);
+            if new_mtimes == mtimes {
+                continue;
+            }
+
+            // A change to the summary can change the table of contents on
+            // every page, so everything has to be rebuilt. Otherwise only the
+            // chapters that changed are.
+            let summary = Path::new("SUMMARY.md");
+            let result = if new_mtimes.get(&summary) != mtimes.get(&summary) {
+                match build::parse_book(&src, term) {
+                    Ok(new_book) => {
+                        book = new_book;
+                        build::render(&book, &src, &tgt, None, Some(&script[]))
+                    }
+                    Err(e) => Err(e),
+                }
+            } else {
+                let changed = new_mtimes.iter()
+                                        .filter(|&(path, mtime)| mtimes.get(path) != Some(mtime))
+                                        .map(|(path, _)| path.clone())
+                                        .collect::<HashSet<Path>>();
+                build::render(&book, &src, &tgt, Some(&changed), Some(&script[]))
+            };
+
+            match result {
+                Ok(()) => { generation.fetch_add(1, Ordering::SeqCst); }
+                Err(err) => term.err(&format!("error: {}", err.description())[]),
+            }
+            mtimes = modification_times(&book, &src);
+        }
+    }
+}
+
+/// Collects the modification times of the summary and all chapters of `book`,
+/// keyed by their path relative to `src`. Files that can't be inspected (e.g.
+/// because they are in the middle of being saved) are left out.
+fn modification_times(book: &Book, src: &Path) -> HashMap<Path, u64> {
+    let mut paths = vec![Path::new("SUMMARY.md")];
+    paths.extend(book.iter().map(|(_, item)| item.path.clone()));
+    paths.into_iter().filter_map(|path| {
+        fs::stat(&src.join(&path)).ok().map(|stat| (path, stat.modified))
+    }).collect()
+}
+
+fn serve<A: Acceptor<TcpStream>>(mut acceptor: A, root: Path, generation: Arc<AtomicUsize>) {
+    for stream in acceptor.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        let root = root.clone();
+        let generation = generation.clone();
+        Thread::spawn(move || {
+            // Errors only affect this one client; there's nobody to report
+            // them to.
+            let _ = handle_request(stream, &root, &*generation);
+        });
+    }
+}
+
+fn handle_request(stream: TcpStream, root: &Path, generation: &AtomicUsize) -> IoResult<()> {
+    let mut stream = BufferedStream::new(stream);
+    let request = try!(stream.read_line());
+    // We don't care about any of the headers.
+    loop {
+        let line = try!(stream.read_line());
+        if line.trim().is_empty() { break }
+    }
+
+    let mut parts = request.words();
+    let (method, url) = match (parts.next(), parts.next()) {
+        (Some(method), Some(url)) => (method, url),
+        _ => return respond(&mut stream, "400 Bad Request", "text/plain", b"bad request"),
+    };
+    if method != "GET" {
+        return respond(&mut stream, "405 Method Not Allowed", "text/plain",
+                       b"method not allowed");
+    }
+
+    let url = url.split(|c: char| c == '?' || c == '#').next().unwrap();
+    if url == GENERATION_URL {
+        let generation = generation.load(Ordering::SeqCst).to_string();
+        return respond(&mut stream, "200 OK", "text/plain", generation.as_bytes());
+    }
+
+    // Don't let requests escape the book.
+    if url.split('/').any(|component| component == "..") {
+        return respond(&mut stream, "403 Forbidden", "text/plain", b"forbidden");
+    }
+    let mut path = Path::new(url.trim_left_matches('/'));
+    if url.ends_with("/") {
+        path.push("index.html");
+    }
+
+    match File::open(&root.join(&path)).read_to_end() {
+        Ok(contents) => {
+            respond(&mut stream, "200 OK", content_type(&path), &contents[])
+        }
+        Err(ref e) if e.kind == io::FileNotFound => {
+            respond(&mut stream, "404 Not Found", "text/plain", b"not found")
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn respond(stream: &mut BufferedStream<TcpStream>,
+           status: &str,
+           content_type: &str,
+           body: &[u8]) -> IoResult<()> {
+    try!(write!(stream, "HTTP/1.0 {}\r\n", status));
+    try!(write!(stream, "Content-Type: {}\r\n", content_type));
+    try!(write!(stream, "Content-Length: {}\r\n", body.len()));
+    try!(write!(stream, "Cache-Control: no-cache\r\n"));
+    try!(write!(stream, "Connection: close\r\n\r\n"));
+    try!(stream.write(body));
+    stream.flush()
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension_str() {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("svg") => "image/svg+xml",
+        Some("woff") => "application/font-woff",
+        _ => "application/octet-stream",
     }
 }
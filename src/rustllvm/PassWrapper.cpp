@@ -19,6 +19,17 @@
 
 #include "llvm-c/Transforms/PassManagerBuilder.h"
 
+#if LLVM_VERSION_MINOR >= 6
+#include "llvm/ADT/SmallPtrSet.h"
+#include "llvm/ADT/StringSet.h"
+#include "llvm/IR/CallSite.h"
+#include "llvm/IR/Intrinsics.h"
+#include "llvm/IR/MDBuilder.h"
+#include "llvm/ProfileData/InstrProfReader.h"
+#include "llvm/Transforms/Utils/Cloning.h"
+#include "llvm/Transforms/Utils/ModuleUtils.h"
+#endif
+
 using namespace llvm;
 
 extern cl::opt<bool> EnableARMEHABI;
@@ -290,3 +301,439 @@ LLVMRustMarkAllFunctionsNounwind(LLVMModuleRef M) {
         }
     }
 }
+
+// Support for profile-guided optimization. Each function counts how often it
+// is entered, and how often each edge out of its conditional branches and
+// switches is taken: the entry counts tell the inliner which functions are
+// hot and the code generator which ones are never run, the edge counts
+// become the branch weights block placement and the like are guided by.
+//
+// Instrumented modules count through `llvm.instrprof.increment`, which the
+// `instrprof` pass later lowers into the counters and registration code the
+// profiling runtime from compiler-rt expects. The runtime writes the counters
+// out when the program exits; `llvm-profdata merge` turns those files into
+// the indexed profile read by `LLVMRustApplyProfileData`. Both work on the
+// unoptimized module, so the counters of a function are found in the same
+// order when the profile is applied as when it was written.
+#if LLVM_VERSION_MINOR >= 6
+
+static GlobalValue::LinkageTypes
+profileNameLinkage(const Function &F) {
+    // Match the function's linkage, except where that would make the name
+    // visible outside of the module for no reason.
+    switch (F.getLinkage()) {
+    case GlobalValue::ExternalWeakLinkage:
+        return GlobalValue::LinkOnceAnyLinkage;
+    case GlobalValue::AvailableExternallyLinkage:
+        return GlobalValue::LinkOnceODRLinkage;
+    case GlobalValue::ExternalLinkage:
+    case GlobalValue::InternalLinkage:
+        return GlobalValue::PrivateLinkage;
+    default:
+        return F.getLinkage();
+    }
+}
+
+// The blocks of `F` whose outgoing edges are counted, in order. Edges out of
+// an invoke are left alone, as the one to the landing pad can't be split.
+static std::vector<BasicBlock*>
+profileBranches(Function &F) {
+    std::vector<BasicBlock*> Branches;
+    for (Function::iterator BB = F.begin(), E = F.end(); BB != E; ++BB) {
+        TerminatorInst *T = BB->getTerminator();
+        if (T->getNumSuccessors() > 1 && (isa<BranchInst>(T) || isa<SwitchInst>(T)))
+            Branches.push_back(&*BB);
+    }
+    return Branches;
+}
+
+// The number of counters of a function with the given `Branches`: one for
+// its entry, then one for each edge out of each branch. `Hash` is set to a
+// hash of the branches' shape, which keeps the counts of a function from
+// being applied after it has changed.
+static uint32_t
+profileCounters(const std::vector<BasicBlock*> &Branches, uint64_t &Hash) {
+    uint32_t Counters = 1;
+    Hash = 0;
+    for (size_t i = 0; i < Branches.size(); ++i) {
+        unsigned Edges = Branches[i]->getTerminator()->getNumSuccessors();
+        Counters += Edges;
+        Hash = Hash * 31 + Edges;
+    }
+    return Counters;
+}
+
+extern "C" bool
+LLVMRustInsertProfileCounters(LLVMModuleRef M, const char *Filename) {
+    Module *Mod = unwrap(M);
+    LLVMContext &Ctx = Mod->getContext();
+    Function *Increment = Intrinsic::getDeclaration(Mod, Intrinsic::instrprof_increment);
+
+    for (Module::iterator F = Mod->begin(), E = Mod->end(); F != E; ++F) {
+        if (F->isDeclaration())
+            continue;
+
+        std::vector<BasicBlock*> Branches = profileBranches(*F);
+        uint64_t Hash;
+        uint32_t NumCounters = profileCounters(Branches, Hash);
+
+        Constant *Name = ConstantDataArray::getString(Ctx, F->getName(), false);
+        GlobalVariable *NameVar =
+            new GlobalVariable(*Mod, Name->getType(), true, profileNameLinkage(*F),
+                               Name, "__llvm_profile_name_" + F->getName());
+        Constant *NamePtr = ConstantExpr::getBitCast(NameVar, Type::getInt8PtrTy(Ctx));
+
+        IRBuilder<> Builder(F->getEntryBlock().getFirstInsertionPt());
+        Builder.CreateCall4(Increment, NamePtr, Builder.getInt64(Hash),
+                            Builder.getInt32(NumCounters), Builder.getInt32(0));
+
+        // Each edge is counted in a block of its own, split off between the
+        // branch and its successor.
+        uint32_t Index = 1;
+        for (size_t i = 0; i < Branches.size(); ++i) {
+            BasicBlock *BB = Branches[i];
+            TerminatorInst *T = BB->getTerminator();
+            for (unsigned s = 0; s < T->getNumSuccessors(); ++s) {
+                BasicBlock *Succ = T->getSuccessor(s);
+                BasicBlock *Edge = BasicBlock::Create(Ctx, "", &*F, Succ);
+                IRBuilder<> EdgeBuilder(Edge);
+                EdgeBuilder.CreateCall4(Increment, NamePtr, EdgeBuilder.getInt64(Hash),
+                                        EdgeBuilder.getInt32(NumCounters),
+                                        EdgeBuilder.getInt32(Index++));
+                EdgeBuilder.CreateBr(Succ);
+                T->setSuccessor(s, Edge);
+
+                // A switch may have several edges to the same block, each
+                // with its own entry in the block's phis; move one of them.
+                for (BasicBlock::iterator I = Succ->begin(); isa<PHINode>(I); ++I) {
+                    PHINode *PN = cast<PHINode>(I);
+                    PN->setIncomingBlock(PN->getBasicBlockIndex(BB), Edge);
+                }
+            }
+        }
+    }
+
+    // The program's entry point is in exactly one module; that one tells the
+    // runtime where to write the profile to.
+    Function *Main = Mod->getFunction("main");
+    if (Main && !Main->isDeclaration()) {
+        Type *VoidTy = Type::getVoidTy(Ctx);
+        Type *Int8PtrTy = Type::getInt8PtrTy(Ctx);
+        Constant *SetFilename = Mod->getOrInsertFunction(
+            "__llvm_profile_override_default_filename", VoidTy, Int8PtrTy, NULL);
+
+        Function *Init = Function::Create(FunctionType::get(VoidTy, false),
+                                          GlobalValue::InternalLinkage,
+                                          "rust.profile.init", Mod);
+        IRBuilder<> Builder(BasicBlock::Create(Ctx, "", Init));
+        Builder.CreateCall(SetFilename, Builder.CreateGlobalStringPtr(Filename));
+        Builder.CreateRetVoid();
+        appendToGlobalCtors(*Mod, Init, 0);
+    }
+    return true;
+}
+
+extern "C" bool
+LLVMRustApplyProfileData(LLVMModuleRef M, const char *Path) {
+    std::unique_ptr<IndexedInstrProfReader> Reader;
+    if (std::error_code EC = IndexedInstrProfReader::create(Path, Reader)) {
+        LLVMRustSetLastError(EC.message().c_str());
+        return false;
+    }
+
+    Module *Mod = unwrap(M);
+    MDBuilder MDB(Mod->getContext());
+    std::vector<std::pair<Function*, uint64_t> > Entries;
+    uint64_t MaxEntries = 0;
+    for (Module::iterator F = Mod->begin(), E = Mod->end(); F != E; ++F) {
+        if (F->isDeclaration())
+            continue;
+
+        // Functions the profile doesn't know about, or whose branches have
+        // changed since it was written, are left alone.
+        std::vector<BasicBlock*> Branches = profileBranches(*F);
+        uint64_t Hash;
+        uint32_t NumCounters = profileCounters(Branches, Hash);
+        std::vector<uint64_t> Counts;
+        if (Reader->getFunctionCounts(F->getName(), Hash, Counts) ||
+            Counts.size() != NumCounters)
+            continue;
+        Entries.push_back(std::make_pair(&*F, Counts[0]));
+        MaxEntries = std::max(MaxEntries, Counts[0]);
+
+        size_t Index = 1;
+        for (size_t i = 0; i < Branches.size(); ++i) {
+            TerminatorInst *T = Branches[i]->getTerminator();
+            unsigned Edges = T->getNumSuccessors();
+            uint64_t MaxCount = 0;
+            for (unsigned s = 0; s < Edges; ++s)
+                MaxCount = std::max(MaxCount, Counts[Index + s]);
+
+            // Branches never reached say nothing about their edges. Weights
+            // are only 32 bits wide, and are kept above zero so that no edge
+            // is taken to be impossible.
+            if (MaxCount > 0) {
+                uint64_t Scale = MaxCount / UINT32_MAX + 1;
+                SmallVector<uint32_t, 4> Weights;
+                for (unsigned s = 0; s < Edges; ++s)
+                    Weights.push_back(Counts[Index + s] / Scale + 1);
+                T->setMetadata(LLVMContext::MD_prof, MDB.createBranchWeights(Weights));
+            }
+            Index += Edges;
+        }
+    }
+
+    // Functions entered at least a hundredth as often as the hottest one are
+    // worth inlining.
+    uint64_t HotThreshold = std::max<uint64_t>(MaxEntries / 100, 1);
+    for (size_t i = 0; i < Entries.size(); ++i) {
+        Function *F = Entries[i].first;
+        if (Entries[i].second == 0) {
+            F->addFnAttr(Attribute::Cold);
+        } else if (Entries[i].second >= HotThreshold &&
+                   !F->hasFnAttribute(Attribute::NoInline)) {
+            F->addFnAttr(Attribute::InlineHint);
+        }
+    }
+    return true;
+}
+
+#else
+
+extern "C" bool
+LLVMRustInsertProfileCounters(LLVMModuleRef M, const char *Filename) {
+    LLVMRustSetLastError("profile-guided optimization requires LLVM 3.6 or later");
+    return false;
+}
+
+extern "C" bool
+LLVMRustApplyProfileData(LLVMModuleRef M, const char *Path) {
+    LLVMRustSetLastError("profile-guided optimization requires LLVM 3.6 or later");
+    return false;
+}
+
+#endif
+
+// Support for summary-based LTO. Every module importable from is summarized
+// up front: for each function it defines and exports, how large it is,
+// whether it can be imported at all, and which functions it calls. The
+// chosen functions are then copied into each importing module as
+// `available_externally` definitions, which the inliner may use but which
+// are never emitted, so that modules can be optimized on their own (and in
+// parallel) while still inlining across module boundaries.
+#if LLVM_VERSION_MINOR >= 6
+
+// Whether `V`, used by a function about to be imported, can be referred to
+// from another module. Local functions and mutable globals can't be shared,
+// while local constants are simply imported along with the function.
+static bool
+isImportable(const Value *V, SmallPtrSet<const Value*, 16> &Visited) {
+    if (!Visited.insert(V).second)
+        return true;
+    if (const Function *F = dyn_cast<Function>(V))
+        return !F->hasLocalLinkage();
+    if (const GlobalVariable *GV = dyn_cast<GlobalVariable>(V)) {
+        if (!GV->hasLocalLinkage())
+            return true;
+        return GV->isConstant() && GV->hasInitializer() &&
+               isImportable(GV->getInitializer(), Visited);
+    }
+    if (isa<GlobalAlias>(V))
+        return false;
+    if (const Constant *C = dyn_cast<Constant>(V)) {
+        for (User::const_op_iterator I = C->op_begin(), E = C->op_end(); I != E; ++I) {
+            if (!isImportable(*I, Visited))
+                return false;
+        }
+    }
+    return true;
+}
+
+static Module*
+parseBitcode(LLVMContext &Ctx, const char *BC, size_t Len) {
+    MemoryBufferRef Buf(StringRef(BC, Len), "<bitcode>");
+    ErrorOr<Module *> Mod = parseBitcodeFile(Buf, Ctx);
+    if (!Mod) {
+        LLVMRustSetLastError(Mod.getError().message().c_str());
+        return nullptr;
+    }
+    return *Mod;
+}
+
+// Parses the bitcode `BC` into a module of the context `C`, for
+// summarizing and extracting functions from it.
+extern "C" LLVMModuleRef
+LLVMRustParseBitcode(LLVMContextRef C, const char *BC, size_t Len) {
+    return wrap(parseBitcode(*unwrap(C), BC, Len));
+}
+
+// Writes the summary of the module `M` to `Str`, one line per exported
+// function: its name, its number of instructions, whether it can be imported,
+// whether it is hot, and the names of the functions it calls.
+extern "C" bool
+LLVMRustThinLTOSummarize(LLVMModuleRef M, RustStringRef Str) {
+    Module *Mod = unwrap(M);
+    raw_rust_string_ostream OS(Str);
+    for (Module::iterator F = Mod->begin(), E = Mod->end(); F != E; ++F) {
+        if (F->isDeclaration() || F->hasLocalLinkage() ||
+            F->hasAvailableExternallyLinkage())
+            continue;
+
+        unsigned Instrs = 0;
+        bool Importable = !F->hasFnAttribute(Attribute::NoInline);
+        SmallPtrSet<const Value*, 16> Visited;
+        std::vector<StringRef> Callees;
+        for (Function::iterator BB = F->begin(), BE = F->end(); BB != BE; ++BB) {
+            for (BasicBlock::iterator I = BB->begin(), IE = BB->end(); I != IE; ++I) {
+                ++Instrs;
+                for (User::op_iterator Op = I->op_begin(), OE = I->op_end(); Op != OE; ++Op) {
+                    if (Importable && !isImportable(*Op, Visited))
+                        Importable = false;
+                }
+                CallSite CS(&*I);
+                if (!CS)
+                    continue;
+                const Function *Callee = CS.getCalledFunction();
+                if (Callee && !Callee->isIntrinsic() && !Callee->hasLocalLinkage())
+                    Callees.push_back(Callee->getName());
+            }
+        }
+        bool Hot = F->hasFnAttribute(Attribute::InlineHint) ||
+                   F->hasFnAttribute(Attribute::AlwaysInline);
+
+        OS << F->getName() << " " << Instrs << " " << (Importable ? 1 : 0)
+           << " " << (Hot ? 1 : 0);
+        for (size_t i = 0; i < Callees.size(); ++i)
+            OS << " " << Callees[i];
+        OS << "\n";
+    }
+    return true;
+}
+
+// Strips `Src` down to `available_externally` definitions of the functions
+// `Imports`, along with whatever local definitions they need.
+static void
+stripForImport(Module &Src, const StringSet<> &Imports) {
+    // Debug info would refer to the compile units of the source module.
+    StripDebugInfo(Src);
+
+    // Aliases and the special `llvm.*` variables (global constructors and
+    // the like) belong to the source module alone.
+    while (!Src.alias_empty()) {
+        GlobalAlias *GA = &*Src.alias_begin();
+        GA->replaceAllUsesWith(GA->getAliasee());
+        GA->eraseFromParent();
+    }
+    for (Module::global_iterator GV = Src.global_begin(); GV != Src.global_end(); ) {
+        GlobalVariable *Var = &*GV++;
+        if (Var->getName().startswith("llvm.")) {
+            Var->eraseFromParent();
+        } else if (!Var->hasLocalLinkage() && !Var->isDeclaration()) {
+            Var->setInitializer(nullptr);
+            Var->setLinkage(GlobalValue::ExternalLinkage);
+        }
+    }
+    for (Module::iterator F = Src.begin(), E = Src.end(); F != E; ++F) {
+        if (F->isDeclaration() || F->hasLocalLinkage())
+            continue;
+        if (Imports.count(F->getName())) {
+            F->setLinkage(GlobalValue::AvailableExternallyLinkage);
+        } else {
+            F->deleteBody();
+        }
+    }
+
+    // Drop whatever local definitions nothing imported refers to.
+    bool Changed = true;
+    while (Changed) {
+        Changed = false;
+        for (Module::iterator F = Src.begin(); F != Src.end(); ) {
+            Function *Fn = &*F++;
+            if (Fn->hasLocalLinkage() && Fn->use_empty()) {
+                Fn->eraseFromParent();
+                Changed = true;
+            }
+        }
+        for (Module::global_iterator GV = Src.global_begin(); GV != Src.global_end(); ) {
+            GlobalVariable *Var = &*GV++;
+            if (Var->hasLocalLinkage() && Var->use_empty()) {
+                Var->eraseFromParent();
+                Changed = true;
+            }
+        }
+    }
+}
+
+// Writes the bitcode of a copy of `M` holding only the functions `Names`,
+// which is what the codegen units importing from `M` are given instead of
+// all of `M`. `M` itself is left as it is.
+extern "C" void
+LLVMRustThinLTOExtract(LLVMModuleRef M, const char **Names, size_t NumNames,
+                       RustStringRef Str) {
+    StringSet<> Imports;
+    for (size_t i = 0; i < NumNames; ++i)
+        Imports.insert(Names[i]);
+
+    std::unique_ptr<Module> Src(CloneModule(unwrap(M)));
+    stripForImport(*Src, Imports);
+
+    raw_rust_string_ostream OS(Str);
+    WriteBitcodeToFile(Src.get(), OS);
+}
+
+// Imports the functions `Names`, extracted by `LLVMRustThinLTOExtract` into
+// `BC`, into `M` as `available_externally` definitions. Everything else the
+// source module defines is left for the linker to find in the module's own
+// object file.
+extern "C" bool
+LLVMRustThinLTOImport(LLVMModuleRef M, const char *BC, size_t Len,
+                      const char **Names, size_t NumNames) {
+    Module *Dst = unwrap(M);
+    Module *Src = parseBitcode(Dst->getContext(), BC, Len);
+    if (!Src)
+        return false;
+
+    // `BC` holds what every codegen unit imports from the source module,
+    // so drop what this one doesn't.
+    StringSet<> Imports;
+    for (size_t i = 0; i < NumNames; ++i)
+        Imports.insert(Names[i]);
+    stripForImport(*Src, Imports);
+
+    std::string Err;
+    if (Linker::LinkModules(Dst, Src, Linker::DestroySource, &Err)) {
+        LLVMRustSetLastError(Err.c_str());
+        delete Src;
+        return false;
+    }
+    delete Src;
+    return true;
+}
+
+#else
+
+extern "C" LLVMModuleRef
+LLVMRustParseBitcode(LLVMContextRef C, const char *BC, size_t Len) {
+    LLVMRustSetLastError("summary-based LTO requires LLVM 3.6 or later");
+    return nullptr;
+}
+
+extern "C" bool
+LLVMRustThinLTOSummarize(LLVMModuleRef M, RustStringRef Str) {
+    LLVMRustSetLastError("summary-based LTO requires LLVM 3.6 or later");
+    return false;
+}
+
+extern "C" void
+LLVMRustThinLTOExtract(LLVMModuleRef M, const char **Names, size_t NumNames,
+                       RustStringRef Str) {
+}
+
+extern "C" bool
+LLVMRustThinLTOImport(LLVMModuleRef M, const char *BC, size_t Len,
+                      const char **Names, size_t NumNames) {
+    LLVMRustSetLastError("summary-based LTO requires LLVM 3.6 or later");
+    return false;
+}
+
+#endif
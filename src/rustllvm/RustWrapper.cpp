@@ -94,6 +94,11 @@ extern "C" void LLVMRustPrintPassTimings() {
   TimerGroup::printAll(OS);
 }
 
+extern "C" void LLVMRustPrintPassTimingsToString(RustStringRef str) {
+  raw_rust_string_ostream OS(str);
+  TimerGroup::printAll(OS);
+}
+
 extern "C" LLVMValueRef LLVMGetOrInsertFunction(LLVMModuleRef M,
                                                 const char* Name,
                                                 LLVMTypeRef FunctionTy) {
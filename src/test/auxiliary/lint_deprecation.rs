@@ -0,0 +1,35 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+
+#[deprecated(since = "1.2.0", note = "it is too slow")]
+pub fn deprecated() {}
+
+#[deprecated(note = "renamed", replacement = "lint_deprecation::new_name")]
+pub fn old_name() {}
+
+pub fn new_name() {}
+
+#[deprecated]
+pub struct DeprecatedStruct;
+
+#[deprecated(since = "1.0.0")]
+pub mod deprecated_mod {
+    pub fn inherited() {}
+}
+
+pub trait Trait {
+    #[deprecated(note = "use `other` instead")]
+    fn method(&self) {}
+    fn other(&self) {}
+}
+
+impl Trait for () {}
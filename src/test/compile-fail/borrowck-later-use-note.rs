@@ -0,0 +1,53 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that borrowck points out the use which keeps a conflicting borrow
+// alive, and suggests how to shorten borrows of method receivers.
+
+struct Stack {
+    items: Vec<uint>,
+}
+
+impl Stack {
+    fn top(&mut self) -> &mut uint { &mut self.items[0] }
+    fn push(&mut self, x: uint) { self.items.push(x) }
+    fn len(&self) -> uint { self.items.len() }
+}
+
+fn reference() {
+    let mut x = 1u;
+    let r = &mut x;
+    let y = &x; //~ ERROR cannot borrow `x` as immutable because it is also borrowed as mutable
+    *r += *y; //~ NOTE previous borrow is later used here
+}
+
+fn reborrow() {
+    let mut x = 1u;
+    let r = &mut x;
+    let rr = &mut *r;
+    let y = &x; //~ ERROR cannot borrow `x` as immutable because it is also borrowed as mutable
+    *rr = *y; //~ NOTE previous borrow is later used here
+}
+
+fn method_result() {
+    let mut s = Stack { items: vec![1] };
+    let top = s.top(); //~ HELP consider moving the uses of the result into a block
+    s.push(2); //~ ERROR cannot borrow `s` as mutable more than once at a time
+    *top = 3; //~ NOTE previous borrow is later used here
+}
+
+fn method_argument() {
+    let mut s = Stack { items: vec![1] };
+    s.push(s.len());
+    //~^ ERROR cannot borrow `s` as immutable because it is also borrowed as mutable
+    //~| HELP storing it in a temporary: `let tmp = s.len();`
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the arithmetic of constants is checked for overflow in the
+// type of the constant.
+
+const ADD: u8 = 200 + 100; //~ ERROR attempted to add with overflow
+const SUB: u32 = 0 - 1; //~ ERROR attempted to subtract with overflow
+const SUB_SIGNED: i8 = -128 - 1; //~ ERROR attempted to subtract with overflow
+const MUL: i16 = 200 * 200; //~ ERROR attempted to multiply with overflow
+const NEG: i8 = -(-128); //~ ERROR attempted to negate with overflow
+const SHL: u64 = 1 << 64; //~ ERROR attempted to shift left with overflow
+const DIV: i32 = 10 / (5 - 5); //~ ERROR attempted to divide by zero
+const REM: i8 = -128 % -1; //~ ERROR attempted remainder with overflow
+
+// The error points at the sub-expression that overflowed.
+const NESTED: usize = 1 +
+    (2 - 3); //~ ERROR attempted to subtract with overflow
+
+// Operations that wrap at run time are fine.
+const NOT: u8 = !0;
+const WRAPPING_SHL: i32 = 1 << 31;
+
+fn main() {}
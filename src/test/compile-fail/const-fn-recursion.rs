@@ -0,0 +1,25 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Constant functions can recurse, but not forever.
+
+#![feature(const_fn)]
+
+#[const_fn]
+fn forever(n: u32) -> u32 {
+    if n == 0 { 0 } else { forever(n + 1) }
+    //~^ ERROR reached the recursion limit while evaluating constant function calls
+}
+
+const FOREVER: u32 = forever(1);
+
+fn main() {
+    println!("{}", FOREVER);
+}
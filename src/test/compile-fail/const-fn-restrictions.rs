@@ -0,0 +1,37 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn)]
+#![allow(unused_variables)]
+
+#[const_fn] fn generic<T>(x: usize) -> usize { x } //~ ERROR cannot have type or lifetime parameters
+
+#[const_fn] fn float(x: f64) -> usize { 0 } //~ ERROR arguments of constant functions must be integers
+
+#[const_fn] fn pattern((a, b): (u8, u8)) -> u8 { a }
+//~^ ERROR arguments of constant functions must be plain bindings
+//~^^ ERROR arguments of constant functions must be integers
+
+#[const_fn] fn unit(x: u8) {} //~ ERROR constant functions must return an integer or `bool`
+//~^ ERROR must be a single expression
+
+#[const_fn] fn statements(x: usize) -> usize { //~ ERROR must be a single expression
+    let y = x;
+    y
+}
+
+#[const_fn] fn calls(x: usize) -> usize { x + not_const() }
+//~^ ERROR function calls in constants are limited
+
+fn not_const() -> usize { 1 }
+
+const C: usize = not_const(); //~ ERROR function calls in constants are limited
+
+fn main() {}
@@ -0,0 +1,20 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[deprecated(since = "1.0.0", reason = "bad")] //~ ERROR unknown key `reason`
+fn unknown_key() {}
+
+#[deprecated(note = "one", note = "two")] //~ ERROR `note` given more than once
+fn duplicate_key() {}
+
+#[deprecated(since)] //~ ERROR expected `since = "..."`
+fn missing_value() {}
+
+fn main() {}
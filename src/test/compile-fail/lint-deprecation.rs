@@ -0,0 +1,49 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:lint_deprecation.rs
+
+// Check that `#[deprecated(...)]` is honored, locally and across crates,
+// by crates which don't use the staged API.
+
+#![deny(deprecated)]
+#![allow(dead_code)]
+
+extern crate lint_deprecation;
+
+mod cross_crate {
+    use lint_deprecation::{self, Trait};
+
+    fn test() {
+        lint_deprecation::deprecated();
+        //~^ ERROR use of deprecated item (deprecated since 1.2.0): it is too slow
+        lint_deprecation::old_name();
+        //~^ ERROR use of deprecated item: renamed; use `lint_deprecation::new_name` instead
+        lint_deprecation::new_name();
+
+        let _ = lint_deprecation::DeprecatedStruct; //~ ERROR use of deprecated item
+        lint_deprecation::deprecated_mod::inherited();
+        //~^ ERROR use of deprecated item (deprecated since 1.0.0)
+
+        ().method(); //~ ERROR use of deprecated item: use `other` instead
+        ().other();
+    }
+}
+
+mod this_crate {
+    #[deprecated(since = "0.1.0", note = "don't")]
+    fn deprecated() {}
+
+    fn test() {
+        deprecated(); //~ ERROR use of deprecated item (deprecated since 0.1.0): don't
+    }
+}
+
+fn main() {}
@@ -0,0 +1,92 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(unconditional_recursion)]
+#![allow(dead_code)]
+
+use std::fmt;
+use std::ops::Add;
+
+fn foo() { //~ ERROR function cannot return without recurring
+    foo(); //~ NOTE recursive call site
+}
+
+fn bar(x: bool) { //~ ERROR function cannot return without recurring
+    if x {
+        bar(!x); //~ NOTE recursive call site
+    } else {
+        bar(x); //~ NOTE recursive call site
+    }
+}
+
+fn baz(x: usize) {
+    if x > 0 {
+        baz(x - 1);
+    }
+}
+
+fn diverges() -> ! {
+    loop {}
+}
+
+fn panics_first() {
+    panic!();
+}
+
+struct Foo;
+
+impl fmt::Show for Foo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { //~ ERROR function cannot return
+        self.fmt(f) //~ NOTE recursive call site
+    }
+}
+
+struct Bar;
+
+impl fmt::Show for Bar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { //~ ERROR function cannot return
+        fmt::Show::fmt(self, f) //~ NOTE recursive call site
+    }
+}
+
+struct Wrapper(Foo);
+
+impl fmt::Show for Wrapper {
+    // Formatting the field calls the impl of `Foo`, not this one.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Add for Foo {
+    type Output = Foo;
+    fn add(self, other: Foo) -> Foo { //~ ERROR function cannot return
+        self + other //~ NOTE recursive call site
+    }
+}
+
+impl Foo {
+    fn inherent(&self) { //~ ERROR function cannot return
+        Foo::inherent(self); //~ NOTE recursive call site
+    }
+}
+
+trait Trait {
+    fn default_method(&self) { //~ ERROR function cannot return
+        self.default_method(); //~ NOTE recursive call site
+    }
+
+    // Calls the method of another type.
+    fn forward<T: Trait>(&self, other: &T) {
+        other.forward(other);
+    }
+}
+
+fn main() {}
@@ -22,7 +22,7 @@ fn main() {
     match (2is, 3is, 4is) { //~ ERROR non-exhaustive patterns: `(_, _, _)` not covered
       (_, _, 4) => {}
     }
-    match (t::a, t::a) { //~ ERROR non-exhaustive patterns: `(a, a)` not covered
+    match (t::a, t::a) { //~ ERROR non-exhaustive patterns: `(a, a)` and `(b, b)` not covered
       (t::a, t::b) => {}
       (t::b, t::a) => {}
     }
@@ -37,7 +37,7 @@ fn main() {
     }
     let vec = vec!(Some(42is), None, Some(21is));
     let vec: &[Option<isize>] = vec.as_slice();
-    match vec { //~ ERROR non-exhaustive patterns: `[]` not covered
+    match vec { //~ ERROR non-exhaustive patterns: `[]`, `[Some(_)]`, `[None, _]` and 1 more not covered
         [Some(..), None, tail..] => {}
         [Some(..), Some(..), tail..] => {}
         [None] => {}
@@ -50,7 +50,7 @@ fn main() {
     }
     let vec = vec!(0.5f32);
     let vec: &[f32] = vec.as_slice();
-    match vec { //~ ERROR non-exhaustive patterns: `[_, _, _, _]` not covered
+    match vec { //~ ERROR non-exhaustive patterns: `[_]`, `[_, _]`, `[_, _, _]` and 1 more not covered
         [0.1, 0.2, 0.3] => (),
         [0.1, 0.2] => (),
         [0.1] => (),
@@ -33,7 +33,7 @@ fn struct_with_a_nested_enum_and_vector() {
 
 fn enum_with_multiple_missing_variants() {
     match Color::Red {
-    //~^ ERROR non-exhaustive patterns: `Red` not covered
+    //~^ ERROR non-exhaustive patterns: `Red` and `Green` not covered
         Color::CustomRGBA { .. } => ()
     }
 }
@@ -67,6 +67,28 @@ fn vectors_with_nested_enums() {
     }
 }
 
+enum Letter {
+    A, B, C, D, E, F
+}
+
+fn many_missing_variants() {
+    match Letter::A {
+    //~^ ERROR non-exhaustive patterns: `B`, `C`, `D` and 2 more not covered
+        Letter::A => ()
+    }
+}
+
+fn missing_nested_variants() {
+    match (Some(true), Letter::A) {
+    //~^ ERROR non-exhaustive patterns: `(None, _)`, `(Some(true), B)` and `(Some(false), _)` not covered
+        (Some(true), Letter::A) => (),
+        (Some(true), Letter::C) |
+        (Some(true), Letter::D) |
+        (Some(true), Letter::E) |
+        (Some(true), Letter::F) => ()
+    }
+}
+
 fn missing_nil() {
     match ((), false) {
     //~^ ERROR non-exhaustive patterns: `((), false)` not covered
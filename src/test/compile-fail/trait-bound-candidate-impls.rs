@@ -0,0 +1,57 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Unsatisfied trait bounds point out the impls for similar types, and the
+// impls whose requirements weren't met.
+
+trait Foo {
+    fn foo(&self) {}
+}
+
+struct Bar<T>(T);
+
+impl Foo for Bar<u32> {}
+
+struct Baz<T>(T);
+
+impl<T: Clone> Foo for Baz<T> {}
+
+struct NotClone;
+
+struct Qux;
+
+impl<'a> Foo for &'a Qux {}
+
+struct Quux;
+
+impl Foo for Box<Quux> {}
+
+fn needs_foo<T: Foo>(_: T) {}
+
+fn main() {
+    needs_foo(Bar(1i8));
+    //~^ ERROR the trait `Foo` is not implemented for the type `Bar<i8>`
+    //~| NOTE the trait `Foo` is implemented for the type `Bar<u32>`
+    //~| NOTE required by `needs_foo`
+
+    needs_foo(Qux);
+    //~^ ERROR the trait `Foo` is not implemented for the type `Qux`
+    //~| NOTE the trait `Foo` is implemented for the type `&'a Qux`
+    //~| NOTE required by `needs_foo`
+
+    needs_foo(Quux);
+    //~^ ERROR the trait `Foo` is not implemented for the type `Quux`
+    //~| NOTE the trait `Foo` is implemented for the type `Box<Quux>`
+    //~| NOTE required by `needs_foo`
+
+    needs_foo(Baz(NotClone));
+    //~^ ERROR the trait `core::clone::Clone` is not implemented for the type `NotClone`
+    //~| NOTE required because of the requirements on the impl of `Foo` for `Baz<NotClone>`
+}
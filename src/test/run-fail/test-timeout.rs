@@ -0,0 +1,25 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// check-stdout
+// error-pattern:test hangs ... TIMEOUT
+// compile-flags: --test
+// ignore-pretty: does not work well with `--test`
+
+use std::io::timer;
+use std::time::Duration;
+
+#[test]
+#[timeout = "1"]
+fn hangs() {
+    loop {
+        timer::sleep(Duration::seconds(60));
+    }
+}
@@ -0,0 +1,20 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn also_unused() {}
+
+// Not generated, so not under the levels given for generated/
+#[path = "../handwritten.rs"]
+mod handwritten;
+
+// Not generated either, and dead code only warned about in it
+#[warn(dead_code)]
+#[path = "../warned.rs"]
+mod warned;
@@ -0,0 +1,39 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+extern crate bar;
+
+pub mod inner {
+    pub struct Point {
+        pub x: int,
+        pub y: int,
+    }
+
+    // Generic functions are inlined into the crates using them.
+    pub fn largest<T: PartialOrd>(a: T, b: T) -> T {
+        if a > b { a } else { b }
+    }
+
+    fn hidden() {}
+}
+
+#[macro_export]
+macro_rules! square {
+    ($e:expr) => ($e * $e)
+}
+
+#[link(name = "m")]
+extern {}
+
+pub fn call_bar() {
+    bar::bar()
+}
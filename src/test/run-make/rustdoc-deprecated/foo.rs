@@ -0,0 +1,19 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[deprecated(since = "1.2.0", note = "it is too slow", replacement = "foo::fast")]
+pub fn slow() {}
+
+pub fn fast() {}
+
+#[deprecated(note = "no longer needed")]
+pub mod old {
+    pub fn inherited() {}
+}
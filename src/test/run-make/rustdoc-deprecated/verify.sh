@@ -0,0 +1,13 @@
+#!/bin/sh
+set -e
+
+# $1 is the TMPDIR
+DOC=$1/doc/foo
+
+grep "class='deprecated'>Deprecated since 1.2.0: it is too slow; use <code>foo::fast</code> instead" \
+    $DOC/fn.slow.html > /dev/null
+echo "slow"
+if grep "class='deprecated'" $DOC/fn.fast.html > /dev/null; then exit 1; fi
+echo "fast"
+grep "class='deprecated'>Deprecated: no longer needed" $DOC/old/fn.inherited.html > /dev/null
+echo "inherited"
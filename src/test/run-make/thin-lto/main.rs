@@ -0,0 +1,33 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate lib;
+
+use std::os;
+
+// Spread over several codegen units, which call each other.
+mod a {
+    pub fn quadruple(x: uint) -> uint {
+        ::lib::double(::lib::double(x))
+    }
+}
+
+mod b {
+    pub fn octuple(x: uint) -> uint {
+        ::lib::double(::a::quadruple(x))
+    }
+}
+
+fn main() {
+    let n = os::args().len();
+    assert_eq!(lib::double(n), n * 2);
+    assert_eq!(a::quadruple(n), n * 4);
+    assert_eq!(b::octuple(n), n * 8);
+}
@@ -0,0 +1,46 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn)]
+
+#[const_fn]
+fn square(x: usize) -> usize { x * x }
+
+#[const_fn]
+fn sum_of_squares(a: usize, b: usize) -> usize { square(a) + square(b) }
+
+#[const_fn]
+fn is_small(x: u8) -> bool { x < 16 }
+
+#[const_fn]
+fn factorial(n: u32) -> u32 {
+    if n == 0 { 1 } else { n * factorial(n - 1) }
+}
+
+const N: usize = sum_of_squares(2, 3);
+const SMALL: bool = is_small(200);
+const FACTORIAL: u32 = factorial(5);
+
+struct Buffer {
+    data: [u8; square(4)],
+}
+
+fn main() {
+    let b = Buffer { data: [0; square(4)] };
+    assert_eq!(b.data.len(), 16);
+    assert_eq!(N, 13);
+    assert!(!SMALL);
+    assert_eq!(FACTORIAL, 120);
+    assert_eq!([1u8; N].len(), 13);
+
+    // Constant functions can still be called at run time.
+    let x = 5;
+    assert_eq!(square(x), 25);
+}
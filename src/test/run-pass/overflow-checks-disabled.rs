@@ -0,0 +1,21 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C overflow-checks=no
+
+use std::num::Int;
+
+pub fn main() {
+    let x = 255u8;
+    assert_eq!(x + 1, 0);
+    let y: i32 = Int::min_value();
+    assert_eq!(-y, y);
+    assert_eq!(y - 1, Int::max_value());
+}
@@ -0,0 +1,46 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z sanitizer=address -g
+// needs-sanitizer-support
+
+// The child process reads memory after freeing it, which AddressSanitizer
+// reports before aborting the program.
+
+extern crate libc;
+
+use std::intrinsics::volatile_load;
+use std::io::process::Command;
+use std::os;
+
+fn use_after_free() -> u8 {
+    unsafe {
+        // Rust's own allocator isn't intercepted by the sanitizer, so the
+        // memory comes from the C library.
+        let p = libc::malloc(16) as *mut u8;
+        *p = 42;
+        libc::free(p as *mut libc::c_void);
+        volatile_load(p as *const u8)
+    }
+}
+
+fn main() {
+    let args = os::args();
+    let args = args.as_slice();
+    if args.len() > 1 && args[1].as_slice() == "child" {
+        println!("{}", use_after_free());
+    } else {
+        let child = Command::new(args[0].as_slice()).arg("child").output().unwrap();
+        assert!(!child.status.success());
+        let error = String::from_utf8_lossy(child.error.as_slice());
+        assert!(error.as_slice().contains("AddressSanitizer: heap-use-after-free"),
+                "unexpected output:\n\n{}", error);
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z sanitizer=thread -g
+// needs-sanitizer-support
+
+// The child process increments a static from two threads without any
+// synchronization, which ThreadSanitizer reports as a data race on that
+// static.
+
+use std::io::process::Command;
+use std::os;
+use std::thread::Thread;
+
+static mut COUNTER: uint = 0;
+
+fn race() {
+    let guard = Thread::scoped(|| unsafe { COUNTER += 1 });
+    unsafe { COUNTER += 1 }
+    guard.join().ok().unwrap();
+}
+
+fn main() {
+    let args = os::args();
+    let args = args.as_slice();
+    if args.len() > 1 && args[1].as_slice() == "child" {
+        race();
+    } else {
+        let child = Command::new(args[0].as_slice()).arg("child").output().unwrap();
+        let error = String::from_utf8_lossy(child.error.as_slice());
+        assert!(error.as_slice().contains("ThreadSanitizer: data race"),
+                "unexpected output:\n\n{}", error);
+        // The report names the static raced on, whether or not the
+        // symbolizer demangles it.
+        assert!(error.as_slice().contains("COUNTER"),
+                "the report doesn't name the static:\n\n{}", error);
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C overflow-checks
+
+// The wrapping methods never panic, even when arithmetic is checked.
+
+use std::num::Int;
+
+pub fn main() {
+    assert_eq!(255u8.wrapping_add(1), 0);
+    assert_eq!(0u32.wrapping_sub(1), 4294967295);
+    assert_eq!(127i8.wrapping_mul(2), -2);
+    let min: i64 = Int::min_value();
+    assert_eq!(min.wrapping_neg(), min);
+    assert_eq!(min.abs(), min);
+    assert_eq!(1u16.wrapping_shl(17), 2);
+    assert_eq!((-8i32).wrapping_shr(33), -4);
+
+    // Negated literals are in range.
+    assert_eq!(-128i8 as int, -128);
+}